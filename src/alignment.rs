@@ -0,0 +1,276 @@
+//! Source Alignment
+//!
+//! Maps each statement and block of an AISP document to the prose it was
+//! produced from, for audits. The deterministic alignment converts every
+//! prose sentence with [`RosettaStone`] on its own and matches the fragments
+//! to statements by the tokens they share; for LLM outputs the provider can
+//! quote the source of each statement instead (see
+//! [`crate::LlmProvider::align`]).
+
+use crate::ambiguity::sentence_spans;
+use crate::ast::{Span, StmtKind};
+use crate::formatter::normalize;
+use crate::lexer::{tokenize, TokenKind};
+use crate::parser::parse;
+use rosetta_aisp::RosettaStone;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Share of a sentence's converted tokens a statement must contain for the
+/// sentence to be one of its sources
+const CONTAINED: f64 = 0.8;
+
+/// Share of a statement's tokens the best matching sentence must produce
+/// when no sentence is contained in it
+const MIN_OVERLAP: f64 = 0.5;
+
+/// Symbols that carry no meaning of their own
+const PUNCTUATION: &[&str] = &["(", ")", "[", "]", "{", "}", "⟨", "⟩", ",", ".", ";", ":"];
+
+/// How a statement was aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlignmentOrigin {
+    /// Matched against the Rosetta conversion of each sentence
+    Rosetta,
+    /// Quoted by the LLM that wrote the statement
+    Llm,
+}
+
+/// Source of one AISP statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementAlignment {
+    /// Label of the enclosing block, e.g. `Λ:Funcs`; `None` outside blocks
+    pub block: Option<String>,
+    /// Byte range of the statement in the AISP
+    pub aisp: Span,
+    /// Byte ranges of the prose it came from, in prose order; empty when the
+    /// statement has no source, such as generated metadata
+    pub prose: Vec<Span>,
+    /// How the sources were found
+    pub origin: AlignmentOrigin,
+}
+
+/// Source of one AISP block: the union of its statements' sources
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAlignment {
+    /// Block label, e.g. `Λ:Funcs`
+    pub label: String,
+    /// Byte range of the block in the AISP
+    pub aisp: Span,
+    /// Byte ranges of the prose, in prose order
+    pub prose: Vec<Span>,
+}
+
+/// Mapping from an AISP document back to the prose it was converted from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alignment {
+    /// Every statement, in document order
+    pub statements: Vec<StatementAlignment>,
+    /// Every block, in document order
+    pub blocks: Vec<BlockAlignment>,
+}
+
+/// A statement and the prose it came from, quoted by an LLM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceQuote {
+    /// The statement as written in the AISP
+    pub statement: String,
+    /// Exact substrings of the prose
+    pub source: Vec<String>,
+}
+
+/// Align an AISP document with the prose it was converted from
+pub fn align(prose: &str, aisp: &str) -> Alignment {
+    let sentences: Vec<(Span, HashSet<String>)> = sentence_spans(prose)
+        .into_iter()
+        .map(|span| {
+            let (fragment, _, _) = RosettaStone::convert(span.text(prose));
+            (span, content_tokens(&fragment))
+        })
+        .collect();
+
+    let doc = parse(aisp).document;
+    let labeled = doc
+        .claims
+        .iter()
+        .map(|stmt| (None, stmt))
+        .chain(doc.blocks.iter().flat_map(|block| {
+            block
+                .body
+                .iter()
+                .map(move |stmt| (Some(label(&block.category, &block.name)), stmt))
+        }));
+
+    let mut statements = Vec::new();
+    for (block, stmt) in labeled {
+        if matches!(stmt.kind, StmtKind::Comment(_)) {
+            continue;
+        }
+        let tokens = content_tokens(stmt.span.text(aisp));
+        statements.push(StatementAlignment {
+            block,
+            aisp: stmt.span,
+            prose: sources(&tokens, &sentences),
+            origin: AlignmentOrigin::Rosetta,
+        });
+    }
+
+    let mut alignment = Alignment {
+        statements,
+        blocks: doc
+            .blocks
+            .iter()
+            .map(|block| BlockAlignment {
+                label: label(&block.category, &block.name),
+                aisp: block.span,
+                prose: Vec::new(),
+            })
+            .collect(),
+    };
+    alignment.collect_blocks();
+    alignment
+}
+
+impl Alignment {
+    /// Take the sources quoted by an LLM over the deterministic ones
+    ///
+    /// Quotes are matched to statements ignoring whitespace; quotes of text
+    /// that is not in the prose are dropped.
+    pub fn apply_quotes(&mut self, prose: &str, aisp: &str, quotes: &[SourceQuote]) {
+        for quote in quotes {
+            let wanted = normalize(&quote.statement, Span::new(0, quote.statement.len()), true);
+            let Some(statement) = self
+                .statements
+                .iter_mut()
+                .find(|s| normalize(aisp, s.aisp, true) == wanted)
+            else {
+                continue;
+            };
+            let mut spans: Vec<Span> = quote
+                .source
+                .iter()
+                .filter(|text| !text.trim().is_empty())
+                .filter_map(|text| {
+                    let text = text.trim();
+                    prose.find(text).map(|i| Span::new(i, i + text.len()))
+                })
+                .collect();
+            if spans.is_empty() {
+                continue;
+            }
+            spans.sort_by_key(|s| s.start);
+            spans.dedup();
+            statement.prose = spans;
+            statement.origin = AlignmentOrigin::Llm;
+        }
+        self.collect_blocks();
+    }
+
+    /// Statements without any prose source
+    pub fn unaligned(&self) -> impl Iterator<Item = &StatementAlignment> {
+        self.statements.iter().filter(|s| s.prose.is_empty())
+    }
+
+    fn collect_blocks(&mut self) {
+        for block in &mut self.blocks {
+            let mut spans: Vec<Span> = self
+                .statements
+                .iter()
+                .filter(|s| block.aisp.start <= s.aisp.start && s.aisp.end <= block.aisp.end)
+                .flat_map(|s| s.prose.iter().copied())
+                .collect();
+            spans.sort_by_key(|s| s.start);
+            spans.dedup();
+            block.prose = spans;
+        }
+    }
+}
+
+/// Sentences whose conversion `tokens` came from
+///
+/// A statement made of several sentences contains most of each one's
+/// tokens; otherwise the sentences that produced most of the statement are
+/// its source.
+fn sources(tokens: &HashSet<String>, sentences: &[(Span, HashSet<String>)]) -> Vec<Span> {
+    let shared = |produced: &HashSet<String>| tokens.intersection(produced).count() as f64;
+    let contained: Vec<Span> = sentences
+        .iter()
+        .filter(|(_, produced)| {
+            !produced.is_empty() && shared(produced) / produced.len() as f64 >= CONTAINED
+        })
+        .map(|(span, _)| *span)
+        .collect();
+    if !contained.is_empty() || tokens.is_empty() {
+        return contained;
+    }
+
+    let best = sentences
+        .iter()
+        .map(|(_, produced)| shared(produced) / tokens.len() as f64)
+        .fold(0.0, f64::max);
+    if best < MIN_OVERLAP {
+        return Vec::new();
+    }
+    sentences
+        .iter()
+        .filter(|(_, produced)| shared(produced) / tokens.len() as f64 == best)
+        .map(|(span, _)| *span)
+        .collect()
+}
+
+fn label(category: &str, name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!("{}:{}", category, name),
+        None => category.to_string(),
+    }
+}
+
+/// Lowercased tokens of `text` other than punctuation and comments
+fn content_tokens(text: &str) -> HashSet<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|t| match t.kind {
+            TokenKind::Comment | TokenKind::Newline => false,
+            TokenKind::Symbol => !PUNCTUATION.contains(&t.text),
+            _ => true,
+        })
+        .map(|t| t.text.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_statements_to_sentences() {
+        let prose = "Define x as 5. The weather is nice. Define y as x";
+        let aisp = "⟦Ω:Meta⟧{domain≜doc}\n⟦Λ:Funcs⟧{x≜5\ny≜x}";
+        let alignment = align(prose, aisp);
+
+        let sources: Vec<Vec<&str>> = alignment
+            .statements
+            .iter()
+            .map(|s| s.prose.iter().map(|p| p.text(prose)).collect())
+            .collect();
+        assert_eq!(
+            sources,
+            vec![vec![], vec!["Define x as 5"], vec!["Define y as x"]]
+        );
+        assert_eq!(alignment.statements[1].block.as_deref(), Some("Λ:Funcs"));
+        assert_eq!(alignment.unaligned().count(), 1);
+        assert_eq!(alignment.blocks[1].prose.len(), 2);
+        assert!(alignment.blocks[0].prose.is_empty());
+
+        let quotes = [SourceQuote {
+            statement: "y ≜ x".to_string(),
+            source: vec!["Define y as x".to_string(), "not in the prose".to_string()],
+        }];
+        let mut alignment = alignment;
+        alignment.apply_quotes(prose, aisp, &quotes);
+        let quoted = &alignment.statements[2];
+        assert_eq!(quoted.origin, AlignmentOrigin::Llm);
+        assert_eq!(quoted.prose, vec![Span::new(36, 49)]);
+    }
+}
@@ -0,0 +1,372 @@
+//! Ambiguity Estimation
+//!
+//! Estimates `Ambig≜λD.1-|Parse_u(D)|/|Parse_t(D)|` from the specification.
+//! Each unit of a text (a prose sentence, an AISP statement) has one intended
+//! reading; every ambiguity found in it multiplies the number of plausible
+//! readings. The score is one minus the share of readings that are intended,
+//! so a text without ambiguities scores 0.
+//!
+//! Prose is checked for open quantifier scope, pronouns with several
+//! candidate antecedents and `or` without an inclusive or exclusive marker.
+//! AISP is checked for operators whose relative precedence readers commonly
+//! misjudge, mixed without parentheses.
+
+use crate::ast::{Expr, ExprKind, Span};
+use crate::lint::{mixed_precedence, statements};
+use crate::parser::parse;
+use serde::{Deserialize, Serialize};
+
+/// Quantifiers ranging over every element
+const UNIVERSAL: &[&str] = &["every", "each", "all", "any"];
+
+/// Determiners introducing a single, possibly shared element
+const EXISTENTIAL: &[&str] = &["some", "a", "an"];
+
+/// Words after which the next word is taken to be a noun
+const DETERMINERS: &[&str] = &[
+    "the", "a", "an", "each", "every", "some", "any", "all", "no", "another",
+];
+
+/// Pronouns whose antecedent must be resolved
+const PRONOUNS: &[&str] = &[
+    "it", "its", "they", "them", "their", "he", "she", "him", "her", "his",
+];
+
+/// Words that settle whether `or` is inclusive or exclusive
+const DISJUNCTION_MARKERS: &[&str] = &["both", "exactly", "xor"];
+
+/// Sources of ambiguity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AmbiguityKind {
+    /// Two quantifiers whose relative scope is open: "every user has a role"
+    QuantifierScope,
+    /// A pronoun with more than one candidate antecedent
+    Pronoun,
+    /// `or` that may be inclusive or exclusive
+    Disjunction,
+    /// AISP operators mixed without parentheses, such as `a∧b∨c`
+    Precedence,
+}
+
+/// An ambiguous part of a text with its plausible readings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmbiguousSpan {
+    /// What makes the text ambiguous
+    pub kind: AmbiguityKind,
+    /// The ambiguous text
+    pub text: String,
+    /// Byte range in the input
+    pub span: Span,
+    /// Plausible readings; the first is the one a conversion assumes
+    pub readings: Vec<String>,
+}
+
+/// Estimated ambiguity of a text
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ambiguity {
+    /// `1-|Parse_u|/|Parse_t|` (0.0 - 1.0)
+    pub score: f64,
+    /// Every ambiguity found, in text order
+    pub spans: Vec<AmbiguousSpan>,
+}
+
+impl Ambiguity {
+    /// Score `units` units of text given the ambiguities found in each
+    fn from_units(units: Vec<Vec<AmbiguousSpan>>) -> Self {
+        let intended = units.len();
+        let total: usize = units
+            .iter()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .map(|s| s.readings.len().max(1))
+                    .product::<usize>()
+            })
+            .sum();
+        Self {
+            score: if total == 0 {
+                0.0
+            } else {
+                1.0 - intended as f64 / total as f64
+            },
+            spans: units.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Ambiguity of a conversion's input and output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AmbiguityReport {
+    /// Ambiguity of the prose
+    pub prose: Ambiguity,
+    /// Ambiguity of the AISP output
+    pub output: Ambiguity,
+}
+
+impl AmbiguityReport {
+    /// Estimate both sides of a conversion
+    pub fn compute(prose: &str, output: &str) -> Self {
+        Self {
+            prose: prose_ambiguity(prose),
+            output: aisp_ambiguity(output),
+        }
+    }
+}
+
+/// A word of prose with its byte range
+struct Word<'a> {
+    text: &'a str,
+    lower: String,
+    span: Span,
+}
+
+/// Sentences of `prose` as lists of words
+fn sentences(prose: &str) -> Vec<Vec<Word<'_>>> {
+    let mut sentences = vec![Vec::new()];
+    let mut start = None;
+
+    for (i, c) in prose.char_indices().chain([(prose.len(), ' ')]) {
+        if c.is_alphanumeric() || c == '_' || c == '\'' {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(s) = start.take() {
+            let text = &prose[s..i];
+            sentences.last_mut().expect("never empty").push(Word {
+                text,
+                lower: text.to_lowercase(),
+                span: Span::new(s, i),
+            });
+        }
+        if matches!(c, '.' | '!' | '?' | ';' | '\n') {
+            sentences.push(Vec::new());
+        }
+    }
+
+    sentences.retain(|words| !words.is_empty());
+    sentences
+}
+
+/// Byte ranges of the sentences of `prose`, from their first to last word
+pub(crate) fn sentence_spans(prose: &str) -> Vec<Span> {
+    sentences(prose)
+        .iter()
+        .map(|words| words[0].span.to(words[words.len() - 1].span))
+        .collect()
+}
+
+/// Estimate the ambiguity of prose
+pub fn prose_ambiguity(prose: &str) -> Ambiguity {
+    let sentences = sentences(prose);
+    let mut units = Vec::new();
+
+    for (i, words) in sentences.iter().enumerate() {
+        let previous = if i > 0 { &sentences[i - 1][..] } else { &[] };
+        let mut spans = Vec::new();
+        spans.extend(quantifier_scope(prose, words));
+        spans.extend(pronouns(prose, previous, words));
+        spans.extend(disjunctions(prose, words));
+        spans.sort_by_key(|s| s.span.start);
+        units.push(spans);
+    }
+
+    Ambiguity::from_units(units)
+}
+
+fn span_between(prose: &str, kind: AmbiguityKind, first: &Word, last: &Word) -> AmbiguousSpan {
+    let span = first.span.to(last.span);
+    AmbiguousSpan {
+        kind,
+        text: span.text(prose).to_string(),
+        span,
+        readings: Vec::new(),
+    }
+}
+
+/// The word after the determiner at `index`, if any
+fn noun_after<'a>(words: &'a [Word], index: usize) -> &'a str {
+    words.get(index + 1).map_or("", |w| w.text)
+}
+
+fn quantifier_scope(prose: &str, words: &[Word]) -> Option<AmbiguousSpan> {
+    let position = |set: &[&str]| words.iter().position(|w| set.contains(&w.lower.as_str()));
+    let (universal, existential) = (position(UNIVERSAL)?, position(EXISTENTIAL)?);
+    let (first, last) = (universal.min(existential), universal.max(existential) + 1);
+    let (every, one) = (noun_after(words, universal), noun_after(words, existential));
+
+    let mut span = span_between(
+        prose,
+        AmbiguityKind::QuantifierScope,
+        &words[first],
+        &words[last.min(words.len() - 1)],
+    );
+    span.readings = vec![
+        format!("a possibly different {} for each {}", one, every),
+        format!("one {} shared by every {}", one, every),
+    ];
+    Some(span)
+}
+
+fn pronouns(prose: &str, previous: &[Word], words: &[Word]) -> Vec<AmbiguousSpan> {
+    let mut spans = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if !PRONOUNS.contains(&word.lower.as_str()) {
+            continue;
+        }
+
+        // Nouns introduced by a determiner before the pronoun
+        let mut candidates: Vec<&str> = Vec::new();
+        for context in [previous, &words[..i]] {
+            for (j, w) in context.iter().enumerate() {
+                let Some(noun) = context.get(j + 1) else {
+                    continue;
+                };
+                let is_noun = !DETERMINERS.contains(&noun.lower.as_str())
+                    && !PRONOUNS.contains(&noun.lower.as_str());
+                if DETERMINERS.contains(&w.lower.as_str())
+                    && is_noun
+                    && !candidates.contains(&noun.text)
+                {
+                    candidates.push(noun.text);
+                }
+            }
+        }
+
+        if candidates.len() >= 2 {
+            // The nearest candidate is the likeliest antecedent
+            let mut span = span_between(prose, AmbiguityKind::Pronoun, word, word);
+            span.readings = candidates
+                .iter()
+                .rev()
+                .map(|c| format!("`{}` refers to {}", word.text, c))
+                .collect();
+            spans.push(span);
+        }
+    }
+    spans
+}
+
+fn disjunctions(prose: &str, words: &[Word]) -> Vec<AmbiguousSpan> {
+    if words
+        .iter()
+        .any(|w| DISJUNCTION_MARKERS.contains(&w.lower.as_str()))
+    {
+        return Vec::new();
+    }
+
+    let mut spans = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if word.lower != "or" || i == 0 || i + 1 == words.len() {
+            continue;
+        }
+        let (a, b) = (&words[i - 1], &words[i + 1]);
+        let mut span = span_between(prose, AmbiguityKind::Disjunction, a, b);
+        span.readings = vec![
+            format!("{}, {} or both", a.text, b.text),
+            format!("exactly one of {} and {}", a.text, b.text),
+        ];
+        spans.push(span);
+    }
+    spans
+}
+
+/// Estimate the ambiguity of an AISP document
+///
+/// Every statement is a unit; statements that fail to parse are skipped,
+/// since the validator already reports them.
+pub fn aisp_ambiguity(source: &str) -> Ambiguity {
+    let doc = parse(source).document;
+    let units = statements(&doc)
+        .filter(|stmt| !stmt.exprs().is_empty())
+        .map(|stmt| {
+            stmt.exprs()
+                .into_iter()
+                .flat_map(|expr| expr.walk())
+                .filter_map(|expr| precedence(source, expr))
+                .collect()
+        })
+        .collect();
+    Ambiguity::from_units(units)
+}
+
+fn precedence(source: &str, expr: &Expr) -> Option<AmbiguousSpan> {
+    let (inner, _) = mixed_precedence(expr)?;
+    let ExprKind::Binary { op, lhs, rhs } = &expr.kind else {
+        return None;
+    };
+
+    // The reading as parsed, then regrouped around the other operator
+    let text = |e: &Expr| e.span.text(source);
+    let readings = match (&lhs.kind, &rhs.kind) {
+        (
+            ExprKind::Binary {
+                op: left,
+                lhs: a,
+                rhs: b,
+            },
+            _,
+        ) if left == inner => vec![
+            format!("({}){}{}", text(lhs), op, text(rhs)),
+            format!("{}{}({}{}{})", text(a), inner, text(b), op, text(rhs)),
+        ],
+        (_, ExprKind::Binary { lhs: a, rhs: b, .. }) => vec![
+            format!("{}{}({})", text(lhs), op, text(rhs)),
+            format!("({}{}{}){}{}", text(lhs), op, text(a), inner, text(b)),
+        ],
+        _ => return None,
+    };
+
+    Some(AmbiguousSpan {
+        kind: AmbiguityKind::Precedence,
+        text: text(expr).to_string(),
+        span: expr.span,
+        readings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(ambiguity: &Ambiguity) -> Vec<AmbiguityKind> {
+        ambiguity.spans.iter().map(|s| s.kind).collect()
+    }
+
+    #[test]
+    fn test_prose_ambiguity() {
+        let clear = prose_ambiguity("Define x as 5. All users must log in.");
+        assert_eq!(clear.score, 0.0);
+        assert!(clear.spans.is_empty());
+
+        let scope = prose_ambiguity("Every user has a role.");
+        assert_eq!(kinds(&scope), vec![AmbiguityKind::QuantifierScope]);
+        assert_eq!(scope.spans[0].text, "Every user has a role");
+        assert_eq!(scope.score, 0.5);
+
+        let pronoun = prose_ambiguity("The admin approves the request. Then it is logged.");
+        assert_eq!(kinds(&pronoun), vec![AmbiguityKind::Pronoun]);
+        assert_eq!(pronoun.spans[0].readings[0], "`it` refers to request");
+        assert!((pronoun.score - 1.0 / 3.0).abs() < 1e-9);
+
+        let or = prose_ambiguity("Grant access to admins or owners");
+        assert_eq!(kinds(&or), vec![AmbiguityKind::Disjunction]);
+        assert_eq!(or.spans[0].text, "admins or owners");
+        assert!(
+            prose_ambiguity("Grant access to admins or owners but not both")
+                .spans
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_aisp_ambiguity() {
+        let ambiguity = aisp_ambiguity("⟦Γ:Rules⟧{a∧b∨c; d∨(e∧f); g}");
+        assert_eq!(kinds(&ambiguity), vec![AmbiguityKind::Precedence]);
+        assert_eq!(ambiguity.spans[0].readings, vec!["(a∧b)∨c", "a∧(b∨c)"]);
+        assert!((ambiguity.score - 0.25).abs() < 1e-9);
+
+        let spec = aisp_ambiguity(&crate::claude::AISP_PROMPT);
+        assert!(spec.score < 0.02, "spec ambiguity is {}", spec.score);
+    }
+}
@@ -0,0 +1,363 @@
+//! AISP Abstract Syntax Tree
+//!
+//! Typed representation of an AISP document as produced by [`crate::parse`].
+//! Every node carries the byte span of the source text it was parsed from.
+//!
+//! All nodes serialize to JSON; see [`crate::Parsed::to_json`] for the
+//! versioned form exchanged with other tools.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the JSON representation of the AST
+///
+/// Bumped whenever a node is added, removed or reshaped.
+pub const AST_VERSION: u32 = 1;
+
+/// Byte range in the source text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    /// Offset of the first byte
+    pub start: usize,
+    /// Offset one past the last byte
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a span covering `start..end`
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Smallest span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// The source text covered by this span
+    pub fn text(self, source: &str) -> &str {
+        &source[self.start..self.end]
+    }
+
+    /// 1-based line and column (in characters) of the span start
+    pub fn line_col(self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let col = source[line_start..self.start].chars().count() + 1;
+        (line, col)
+    }
+}
+
+/// A parsed AISP document
+///
+/// Follows `Doc≜𝔸≫CTX?≫REF?≫⟦Ω⟧≫⟦Σ⟧≫⟦Γ⟧≫⟦Λ⟧≫⟦Χ⟧?≫⟦Ε⟧`, but every part is
+/// optional so that partial and malformed documents can still be inspected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    /// `𝔸Ver.Name@Date` header
+    pub header: Option<Header>,
+    /// `γ≔Id` context
+    pub context: Option<Context>,
+    /// `ρ≔⟨…⟩` references
+    pub references: Option<References>,
+    /// Top-level statements outside any block, such as `⊢ND∧CAT`
+    pub claims: Vec<Stmt>,
+    /// `⟦Cat:Name⟧{…}` blocks in source order
+    pub blocks: Vec<Block>,
+    /// `⟦Ε⟧⟨…⟩` evidence block
+    pub evidence: Option<Evidence>,
+    /// Top-level comments not attached to a block
+    pub comments: Vec<Comment>,
+    /// Span of the whole document
+    pub span: Span,
+}
+
+impl Document {
+    /// Find the first block of a category (e.g. `"Σ"`)
+    pub fn block(&self, category: &str) -> Option<&Block> {
+        self.blocks.iter().find(|b| b.category == category)
+    }
+
+    /// Whether the document has a block of a category
+    pub fn has_block(&self, category: &str) -> bool {
+        self.block(category).is_some() || (category == "Ε" && self.evidence.is_some())
+    }
+}
+
+/// Document header `𝔸Ver.Name@Date`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    /// Version, e.g. `5.1`
+    pub version: String,
+    /// Document name, e.g. `auth.rules`
+    pub name: String,
+    /// Date, e.g. `2026-01-09`
+    pub date: String,
+    /// Source span
+    pub span: Span,
+}
+
+/// Context declaration `γ≔Id`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Context {
+    /// Context identifier, e.g. `aisp.specification`
+    pub id: String,
+    /// Source span
+    pub span: Span,
+}
+
+/// Reference list `ρ≔⟨a,b,c⟩`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct References {
+    /// Referenced names in source order
+    pub items: Vec<String>,
+    /// Source span
+    pub span: Span,
+}
+
+/// A `;;` comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    /// Comment text without the leading `;;`
+    pub text: String,
+    /// Source span
+    pub span: Span,
+}
+
+/// A `⟦Cat:Name⟧{…}` block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    /// Category glyph, e.g. `Ω`, `Σ`, `Γ`, `Λ`, `Χ`
+    pub category: String,
+    /// Optional name after the colon, e.g. `Types`
+    pub name: Option<String>,
+    /// Statements in the block body
+    pub body: Vec<Stmt>,
+    /// Top-level comments directly preceding the block
+    pub leading_comments: Vec<Comment>,
+    /// Source span
+    pub span: Span,
+}
+
+/// Evidence block `⟦Ε⟧⟨δ≜0.82;φ≜100;τ≜◊⁺⁺;⊢valid⟩`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Evidence {
+    /// Entries in source order
+    pub entries: Vec<EvidenceEntry>,
+    /// Top-level comments directly preceding the block
+    pub leading_comments: Vec<Comment>,
+    /// Source span
+    pub span: Span,
+}
+
+impl Evidence {
+    /// Value of the first `name≜value` entry with this name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.name.as_deref() == Some(name))
+            .map(|e| e.value.as_str())
+    }
+}
+
+/// Evidence entry: either a `name≜value` field or a bare claim such as `⊢valid`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceEntry {
+    /// Field name, `None` for claims
+    pub name: Option<String>,
+    /// Field value, or the whole claim text
+    pub value: String,
+    /// Source span
+    pub span: Span,
+}
+
+/// Statement inside a block body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stmt {
+    /// What kind of statement this is
+    pub kind: StmtKind,
+    /// Trailing `;;` comment on the same line
+    pub comment: Option<Comment>,
+    /// Source span
+    pub span: Span,
+}
+
+/// Statement kinds per `Stmt≜Def|Rule|Expr|';; '∘.*`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum StmtKind {
+    /// `target ≜ value` or `target ≔ value`
+    Def {
+        target: Expr,
+        /// `≜` or `≔`
+        op: String,
+        value: Expr,
+    },
+    /// `premise ⇒ consequent`
+    Rule { premise: Expr, consequent: Expr },
+    /// Any other expression
+    Expr(Expr),
+    /// A comment on its own line
+    Comment(Comment),
+    /// A statement that could not be parsed; the raw source is kept
+    Error(String),
+}
+
+/// Expression node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expr {
+    /// What kind of expression this is
+    pub kind: ExprKind,
+    /// Source span
+    pub span: Span,
+}
+
+impl Expr {
+    /// Direct subexpressions in source order
+    pub fn children(&self) -> Vec<&Expr> {
+        match &self.kind {
+            ExprKind::Ident(_) | ExprKind::Number(_) | ExprKind::Text(_) | ExprKind::Symbol(_) => {
+                Vec::new()
+            }
+            ExprKind::Unary { operand, .. } | ExprKind::Postfix { operand, .. } => vec![operand],
+            ExprKind::Binary { lhs, rhs, .. } => vec![lhs, rhs],
+            ExprKind::Lambda { body, .. } => vec![body],
+            ExprKind::Quant { binder, body, .. } => vec![binder, body],
+            ExprKind::Apply { func, args } => std::iter::once(&**func).chain(args).collect(),
+            ExprKind::Index { base, args } => std::iter::once(&**base).chain(args).collect(),
+            ExprKind::Juxtapose { func, arg } => vec![func, arg],
+            ExprKind::Group { items, .. } => items.iter().collect(),
+        }
+    }
+
+    /// This expression and all of its subexpressions, outermost first
+    pub fn walk(&self) -> Vec<&Expr> {
+        let mut exprs = vec![self];
+        let mut i = 0;
+        while i < exprs.len() {
+            let children = exprs[i].children();
+            exprs.extend(children);
+            i += 1;
+        }
+        exprs
+    }
+}
+
+impl Stmt {
+    /// Top-level expressions of the statement
+    pub fn exprs(&self) -> Vec<&Expr> {
+        match &self.kind {
+            StmtKind::Def { target, value, .. } => vec![target, value],
+            StmtKind::Rule {
+                premise,
+                consequent,
+            } => vec![premise, consequent],
+            StmtKind::Expr(expr) => vec![expr],
+            StmtKind::Comment(_) | StmtKind::Error(_) => Vec::new(),
+        }
+    }
+}
+
+/// Expression kinds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ExprKind {
+    /// Identifier, including double-struck and subscripted names (`ℕ`, `T₁`)
+    Ident(String),
+    /// Numeric literal, kept as written
+    Number(String),
+    /// Quoted literal including its quotes
+    Text(String),
+    /// A glyph used as a value, such as `⊤`, `∅` or `◊⁺⁺`
+    Symbol(String),
+    /// Prefix operator application, such as `¬A`
+    Unary { op: String, operand: Box<Expr> },
+    /// Postfix operator application, such as `CTX?`
+    Postfix { op: String, operand: Box<Expr> },
+    /// Infix operator application, such as `A∧B`
+    Binary {
+        op: String,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    /// `λx y.body`
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expr>,
+    },
+    /// `∀x∈S:body`, `∃!x:body`, `Πn:ℕ.body`
+    Quant {
+        quantifier: String,
+        binder: Box<Expr>,
+        body: Box<Expr>,
+    },
+    /// `f(a,b)` with the parenthesis directly after the callee
+    Apply { func: Box<Expr>, args: Vec<Expr> },
+    /// `T[a,b]` with the bracket directly after the base
+    Index { base: Box<Expr>, args: Vec<Expr> },
+    /// Application by juxtaposition, such as `hd s`
+    Juxtapose { func: Box<Expr>, arg: Box<Expr> },
+    /// Bracketed, comma-separated items: `(a)`, `⟨a,b⟩`, `{a,b}`, `[a]`, `|a|`
+    Group { delim: Delim, items: Vec<Expr> },
+}
+
+/// Bracket pairs recognized by the parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Delim {
+    /// `( )`
+    Paren,
+    /// `⟨ ⟩`
+    Angle,
+    /// `{ }`
+    Brace,
+    /// `[ ]`
+    Square,
+    /// `| |`
+    Bar,
+    /// `‖ ‖`
+    Norm,
+    /// `⌈ ⌉`
+    Ceil,
+    /// `⌊ ⌋`
+    Floor,
+    /// `⟪ ⟫`
+    DoubleAngle,
+    /// `⟦ ⟧` used inside an expression, such as `⟦Ω⟧∘⟦Σ⟧`
+    Block,
+}
+
+impl Delim {
+    /// Opening and closing glyphs
+    pub fn glyphs(self) -> (char, char) {
+        match self {
+            Delim::Paren => ('(', ')'),
+            Delim::Angle => ('⟨', '⟩'),
+            Delim::Brace => ('{', '}'),
+            Delim::Square => ('[', ']'),
+            Delim::Bar => ('|', '|'),
+            Delim::Norm => ('‖', '‖'),
+            Delim::Ceil => ('⌈', '⌉'),
+            Delim::Floor => ('⌊', '⌋'),
+            Delim::DoubleAngle => ('⟪', '⟫'),
+            Delim::Block => ('⟦', '⟧'),
+        }
+    }
+
+    /// The delimiter opened by `c`, if any
+    pub fn from_open(c: char) -> Option<Delim> {
+        Some(match c {
+            '(' => Delim::Paren,
+            '⟨' => Delim::Angle,
+            '{' => Delim::Brace,
+            '[' => Delim::Square,
+            '|' => Delim::Bar,
+            '‖' => Delim::Norm,
+            '⌈' => Delim::Ceil,
+            '⌊' => Delim::Floor,
+            '⟪' => Delim::DoubleAngle,
+            '⟦' => Delim::Block,
+            _ => return None,
+        })
+    }
+}
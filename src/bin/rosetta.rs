@@ -3,15 +3,24 @@
 //! A command-line tool for bidirectional prose ↔ AISP conversion
 //! with optional LLM fallback for improved accuracy.
 
+use async_trait::async_trait;
 use clap::{Parser, Subcommand, ValueEnum};
 use rosetta_aisp_llm::{
-    convert_with_fallback, AispConverter, ConversionOptions, ConversionOptionsExt, ConversionTier,
-    RosettaStone,
+    aisp_ambiguity, convert_clarified, convert_interactive, convert_or_clarify,
+    convert_with_fallback_ext, diff, format_aisp, lint, lint_to_sarif, parse, prose_ambiguity,
+    render, repair, rewrite_evidence, trace_requirements, validate, verify_evidence, AispConverter,
+    Alignment, AlignmentOrigin, ChangeKind, ClarificationAnswer, ClarificationHandler,
+    ClarificationQuestion, ClarificationRequest, ConversionOptionsExt, ConversionOutcome,
+    ConversionTier, EvidenceMode, FallbackMode, FirstInterpretation, FormatStyle, LintConfig,
+    LintRule, Parsed, RequirementCoverage, RosettaStone, Severity, TierCheck,
 };
 use rosetta_aisp::{
     get_all_categories, prose_to_symbol, symbol_to_prose, symbols_by_category,
 };
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufReader, Stdin};
+use tokio::sync::Mutex;
 
 #[derive(Parser)]
 #[command(name = "rosetta")]
@@ -54,6 +63,233 @@ enum Commands {
         /// Use AISP symbolic prompt instead of English prompt
         #[arg(long)]
         aisp_prompt: bool,
+
+        /// Send the whole prose to the LLM, or only the unmapped phrases
+        #[arg(long, value_enum, default_value = "full")]
+        fallback_mode: FallbackModeArg,
+
+        /// Score deterministic and LLM outputs and keep the better one
+        #[arg(long)]
+        select_best: bool,
+
+        /// Number of LLM samples to request and rank
+        #[arg(long, default_value = "1")]
+        samples: usize,
+
+        /// Request LLM samples concurrently
+        #[arg(long)]
+        parallel_samples: bool,
+
+        /// Accept LLM outputs even if they fail validation
+        #[arg(long)]
+        no_validate: bool,
+
+        /// Keep, verify or rewrite the evidence block of the output
+        #[arg(long, value_enum, default_value = "keep")]
+        evidence: EvidenceModeArg,
+
+        /// Repair structural errors in the output
+        #[arg(long)]
+        repair: bool,
+
+        /// Report, re-prompt for, or ignore LLM outputs below the requested tier
+        #[arg(long, value_enum, default_value = "report")]
+        tier_check: TierCheckArg,
+
+        /// Use the LLM fallback when prose ambiguity reaches this score
+        #[arg(long)]
+        ambiguity_threshold: Option<f64>,
+
+        /// Ask the LLM for a JSON document and render the AISP from it
+        #[arg(long)]
+        json_mode: bool,
+
+        /// Let the LLM validate its drafts with tools before answering
+        #[arg(long)]
+        agentic: bool,
+
+        /// Start the LLM request early when low confidence is predicted
+        #[arg(long)]
+        speculative: bool,
+
+        /// Models to try in turn, cheapest first, e.g. haiku,sonnet,opus
+        #[arg(long, value_delimiter = ',')]
+        model_ladder: Vec<String>,
+
+        /// Move up the model ladder when the quality score is below this
+        #[arg(long)]
+        escalation_quality: Option<f64>,
+
+        /// Show each output statement next to the prose it came from
+        #[arg(long)]
+        align: bool,
+
+        /// Tag statements with the `REQ-123:` style requirements they came from
+        /// and report requirements that produced no AISP
+        #[arg(long)]
+        requirements: bool,
+
+        /// Ask about ambiguous prose before converting: prompts on a terminal
+        /// when the prose is given with --input, otherwise prints the questions
+        /// and exits non-zero
+        #[arg(long)]
+        clarify: bool,
+
+        /// Assume the first interpretation of every ambiguity, listing each
+        /// assumption on stderr
+        #[arg(long, requires = "clarify")]
+        assume_first: bool,
+
+        /// Answer a clarification question as QUESTION=CHOICE, where CHOICE is
+        /// an interpretation number or free text (repeatable)
+        #[arg(long, value_name = "QUESTION=CHOICE", value_parser = parse_answer, requires = "clarify")]
+        answer: Vec<(usize, String)>,
+    },
+
+    /// Estimate the ambiguity of prose, or of an AISP document
+    Ambiguity {
+        /// Text to analyze (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Treat the input as AISP instead of prose
+        #[arg(long)]
+        aisp: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Validate an AISP document (well-formedness, density and tier)
+    Validate {
+        /// AISP document to validate (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Check the ⟦Ε⟧ evidence claims of an AISP document against its content
+    Evidence {
+        /// AISP document to check (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Print the document with a recomputed evidence block instead
+        #[arg(long)]
+        rewrite: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Lint AISP documents
+    Lint {
+        /// Files to lint (reads --input or stdin if none are given)
+        files: Vec<PathBuf>,
+
+        /// AISP document to lint when no files are given
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Tier whose required blocks must be present
+        #[arg(short, long, value_enum, default_value = "standard")]
+        tier: TierArg,
+
+        /// Don't report a rule (repeatable)
+        #[arg(long, value_name = "RULE")]
+        allow: Vec<LintRule>,
+
+        /// Report a rule as a warning (repeatable)
+        #[arg(long, value_name = "RULE")]
+        warn: Vec<LintRule>,
+
+        /// Report a rule as an error (repeatable)
+        #[arg(long, value_name = "RULE")]
+        deny: Vec<LintRule>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: LintFormatArg,
+    },
+
+    /// Repair structural errors in an AISP document
+    Repair {
+        /// AISP document to repair (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Tier whose required blocks the document must carry
+        #[arg(short, long, value_enum, default_value = "standard")]
+        tier: TierArg,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Format an AISP document in the canonical layout
+    Fmt {
+        /// AISP document to format (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Put the whole document on a single line and drop comments
+        #[arg(long)]
+        compact: bool,
+
+        /// Exit non-zero if the document is not already formatted instead of printing it
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Parse an AISP document and print its structure
+    Parse {
+        /// AISP document to parse (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Print the versioned JSON AST instead of an outline
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Render a JSON AST (as printed by `parse --json`) back to AISP
+    Render {
+        /// JSON AST to render (reads from stdin if not provided)
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
+    /// Compare two AISP documents structurally
+    Diff {
+        /// Original document
+        before: PathBuf,
+
+        /// Changed document
+        after: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Report which requirements of a prose spec produced AISP (exits
+    /// non-zero if any produced none)
+    Requirements {
+        /// Prose spec with `REQ-123:` style labels
+        prose: PathBuf,
+
+        /// AISP document converted from it
+        aisp: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
 
     /// Convert AISP notation back to prose
@@ -121,6 +357,63 @@ impl From<TierArg> for ConversionTier {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LintFormatArg {
+    Text,
+    Json,
+    /// SARIF 2.1.0 for code-scanning tools
+    Sarif,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FallbackModeArg {
+    Full,
+    Targeted,
+}
+
+impl From<FallbackModeArg> for FallbackMode {
+    fn from(mode: FallbackModeArg) -> Self {
+        match mode {
+            FallbackModeArg::Full => FallbackMode::Full,
+            FallbackModeArg::Targeted => FallbackMode::Targeted,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EvidenceModeArg {
+    Keep,
+    Verify,
+    Rewrite,
+}
+
+impl From<EvidenceModeArg> for EvidenceMode {
+    fn from(mode: EvidenceModeArg) -> Self {
+        match mode {
+            EvidenceModeArg::Keep => EvidenceMode::Keep,
+            EvidenceModeArg::Verify => EvidenceMode::Verify,
+            EvidenceModeArg::Rewrite => EvidenceMode::Rewrite,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TierCheckArg {
+    Off,
+    Report,
+    Reprompt,
+}
+
+impl From<TierCheckArg> for TierCheck {
+    fn from(check: TierCheckArg) -> Self {
+        match check {
+            TierCheckArg::Off => TierCheck::Off,
+            TierCheckArg::Report => TierCheck::Report,
+            TierCheckArg::Reprompt => TierCheck::Reprompt,
+        }
+    }
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 enum OutputFormat {
     /// Plain text output
@@ -129,6 +422,111 @@ enum OutputFormat {
     Json,
 }
 
+fn print_questions(request: &ClarificationRequest, format: OutputFormat) {
+    match format {
+        OutputFormat::Text => {
+            for (i, question) in request.questions.iter().enumerate() {
+                println!("{}. {}", i + 1, question.question);
+                for (j, interpretation) in question.interpretations.iter().enumerate() {
+                    println!("   {}) {}", j + 1, interpretation);
+                }
+            }
+            eprintln!();
+            eprintln!("Answer with --answer <QUESTION>=<CHOICE> for each question");
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(request).expect("Failed to serialize");
+            println!("{}", json);
+        }
+    }
+}
+
+fn print_coverage(coverage: &RequirementCoverage) {
+    for requirement in &coverage.requirements {
+        if requirement.statements.is_empty() {
+            println!("{}: no AISP", requirement.id);
+        }
+        for statement in &requirement.statements {
+            println!("{}: {}", requirement.id, statement);
+        }
+    }
+    let uncovered = coverage.uncovered().count();
+    println!(
+        "{}/{} requirements covered",
+        coverage.requirements.len() - uncovered,
+        coverage.requirements.len()
+    );
+}
+
+/// Width of the prose column in [`side_by_side`]
+const PROSE_WIDTH: usize = 48;
+
+/// Prose and AISP side by side, one row per statement
+///
+/// Statements without a source show `-`, and sources quoted by the LLM are
+/// marked with `*`.
+fn side_by_side(prose: &str, aisp: &str, alignment: &Alignment) -> String {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut block = None;
+    for statement in &alignment.statements {
+        if statement.block != block {
+            block = statement.block.clone();
+            if let Some(label) = &block {
+                rows.push((String::new(), format!("⟦{}⟧", label)));
+            }
+        }
+        let mut source: Vec<&str> = statement.prose.iter().map(|s| s.text(prose)).collect();
+        if source.is_empty() {
+            source.push("-");
+        }
+        let marker = match statement.origin {
+            AlignmentOrigin::Rosetta => "",
+            AlignmentOrigin::Llm => " *",
+        };
+        let text: Vec<&str> = statement.aisp.text(aisp).split_whitespace().collect();
+        rows.push((
+            format!("{}{}", source.join(" … "), marker),
+            format!("  {}", text.join(" ")),
+        ));
+    }
+
+    let mut output = String::new();
+    for (left, right) in rows {
+        let lines = wrap(&left, PROSE_WIDTH);
+        for (i, line) in lines.iter().enumerate() {
+            let right = if i == 0 { right.as_str() } else { "" };
+            let row = format!("{:<width$} │ {}", line, right, width = PROSE_WIDTH);
+            output.push_str(row.trim_end());
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// Break `text` into lines of at most `width` characters at spaces
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().expect("never empty");
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(word.to_string());
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+    lines
+}
+
+fn read_file(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {}", path.display(), e);
+        std::process::exit(2);
+    })
+}
+
 fn read_input(input: Option<String>) -> String {
     match input {
         Some(text) => text,
@@ -142,6 +540,102 @@ fn read_input(input: Option<String>) -> String {
     }
 }
 
+fn parse_answer(arg: &str) -> Result<(usize, String), String> {
+    let (question, choice) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected QUESTION=CHOICE, got `{}`", arg))?;
+    let question: usize = question
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid question number `{}`", question))?;
+    if question == 0 || choice.trim().is_empty() {
+        return Err(format!("expected QUESTION=CHOICE, got `{}`", arg));
+    }
+    Ok((question, choice.trim().to_string()))
+}
+
+/// The interpretation numbered `choice`, or `choice` itself as free text
+fn resolve_choice(question: &ClarificationQuestion, choice: &str) -> String {
+    choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| question.choose(n.checked_sub(1)?))
+        .unwrap_or_else(|| choice.to_string())
+}
+
+/// The `--answer` choice for the question at `index`
+fn preset_answer(answers: &[(usize, String)], index: usize) -> Option<&str> {
+    answers
+        .iter()
+        .rev()
+        .find(|(n, _)| *n == index + 1)
+        .map(|(_, choice)| choice.as_str())
+}
+
+/// Resolve `--answer` values against the questions they refer to
+///
+/// Returns `None` while some question is still unanswered.
+fn resolve_answers(
+    request: &ClarificationRequest,
+    answers: &[(usize, String)],
+) -> Option<Vec<ClarificationAnswer>> {
+    request
+        .questions
+        .iter()
+        .enumerate()
+        .map(|(i, question)| {
+            let choice = preset_answer(answers, i)?;
+            Some(ClarificationAnswer {
+                question: i,
+                interpretation: resolve_choice(question, choice),
+            })
+        })
+        .collect()
+}
+
+/// Asks clarification questions on the terminal, skipping those answered
+/// with `--answer`
+struct StdinPrompt {
+    preset: Vec<(usize, String)>,
+    /// Shared by every question, so lines read ahead aren't lost
+    stdin: Mutex<BufReader<Stdin>>,
+}
+
+impl StdinPrompt {
+    fn new(preset: Vec<(usize, String)>) -> Self {
+        Self {
+            preset,
+            stdin: Mutex::new(BufReader::new(tokio::io::stdin())),
+        }
+    }
+}
+
+#[async_trait]
+impl ClarificationHandler for StdinPrompt {
+    async fn answer(&self, request: &ClarificationRequest, index: usize) -> Option<String> {
+        let question = request.questions.get(index)?;
+        if let Some(choice) = preset_answer(&self.preset, index) {
+            return Some(resolve_choice(question, choice));
+        }
+
+        eprintln!("{}. {}", index + 1, question.question);
+        for (i, interpretation) in question.interpretations.iter().enumerate() {
+            eprintln!("   {}) {}", i + 1, interpretation);
+        }
+        eprint!("Choice or own wording [1]: ");
+        io::stderr().flush().ok()?;
+
+        let mut line = String::new();
+        self.stdin.lock().await.read_line(&mut line).await.ok()?;
+        match line.trim() {
+            // End of input leaves the text as written
+            _ if line.is_empty() => None,
+            "" => question.choose(0),
+            choice => Some(resolve_choice(question, choice)),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
@@ -155,32 +649,100 @@ async fn main() {
             threshold,
             model,
             aisp_prompt,
+            fallback_mode,
+            select_best,
+            samples,
+            parallel_samples,
+            no_validate,
+            evidence,
+            repair,
+            tier_check,
+            ambiguity_threshold,
+            json_mode,
+            agentic,
+            speculative,
+            model_ladder,
+            escalation_quality,
+            align,
+            requirements,
+            clarify,
+            assume_first,
+            answer,
         } => {
+            // Prose read from stdin leaves nothing to read the answers from
+            let interactive = input.is_some() && io::stdin().is_terminal();
             let prose = read_input(input);
 
-            let result = if llm_fallback {
-                let options = ConversionOptionsExt {
-                    tier: tier.map(Into::into),
-                    confidence_threshold: Some(threshold),
-                    enable_llm_fallback: true,
-                    llm_model: Some(model),
-                    use_aisp_prompt: aisp_prompt,
-                };
-                convert_with_fallback(&prose, Some(options)).await
+            // Without --llm-fallback the provider is never queried
+            let options = ConversionOptionsExt {
+                tier: tier.map(Into::into),
+                confidence_threshold: Some(threshold),
+                enable_llm_fallback: llm_fallback,
+                llm_model: Some(model),
+                use_aisp_prompt: aisp_prompt,
+                fallback_mode: fallback_mode.into(),
+                select_best,
+                samples,
+                parallel_samples,
+                validate_llm_output: !no_validate,
+                evidence_mode: evidence.into(),
+                repair,
+                tier_check: tier_check.into(),
+                ambiguity_threshold,
+                align,
+                trace_requirements: requirements,
+                json_mode,
+                agentic,
+                speculative,
+                model_ladder,
+                escalation_quality,
+            };
+            let ext = if !clarify {
+                convert_with_fallback_ext(&prose, Some(options)).await
+            } else if assume_first {
+                let handler = FirstInterpretation::new();
+                let ext = convert_interactive(&prose, Some(options), &handler).await;
+                for assumption in handler.log() {
+                    eprintln!("Assumed: {}", assumption);
+                }
+                ext
+            } else if interactive {
+                let handler = StdinPrompt::new(answer);
+                convert_interactive(&prose, Some(options), &handler).await
             } else {
-                let options = ConversionOptions {
-                    tier: tier.map(Into::into),
-                    confidence_threshold: Some(threshold),
-                };
-                AispConverter::convert(&prose, Some(options))
+                match convert_or_clarify(&prose, Some(options.clone())).await {
+                    ConversionOutcome::Converted(ext) => *ext,
+                    ConversionOutcome::NeedsClarification(request) => {
+                        match resolve_answers(&request, &answer) {
+                            Some(answers) => {
+                                convert_clarified(&request, &answers, Some(options)).await
+                            }
+                            None => {
+                                print_questions(&request, format);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                }
             };
+            let result = &ext.result;
 
             match format {
                 OutputFormat::Text => {
-                    println!("{}", result.output);
+                    match &ext.alignment {
+                        Some(alignment) => {
+                            print!("{}", side_by_side(&prose, &result.output, alignment))
+                        }
+                        None => println!("{}", result.output),
+                    }
                     eprintln!();
                     eprintln!("---");
-                    eprintln!("Tier: {:?}", result.tier);
+                    match ext.requested_tier {
+                        Some(requested) => {
+                            eprintln!("Tier: {:?} (requested {:?})", result.tier, requested)
+                        }
+                        None => eprintln!("Tier: {:?}", result.tier),
+                    }
                     eprintln!("Confidence: {:.1}%", result.confidence * 100.0);
                     eprintln!(
                         "Tokens: {} → {} ({:.2}x)",
@@ -192,12 +754,453 @@ async fn main() {
                     if !result.unmapped.is_empty() {
                         eprintln!("Unmapped: {:?}", result.unmapped);
                     }
+                    if let Some(selection) = &ext.selection {
+                        eprintln!(
+                            "Selected: {:?} (deterministic {:.2}, llm {:.2})",
+                            selection.chosen, selection.deterministic.total, selection.llm.total
+                        );
+                    }
+                    if let Some(report) = &ext.evidence {
+                        eprintln!(
+                            "Evidence: δ={:.2} φ={} τ={} ({} mismatched claim(s))",
+                            report.density,
+                            report.completeness,
+                            report.tier,
+                            report.mismatches.len()
+                        );
+                    }
+                    for applied in &ext.repairs {
+                        eprintln!("Repaired: {}", applied.message);
+                    }
+                    if let Some(report) = &ext.ambiguity {
+                        eprintln!(
+                            "Ambiguity: prose {:.2}, output {:.2}",
+                            report.prose.score, report.output.score
+                        );
+                    }
+                    if let Some(coverage) = &ext.requirements {
+                        let uncovered: Vec<&str> =
+                            coverage.uncovered().map(|r| r.id.as_str()).collect();
+                        eprintln!(
+                            "Requirements: {}/{} covered",
+                            coverage.requirements.len() - uncovered.len(),
+                            coverage.requirements.len()
+                        );
+                        if !uncovered.is_empty() {
+                            eprintln!("Uncovered: {}", uncovered.join(", "));
+                        }
+                    }
+                    if let Some(speculation) = &ext.speculation {
+                        eprintln!(
+                            "Speculation: {:?} (predicted confidence {:.2})",
+                            speculation.outcome, speculation.predicted_confidence
+                        );
+                    }
+                    for rung in &ext.ladder {
+                        let cost = rung
+                            .cost_usd
+                            .map_or_else(|| "$?".to_string(), |c| format!("${:.4}", c));
+                        let escalation = match rung.escalation {
+                            Some(reason) => format!(", escalated: {:?}", reason),
+                            None => String::new(),
+                        };
+                        eprintln!(
+                            "Rung: {} ({} request(s), {}, {} ms{})",
+                            rung.model, rung.requests, cost, rung.elapsed_ms, escalation
+                        );
+                    }
+                    if let Some(report) = &ext.samples {
+                        eprintln!(
+                            "Samples: {} (chose #{}, self-consistency {:.1}%)",
+                            report.scores.len(),
+                            report.chosen + 1,
+                            report.self_consistency * 100.0
+                        );
+                    }
+                }
+                OutputFormat::Json => {
+                    let json = serde_json::to_string_pretty(&ext).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+        }
+
+        Commands::Validate { input, format } => {
+            let aisp = read_input(input);
+            let validation = validate(&aisp);
+
+            match format {
+                OutputFormat::Text => {
+                    println!("ν: {}", if validation.valid { "⊤" } else { "⊥" });
+                    println!("τ: {}", validation.tier);
+                    println!("δ: {:.2}", validation.density);
+                    println!("φ: {}", validation.completeness);
+                    for violation in &validation.violations {
+                        match violation.span {
+                            Some(span) => {
+                                let (line, col) = span.line_col(&aisp);
+                                eprintln!("{}:{}: {}", line, col, violation.message);
+                            }
+                            None => eprintln!("{}", violation.message),
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let json =
+                        serde_json::to_string_pretty(&validation).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+
+            if !validation.valid {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Ambiguity {
+            input,
+            aisp,
+            format,
+        } => {
+            let text = read_input(input);
+            let ambiguity = if aisp {
+                aisp_ambiguity(&text)
+            } else {
+                prose_ambiguity(&text)
+            };
+
+            match format {
+                OutputFormat::Text => {
+                    println!("Ambig: {:.2}", ambiguity.score);
+                    for span in &ambiguity.spans {
+                        let (line, col) = span.span.line_col(&text);
+                        println!("{}:{}: {:?} `{}`", line, col, span.kind, span.text);
+                        for reading in &span.readings {
+                            println!("  - {}", reading);
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let json =
+                        serde_json::to_string_pretty(&ambiguity).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+        }
+
+        Commands::Evidence {
+            input,
+            rewrite,
+            format,
+        } => {
+            let aisp = read_input(input);
+
+            if rewrite {
+                println!("{}", rewrite_evidence(&aisp));
+                return;
+            }
+
+            let report = verify_evidence(&aisp);
+            match format {
+                OutputFormat::Text => {
+                    if !report.present {
+                        println!("No ⟦Ε⟧ evidence block");
+                    }
+                    println!(
+                        "Recomputed: δ≜{:.2};φ≜{};τ≜{}",
+                        report.density, report.completeness, report.tier
+                    );
+                    for mismatch in &report.mismatches {
+                        let (line, col) = mismatch.span.line_col(&aisp);
+                        println!(
+                            "{}:{}: {} claimed {}, actual {}",
+                            line, col, mismatch.field, mismatch.claimed, mismatch.actual
+                        );
+                    }
+                }
+                OutputFormat::Json => {
+                    let json = serde_json::to_string_pretty(&report).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+
+            if !report.is_accurate() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Lint {
+            files,
+            input,
+            tier,
+            allow,
+            warn,
+            deny,
+            format,
+        } => {
+            let mut config = LintConfig {
+                tier: tier.into(),
+                ..Default::default()
+            };
+            for (rules, severity) in [
+                (allow, Severity::Allow),
+                (warn, Severity::Warning),
+                (deny, Severity::Error),
+            ] {
+                for rule in rules {
+                    config.severities.insert(rule, severity);
+                }
+            }
+
+            let sources: Vec<(String, String)> = if files.is_empty() {
+                vec![("<stdin>".to_string(), read_input(input))]
+            } else {
+                files
+                    .iter()
+                    .map(|path| (path.display().to_string(), read_file(path)))
+                    .collect()
+            };
+
+            let mut errors = 0;
+            let mut warnings = 0;
+            let mut reports = Vec::new();
+            for (file, source) in &sources {
+                let diagnostics = lint(source, &config);
+                for diagnostic in &diagnostics {
+                    match diagnostic.severity {
+                        Severity::Error => errors += 1,
+                        _ => warnings += 1,
+                    }
+                }
+
+                match format {
+                    LintFormatArg::Text => {
+                        for diagnostic in &diagnostics {
+                            eprintln!("{}", diagnostic.render(source, file));
+                        }
+                    }
+                    LintFormatArg::Sarif => {}
+                    LintFormatArg::Json => {
+                        let diagnostics: Vec<serde_json::Value> = diagnostics
+                            .iter()
+                            .map(|diagnostic| {
+                                let mut value =
+                                    serde_json::to_value(diagnostic).expect("Failed to serialize");
+                                if let Some(span) = diagnostic.span {
+                                    let (line, column) = span.line_col(source);
+                                    value["line"] = line.into();
+                                    value["column"] = column.into();
+                                }
+                                value
+                            })
+                            .collect();
+                        reports.push(serde_json::json!({
+                            "file": file,
+                            "diagnostics": diagnostics,
+                        }));
+                    }
+                }
+            }
+
+            match format {
+                LintFormatArg::Text => {
+                    eprintln!("{} error(s), {} warning(s)", errors, warnings);
+                }
+                LintFormatArg::Json => {
+                    let json = serde_json::to_string_pretty(&reports).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+                LintFormatArg::Sarif => {
+                    let files: Vec<(&str, &str)> = sources
+                        .iter()
+                        .map(|(file, source)| (file.as_str(), source.as_str()))
+                        .collect();
+                    let log = lint_to_sarif(&files, &config);
+                    let json = serde_json::to_string_pretty(&log).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Repair {
+            input,
+            tier,
+            format,
+        } => {
+            let aisp = read_input(input);
+            let repaired = repair(&aisp, tier.into());
+
+            match format {
+                OutputFormat::Text => {
+                    println!("{}", repaired.output);
+                    for applied in &repaired.repairs {
+                        eprintln!("Repaired: {}", applied.message);
+                    }
+                }
+                OutputFormat::Json => {
+                    let json =
+                        serde_json::to_string_pretty(&repaired).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+        }
+
+        Commands::Fmt {
+            input,
+            compact,
+            check,
+        } => {
+            let aisp = read_input(input);
+            let style = if compact {
+                FormatStyle::Compact
+            } else {
+                FormatStyle::Pretty
+            };
+
+            let formatted = match format_aisp(&aisp, style) {
+                Ok(formatted) => formatted,
+                Err(errors) => {
+                    for error in &errors {
+                        let (line, col) = error.span.line_col(&aisp);
+                        eprintln!("{}:{}: {}", line, col, error.message);
+                    }
+                    std::process::exit(1);
+                }
+            };
+
+            if check {
+                if formatted.trim_end() != aisp.trim_end() {
+                    eprintln!("Document is not formatted");
+                    std::process::exit(1);
+                }
+            } else {
+                print!("{}", formatted);
+            }
+        }
+
+        Commands::Parse { input, json } => {
+            let aisp = read_input(input);
+            let parsed = parse(&aisp);
+
+            if json {
+                let json =
+                    serde_json::to_string_pretty(&parsed.to_json()).expect("Failed to serialize");
+                println!("{}", json);
+            } else {
+                let doc = &parsed.document;
+                if let Some(header) = &doc.header {
+                    println!("𝔸 {} {} {}", header.version, header.name, header.date);
+                }
+                if let Some(context) = &doc.context {
+                    println!("γ {}", context.id);
+                }
+                if !doc.claims.is_empty() {
+                    println!("{} claim(s)", doc.claims.len());
+                }
+                for block in &doc.blocks {
+                    let label = match &block.name {
+                        Some(name) => format!("⟦{}:{}⟧", block.category, name),
+                        None => format!("⟦{}⟧", block.category),
+                    };
+                    println!("{} {} statement(s)", label, block.body.len());
+                }
+                if let Some(evidence) = &doc.evidence {
+                    println!("⟦Ε⟧ {} item(s)", evidence.entries.len());
+                }
+                for error in &parsed.errors {
+                    let (line, col) = error.span.line_col(&aisp);
+                    eprintln!("{}:{}: {}", line, col, error.message);
+                }
+            }
+
+            if !parsed.is_ok() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Render { input } => {
+            let json = read_input(input);
+            match Parsed::from_json(&json) {
+                Ok(parsed) => print!("{}", render(&parsed.document)),
+                Err(e) => {
+                    eprintln!("Invalid AST: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Requirements {
+            prose,
+            aisp,
+            format,
+        } => {
+            let coverage = trace_requirements(&read_file(&prose), &read_file(&aisp));
+
+            match format {
+                OutputFormat::Text => print_coverage(&coverage),
+                OutputFormat::Json => {
+                    let json =
+                        serde_json::to_string_pretty(&coverage).expect("Failed to serialize");
+                    println!("{}", json);
+                }
+            }
+
+            if !coverage.is_complete() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Diff {
+            before,
+            after,
+            format,
+        } => {
+            let changes = diff(&read_file(&before), &read_file(&after));
+
+            match format {
+                OutputFormat::Text => {
+                    let mut section = None;
+                    for change in &changes.changes {
+                        if section != Some(&change.section) {
+                            section = Some(&change.section);
+                            if !change.section.is_empty() {
+                                println!("⟦{}⟧", change.section);
+                            }
+                        }
+                        let (marker, text) = match change.kind {
+                            ChangeKind::Added => ("+", change.after.clone()),
+                            ChangeKind::Removed => ("-", change.before.clone()),
+                            ChangeKind::Changed => (
+                                "~",
+                                Some(format!(
+                                    "{} → {}",
+                                    change.before.as_deref().unwrap_or_default(),
+                                    change.after.as_deref().unwrap_or_default()
+                                )),
+                            ),
+                        };
+                        match text {
+                            Some(text) => println!("  {} {:?} {}", marker, change.item, text),
+                            None => println!("  {} {:?} {}", marker, change.item, change.name),
+                        }
+                    }
+                    if changes.is_empty() {
+                        println!("No structural differences");
+                    }
                 }
                 OutputFormat::Json => {
-                    let json = serde_json::to_string_pretty(&result).expect("Failed to serialize");
+                    let json = serde_json::to_string_pretty(&changes).expect("Failed to serialize");
                     println!("{}", json);
                 }
             }
+
+            if !changes.is_empty() {
+                std::process::exit(1);
+            }
         }
 
         Commands::ToProse { input } => {
@@ -0,0 +1,331 @@
+//! Clarification
+//!
+//! Implements `ambiguity_detected⇒reject∧clarify` from `⟦Γ:Agent⟧`: instead
+//! of guessing, a conversion can stop with questions about the ambiguous
+//! parts of the prose, each with candidate interpretations, and resume once
+//! they are answered. Answers are written into the prose next to the text
+//! they clarify, so both the deterministic pass and the LLM see them.
+
+use crate::ambiguity::{prose_ambiguity, AmbiguityKind, AmbiguousSpan};
+use crate::ast::Span;
+use crate::provider::LlmProvider;
+use crate::targeted::find_word;
+use crate::{ConversionOptionsExt, ConversionResultExt};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// A question about an ambiguous part of the prose
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClarificationQuestion {
+    /// What makes the text ambiguous, when found by the deterministic analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AmbiguityKind>,
+    /// The ambiguous prose
+    pub text: String,
+    /// Byte range of `text` in the prose, when it could be located
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    /// The question to ask
+    pub question: String,
+    /// Candidate interpretations, likeliest first
+    pub interpretations: Vec<String>,
+}
+
+impl ClarificationQuestion {
+    fn from_span(span: &AmbiguousSpan) -> Self {
+        let question = match span.kind {
+            AmbiguityKind::QuantifierScope => {
+                format!("In \"{}\", is it one each or one shared?", span.text)
+            }
+            AmbiguityKind::Pronoun => format!("What does \"{}\" refer to?", span.text),
+            AmbiguityKind::Disjunction => {
+                format!("Does \"{}\" allow both, or exactly one?", span.text)
+            }
+            AmbiguityKind::Precedence => format!("How is \"{}\" grouped?", span.text),
+        };
+        Self {
+            kind: Some(span.kind),
+            text: span.text.clone(),
+            span: Some(span.span),
+            question,
+            interpretations: span.readings.clone(),
+        }
+    }
+
+    /// The span of `text` in `prose`, if `span` is one
+    ///
+    /// Spans from providers or deserialized requests are only trusted when
+    /// they are in bounds, on word boundaries and cover exactly `text`.
+    fn checked_span(&self, prose: &str) -> Option<Span> {
+        self.span.filter(|span| {
+            prose.get(span.start..span.end) == Some(self.text.as_str())
+                && find_word(prose, &self.text).contains(&(span.start..span.end))
+        })
+    }
+
+    /// Check `span` against `prose`, locating `text` on word boundaries if
+    /// the span is missing or wrong
+    fn locate(&mut self, prose: &str) {
+        self.span = self.checked_span(prose).or_else(|| {
+            find_word(prose, &self.text)
+                .into_iter()
+                .find(|range| prose[range.clone()] == self.text)
+                .map(|range| Span::new(range.start, range.end))
+        });
+    }
+
+    /// Answer with the candidate interpretation at `index`
+    pub fn choose(&self, index: usize) -> Option<String> {
+        self.interpretations.get(index).cloned()
+    }
+}
+
+/// Questions that must be answered before the prose is converted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClarificationRequest {
+    /// The prose as given
+    pub prose: String,
+    /// Questions in prose order
+    pub questions: Vec<ClarificationQuestion>,
+}
+
+impl ClarificationRequest {
+    /// The prose with each answer written after the text it clarifies
+    ///
+    /// `answers` holds the chosen interpretation, or free text, per question
+    /// index; unanswered questions keep the text as written.
+    pub fn clarified_prose(&self, answers: &[ClarificationAnswer]) -> String {
+        let mut inline: Vec<(usize, &str)> = Vec::new();
+        let mut trailing = Vec::new();
+        for answer in answers {
+            let Some(question) = self.questions.get(answer.question) else {
+                continue;
+            };
+            match question.checked_span(&self.prose) {
+                Some(span) => inline.push((span.end, &answer.interpretation)),
+                None => trailing.push(format!("{}: {}.", question.text, answer.interpretation)),
+            }
+        }
+
+        // From the end, so earlier offsets stay valid
+        inline.sort_by_key(|(end, _)| std::cmp::Reverse(*end));
+        let mut prose = self.prose.clone();
+        for (end, interpretation) in inline {
+            prose.insert_str(end, &format!(" (meaning {})", interpretation));
+        }
+        for line in trailing {
+            prose.push('\n');
+            prose.push_str(&line);
+        }
+        prose
+    }
+}
+
+/// The answer to one clarification question
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClarificationAnswer {
+    /// Index of the question in [`ClarificationRequest::questions`]
+    pub question: usize,
+    /// The chosen interpretation, or the user's own wording
+    pub interpretation: String,
+}
+
+/// Result of a conversion that may stop to ask for clarification
+#[derive(Debug, Clone)]
+pub enum ConversionOutcome {
+    /// The prose was unambiguous enough to convert
+    Converted(Box<ConversionResultExt>),
+    /// The prose needs clarification first
+    NeedsClarification(ClarificationRequest),
+}
+
+/// Answers clarification questions while a conversion is running
+///
+/// Implement this to put a human in the loop, for example in a chat or a
+/// terminal. Questions are asked one at a time, in prose order.
+#[async_trait]
+pub trait ClarificationHandler: Send + Sync {
+    /// Answer the question at `index` in `request`
+    ///
+    /// Returns the chosen interpretation or the user's own wording, or
+    /// `None` to leave the text as written.
+    async fn answer(&self, request: &ClarificationRequest, index: usize) -> Option<String>;
+}
+
+/// Non-interactive handler that assumes the likeliest interpretation
+///
+/// Every assumption is logged, so that it can be reported alongside the
+/// result.
+#[derive(Debug, Default)]
+pub struct FirstInterpretation {
+    log: Mutex<Vec<String>>,
+}
+
+impl FirstInterpretation {
+    /// Create a handler with an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The assumptions made so far, as `"<text>": <interpretation>`
+    pub fn log(&self) -> Vec<String> {
+        self.log.lock().expect("log lock poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl ClarificationHandler for FirstInterpretation {
+    async fn answer(&self, request: &ClarificationRequest, index: usize) -> Option<String> {
+        let question = request.questions.get(index)?;
+        let interpretation = question.choose(0)?;
+        self.log
+            .lock()
+            .expect("log lock poisoned")
+            .push(format!("\"{}\": {}", question.text, interpretation));
+        Some(interpretation)
+    }
+}
+
+/// Ask `handler` every question in `request`
+pub(crate) async fn ask(
+    request: &ClarificationRequest,
+    handler: &dyn ClarificationHandler,
+) -> Vec<ClarificationAnswer> {
+    let mut answers = Vec::new();
+    for question in 0..request.questions.len() {
+        if let Some(interpretation) = handler.answer(request, question).await {
+            answers.push(ClarificationAnswer {
+                question,
+                interpretation,
+            });
+        }
+    }
+    answers
+}
+
+/// Questions about `prose`, from the deterministic analysis and the provider
+///
+/// The deterministic analysis asks once the prose ambiguity reaches
+/// `opts.ambiguity_threshold` (any ambiguity if unset). With LLM fallback
+/// enabled the provider is asked as well; its questions about text already
+/// covered are dropped.
+pub(crate) async fn questions(
+    prose: &str,
+    opts: &ConversionOptionsExt,
+    provider: &dyn LlmProvider,
+) -> Vec<ClarificationQuestion> {
+    let ambiguity = prose_ambiguity(prose);
+    let mut questions: Vec<ClarificationQuestion> =
+        if ambiguity.score > 0.0 && ambiguity.score >= opts.ambiguity_threshold.unwrap_or(0.0) {
+            ambiguity
+                .spans
+                .iter()
+                .map(ClarificationQuestion::from_span)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+    if opts.enable_llm_fallback && provider.is_available().await {
+        let asked = provider.clarify(prose).await.unwrap_or_default();
+        for mut question in asked {
+            if question.interpretations.len() < 2 {
+                continue;
+            }
+            question.locate(prose);
+            let covered = questions.iter().any(|q| match (q.span, question.span) {
+                (Some(a), Some(b)) => a.start < b.end && b.start < a.end,
+                _ => q.text == question.text,
+            });
+            if !covered {
+                questions.push(question);
+            }
+        }
+    }
+
+    questions.sort_by_key(|q| q.span.map_or(usize::MAX, |s| s.start));
+    questions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clarified_prose() {
+        let prose = "Every user has a role. Grant read or write.";
+        let ambiguity = prose_ambiguity(prose);
+        let request = ClarificationRequest {
+            prose: prose.to_string(),
+            questions: ambiguity
+                .spans
+                .iter()
+                .map(ClarificationQuestion::from_span)
+                .collect(),
+        };
+        assert_eq!(request.questions.len(), 2);
+        assert_eq!(
+            request.questions[1].question,
+            "Does \"read or write\" allow both, or exactly one?"
+        );
+
+        let answers = [
+            ClarificationAnswer {
+                question: 0,
+                interpretation: request.questions[0].choose(1).unwrap(),
+            },
+            ClarificationAnswer {
+                question: 1,
+                interpretation: "exactly one".to_string(),
+            },
+        ];
+        assert_eq!(
+            request.clarified_prose(&answers),
+            "Every user has a role (meaning one role shared by every user). \
+             Grant read or write (meaning exactly one)."
+        );
+    }
+
+    #[test]
+    fn test_question_spans_are_checked() {
+        let prose = "Submit the file and archive it.";
+        let mut question = ClarificationQuestion {
+            kind: None,
+            text: "it".to_string(),
+            span: None,
+            question: "What does \"it\" refer to?".to_string(),
+            interpretations: vec!["the file".to_string(), "the archive".to_string()],
+        };
+        question.locate(prose);
+        assert_eq!(question.span, Some(Span::new(28, 30)));
+
+        // Spans inside a word, past the end or off a char boundary are
+        // re-located, or ignored if the text isn't there
+        for span in [Span::new(4, 6), Span::new(28, 99)] {
+            question.span = Some(span);
+            question.locate(prose);
+            assert_eq!(question.span, Some(Span::new(28, 30)));
+        }
+        let mut request = ClarificationRequest {
+            prose: "Ünïcode it".to_string(),
+            questions: vec![ClarificationQuestion {
+                span: Some(Span::new(1, 3)),
+                ..question
+            }],
+        };
+        let answers = [ClarificationAnswer {
+            question: 0,
+            interpretation: "the file".to_string(),
+        }];
+        assert_eq!(
+            request.clarified_prose(&answers),
+            "Ünïcode it\nit: the file."
+        );
+        request.questions[0].locate("Ünïcode it");
+        assert_eq!(
+            request.clarified_prose(&answers),
+            "Ünïcode it (meaning the file)"
+        );
+    }
+}
@@ -3,11 +3,19 @@
 //! Uses claude-agent-sdk-rs for LLM-based AISP conversion
 //! when deterministic Rosetta mappings have low confidence.
 
-use crate::provider::{LlmProvider, LlmResult};
-use anyhow::Result;
+use crate::alignment::SourceQuote;
+use crate::clarify::ClarificationQuestion;
+use crate::pool::{PoolStats, Reply, SessionFactory, SessionPool, WarmSession};
+use crate::provider::{LlmProvider, LlmResult, SpanRequest};
+use crate::structured::StructuredDocument;
+use crate::tools::{ValidationTools, LOOKUP_SYMBOL, VALIDATE_AISP};
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use rosetta_aisp::{get_all_categories, symbol_to_prose, symbols_by_category, ConversionTier};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Generate symbol reference grouped by category
 fn symbol_ref_grouped() -> String {
@@ -143,7 +151,7 @@ Associativity: → is right, ∧∨ are left, ∘ is right
 
 /// Full AISP 5.1 Platinum Specification system prompt
 /// Based on https://github.com/bar181/aisp-open-core/blob/main/AI_GUIDE.md
-static AISP_PROMPT: Lazy<String> = Lazy::new(|| {
+pub(crate) static AISP_PROMPT: Lazy<String> = Lazy::new(|| {
     r#"𝔸5.1.complete@2026-01-09
 γ≔aisp.specification.complete
 ρ≔⟨glossary,types,rules,functions,errors,proofs,parser,agent⟩
@@ -355,12 +363,139 @@ fn create_user_prompt(
     prompt
 }
 
+/// Create user prompt asking for the AISP as a JSON document
+fn create_structured_prompt(
+    prose: &str,
+    tier: ConversionTier,
+    unmapped: &[String],
+    partial_output: Option<&str>,
+) -> String {
+    format!(
+        "{}\n\nInstead of AISP text, reply with only a JSON object following this schema, \
+with one AISP statement per entry of `statements`:\n{}",
+        create_user_prompt(prose, tier, unmapped, partial_output),
+        StructuredDocument::schema()
+    )
+}
+
+/// Create user prompt asking the model to check its draft with the tools
+fn create_agentic_prompt(
+    prose: &str,
+    tier: ConversionTier,
+    unmapped: &[String],
+    partial_output: Option<&str>,
+) -> String {
+    format!(
+        "{}\n\nBefore answering, check your draft with the `{}` tool and fix what it \
+reports until it is accepted. Use `{}` when unsure of a symbol. Then reply with only \
+the final AISP.",
+        create_user_prompt(prose, tier, unmapped, partial_output),
+        VALIDATE_AISP,
+        LOOKUP_SYMBOL
+    )
+}
+
+/// Create user prompt asking for a batch of isolated span translations
+fn create_span_prompt(spans: &[SpanRequest]) -> String {
+    let mut prompt = String::from(
+        "Convert each numbered prose span to AISP (minimal tier). \
+The context is for disambiguation only; translate just the span.\n\
+Reply with exactly one line per span in the form `N: <aisp>`.\n",
+    );
+
+    for (i, span) in spans.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n{}. span: \"{}\" context: \"{}\"",
+            i + 1,
+            span.text,
+            span.context
+        ));
+    }
+
+    prompt
+}
+
+/// Parse `N: <aisp>` lines from a span translation response
+fn parse_span_response(response: &str, expected: usize) -> Result<Vec<String>> {
+    let mut translations: Vec<Option<String>> = vec![None; expected];
+
+    for line in response.lines() {
+        let Some((index, aisp)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let Ok(index) = index.trim().trim_end_matches('.').parse::<usize>() else {
+            continue;
+        };
+        if (1..=expected).contains(&index) {
+            translations[index - 1] = Some(aisp.trim().trim_matches('`').to_string());
+        }
+    }
+
+    translations
+        .into_iter()
+        .enumerate()
+        .map(|(i, t)| match t {
+            Some(t) if !t.is_empty() => Ok(t),
+            _ => bail!("Missing translation for span {}", i + 1),
+        })
+        .collect()
+}
+
+/// Create user prompt asking for clarification questions instead of AISP
+fn create_clarify_prompt(prose: &str) -> String {
+    format!(
+        "Do not convert the prose below. List the parts whose AISP would depend on \
+a reading the author did not make explicit (scope, reference, inclusive or exclusive \
+choice, grouping). Reply with only a JSON array of objects \
+{{\"text\": <exact substring of the prose>, \"question\": <question for the author>, \
+\"interpretations\": [<at least two readings, likeliest first>]}}. \
+Reply with [] if the prose is unambiguous.\n\nProse: {}",
+        prose
+    )
+}
+
+/// Create user prompt asking which prose each statement came from
+fn create_align_prompt(prose: &str, aisp: &str) -> String {
+    format!(
+        "For each statement of the AISP below, quote the parts of the prose it was \
+converted from. Reply with only a JSON array of objects \
+{{\"statement\": <statement exactly as in the AISP>, \
+\"source\": [<exact substrings of the prose>]}}. \
+Leave out statements with no source in the prose.\n\nProse: {}\n\nAISP:\n{}",
+        prose, aisp
+    )
+}
+
+/// Parse the JSON array of questions from a clarification response
+fn parse_clarify_response(response: &str) -> Result<Vec<ClarificationQuestion>> {
+    parse_json_array(response, "question list")
+}
+
+/// Parse the JSON array of quotes from an alignment response
+fn parse_align_response(response: &str) -> Result<Vec<SourceQuote>> {
+    parse_json_array(response, "quote list")
+}
+
+/// Parse the outermost JSON array in a response, ignoring text around it
+fn parse_json_array<T: DeserializeOwned>(response: &str, what: &str) -> Result<Vec<T>> {
+    let Some((start, end)) = response
+        .find('[')
+        .zip(response.rfind(']'))
+        .filter(|(start, end)| start < end)
+    else {
+        bail!("No {} in response", what);
+    };
+    serde_json::from_str(&response[start..=end])
+        .map_err(|e| anyhow!("Invalid {} in response: {}", what, e))
+}
+
 /// Claude SDK fallback provider
 ///
 /// Uses Claude models via the claude-agent-sdk-rs crate to convert
 /// prose to AISP when deterministic conversion has low confidence.
 pub struct ClaudeFallback {
     model: String,
+    pools: Option<Arc<WarmPools>>,
 }
 
 impl Default for ClaudeFallback {
@@ -372,15 +507,14 @@ impl Default for ClaudeFallback {
 impl ClaudeFallback {
     /// Create new Claude fallback with default model (haiku for speed)
     pub fn new() -> Self {
-        Self {
-            model: "haiku".to_string(),
-        }
+        Self::with_model("haiku")
     }
 
     /// Create with specific model
     pub fn with_model(model: impl Into<String>) -> Self {
         Self {
             model: model.into(),
+            pools: None,
         }
     }
 
@@ -398,81 +532,433 @@ impl ClaudeFallback {
     pub fn opus() -> Self {
         Self::with_model("opus")
     }
-}
 
-#[async_trait]
-impl LlmProvider for ClaudeFallback {
-    async fn convert(
+    /// Keep up to `size` warm sessions per prompt style for reuse across
+    /// conversions, instead of starting the CLI for every request
+    ///
+    /// Meant for batch jobs that keep one provider for many conversions.
+    /// Providers returned by `for_model`, such as the rungs of a model
+    /// ladder, share the pools, with sessions of their own model. Agentic
+    /// requests still start their own session.
+    pub fn with_pool(mut self, size: usize) -> Self {
+        self.pools = Some(Arc::new(WarmPools {
+            size,
+            models: Mutex::new(HashMap::new()),
+        }));
+        self
+    }
+
+    /// Start the pooled sessions for a prompt style ahead of the first request
+    pub async fn warm_up(&self, use_aisp_prompt: bool) -> Result<()> {
+        match &self.pools {
+            Some(pools) => {
+                pools
+                    .model(&self.model)
+                    .get(use_aisp_prompt)
+                    .warm_up()
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Session reuse so far across every model sharing the pools, if pooled
+    pub fn pool_stats(&self) -> Option<PoolStats> {
+        self.pools.as_ref().map(|pools| {
+            pools
+                .all()
+                .iter()
+                .map(|model| model.aisp.stats() + model.english.stats())
+                .fold(PoolStats::default(), |total, stats| total + stats)
+        })
+    }
+
+    /// Shut down the idle pooled sessions of every model sharing the pools
+    pub async fn shutdown(&self) {
+        for model in self.pools.iter().flat_map(|pools| pools.all()) {
+            model.aisp.close().await;
+            model.english.close().await;
+        }
+    }
+
+    /// Run a single-turn query and return the text response with its usage
+    async fn query(&self, user_prompt: &str, use_aisp_prompt: bool) -> Result<Reply> {
+        if let Some(pools) = &self.pools {
+            let pools = pools.model(&self.model);
+            return pools.get(use_aisp_prompt).ask(user_prompt).await;
+        }
+        let options = minimal_options(&self.model, use_aisp_prompt);
+        let messages = claude_agent_sdk_rs::query(user_prompt, Some(options)).await?;
+        Ok(response_text(messages))
+    }
+
+    /// Run a query over several turns with the validation tools available
+    ///
+    /// In-process MCP servers need the SDK's bidirectional client rather
+    /// than a one-shot `query`.
+    async fn query_with_tools(
         &self,
-        prose: &str,
-        tier: ConversionTier,
-        unmapped: &[String],
-        partial_output: Option<&str>,
+        user_prompt: &str,
         use_aisp_prompt: bool,
-    ) -> Result<LlmResult> {
+        tools: &ValidationTools,
+    ) -> Result<Reply> {
         use claude_agent_sdk_rs::{
-            query, ClaudeAgentOptions, ContentBlock, McpServers, Message, PermissionMode,
-            SettingSource,
+            create_sdk_mcp_server, ClaudeAgentOptions, ClaudeClient, McpServerConfig, McpServers,
+            PermissionMode, SdkMcpTool, SettingSource,
         };
-        use std::collections::HashMap;
-
-        let user_prompt = create_user_prompt(prose, tier, unmapped, partial_output);
-
-        // Build extra args for minimal CLI invocation
-        let mut extra_args: HashMap<String, Option<String>> = HashMap::new();
-        extra_args.insert("no-chrome".to_string(), None);
-        extra_args.insert("no-session-persistence".to_string(), None);
-        extra_args.insert("disable-slash-commands".to_string(), None);
-        extra_args.insert("strict-mcp-config".to_string(), None);
-
-        // Configure minimal Claude instance - no plugins, no MCP, no settings
+        use futures::TryStreamExt;
+
+        let definitions = tools.definitions();
+        let allowed: Vec<String> = definitions
+            .iter()
+            .map(|d| format!("mcp__{}__{}", TOOL_SERVER, d.name))
+            .collect();
+        let sdk_tools = definitions
+            .into_iter()
+            .map(|d| SdkMcpTool {
+                name: d.name.to_string(),
+                description: d.description.to_string(),
+                input_schema: d.input_schema,
+                handler: Arc::new(ToolBridge {
+                    tools: *tools,
+                    name: d.name,
+                }),
+            })
+            .collect();
+        let server = create_sdk_mcp_server(TOOL_SERVER, env!("CARGO_PKG_VERSION"), sdk_tools);
+        let mut servers = HashMap::new();
+        servers.insert(TOOL_SERVER.to_string(), McpServerConfig::Sdk(server));
+
+        // As in `query`, but with the validation tools as the only tools
         let options = ClaudeAgentOptions::builder()
             .model(&self.model)
             .system_prompt(system_prompt(use_aisp_prompt).to_string())
-            .max_turns(1) // Single turn for conversion
+            .max_turns(AGENTIC_MAX_TURNS)
             .permission_mode(PermissionMode::BypassPermissions)
-            .tools(Vec::<String>::new()) // No tools needed
-            .mcp_servers(McpServers::Empty) // No MCP servers
-            .setting_sources(Vec::<SettingSource>::new()) // No filesystem settings
-            .plugins(Vec::new()) // No plugins
-            .skip_version_check(true) // Skip version check for speed
-            .fork_session(true) // Fresh session, no history loading
-            .extra_args(extra_args) // Minimal CLI flags
+            .tools(Vec::<String>::new()) // No built-in tools
+            .allowed_tools(allowed)
+            .mcp_servers(McpServers::Dict(servers))
+            .setting_sources(Vec::<SettingSource>::new())
+            .plugins(Vec::new())
+            .skip_version_check(true)
+            .fork_session(true)
+            .extra_args(minimal_cli_args())
             .build();
 
-        let messages = query(&user_prompt, Some(options)).await?;
+        let mut client = ClaudeClient::new(options);
+        client.connect().await?;
+        let messages: Result<Vec<_>> = async {
+            client.query(user_prompt).await?;
+            let messages = client.receive_response().try_collect().await?;
+            Ok(messages)
+        }
+        .await;
+        // Disconnect before returning an error too, so a failed exchange
+        // doesn't leave the CLI process running
+        let disconnected = client.disconnect().await;
+        let messages = messages?;
+        disconnected?;
+        Ok(response_text(messages))
+    }
+}
 
-        // Extract text response
-        let mut output = String::new();
-        let mut tokens_used = None;
+/// Configure minimal Claude instance - no plugins, no MCP, no settings
+fn minimal_options(model: &str, use_aisp_prompt: bool) -> claude_agent_sdk_rs::ClaudeAgentOptions {
+    use claude_agent_sdk_rs::{ClaudeAgentOptions, McpServers, PermissionMode, SettingSource};
+
+    ClaudeAgentOptions::builder()
+        .model(model)
+        .system_prompt(system_prompt(use_aisp_prompt).to_string())
+        .max_turns(1) // Single turn for conversion
+        .permission_mode(PermissionMode::BypassPermissions)
+        .tools(Vec::<String>::new()) // No tools needed
+        .mcp_servers(McpServers::Empty) // No MCP servers
+        .setting_sources(Vec::<SettingSource>::new()) // No filesystem settings
+        .plugins(Vec::new()) // No plugins
+        .skip_version_check(true) // Skip version check for speed
+        .fork_session(true) // Fresh session, no history loading
+        .extra_args(minimal_cli_args()) // Minimal CLI flags
+        .build()
+}
+
+/// Warm session pools of every model, created as models are first used
+struct WarmPools {
+    /// Sessions per pool
+    size: usize,
+    models: Mutex<HashMap<String, Arc<ModelPools>>>,
+}
 
-        for message in messages {
-            match message {
-                Message::Assistant(msg) => {
-                    for block in msg.message.content {
-                        if let ContentBlock::Text(text) = block {
-                            output.push_str(&text.text);
-                        }
+impl WarmPools {
+    /// Pools of `model`, created empty if it has none yet
+    fn model(&self, model: &str) -> Arc<ModelPools> {
+        let mut models = self.models.lock().expect("pool map poisoned");
+        let pools = models.entry(model.to_string()).or_insert_with(|| {
+            let pool = |use_aisp_prompt| {
+                let factory = ClaudeSessions {
+                    model: model.to_string(),
+                    use_aisp_prompt,
+                };
+                SessionPool::new(factory, self.size)
+            };
+            Arc::new(ModelPools {
+                aisp: pool(true),
+                english: pool(false),
+            })
+        });
+        pools.clone()
+    }
+
+    fn all(&self) -> Vec<Arc<ModelPools>> {
+        let models = self.models.lock().expect("pool map poisoned");
+        models.values().cloned().collect()
+    }
+}
+
+/// Warm session pools of one model, one per system prompt
+struct ModelPools {
+    aisp: SessionPool<ClaudeSessions>,
+    english: SessionPool<ClaudeSessions>,
+}
+
+impl ModelPools {
+    fn get(&self, use_aisp_prompt: bool) -> &SessionPool<ClaudeSessions> {
+        if use_aisp_prompt {
+            &self.aisp
+        } else {
+            &self.english
+        }
+    }
+}
+
+/// Starts interactive Claude clients with the conversion system prompt
+struct ClaudeSessions {
+    model: String,
+    use_aisp_prompt: bool,
+}
+
+#[async_trait]
+impl SessionFactory for ClaudeSessions {
+    type Session = ClaudeSession;
+
+    async fn start(&self) -> Result<ClaudeSession> {
+        let options = minimal_options(&self.model, self.use_aisp_prompt);
+        let mut client = claude_agent_sdk_rs::ClaudeClient::new(options);
+        client.connect().await?;
+        Ok(ClaudeSession {
+            client,
+            requests: 0,
+        })
+    }
+}
+
+/// A connected Claude client answering conversions one at a time
+struct ClaudeSession {
+    client: claude_agent_sdk_rs::ClaudeClient,
+    requests: usize,
+}
+
+#[async_trait]
+impl WarmSession for ClaudeSession {
+    async fn ask(&mut self, prompt: &str) -> Result<Reply> {
+        use futures::TryStreamExt;
+
+        // A new session id per request keeps earlier conversions out of context
+        self.requests += 1;
+        let session_id = format!("rosetta-{}", self.requests);
+        self.client.query_with_session(prompt, session_id).await?;
+        let messages: Vec<_> = self.client.receive_response().try_collect().await?;
+        Ok(response_text(messages))
+    }
+
+    async fn close(&mut self) {
+        let _ = self.client.disconnect().await;
+    }
+}
+
+/// Most turns an agentic conversion may take, tool calls included
+const AGENTIC_MAX_TURNS: u32 = 6;
+
+/// Name of the in-process MCP server serving the validation tools
+const TOOL_SERVER: &str = "aisp";
+
+/// Extra args for a minimal CLI invocation
+fn minimal_cli_args() -> std::collections::HashMap<String, Option<String>> {
+    [
+        "no-chrome",
+        "no-session-persistence",
+        "disable-slash-commands",
+        "strict-mcp-config",
+    ]
+    .into_iter()
+    .map(|arg| (arg.to_string(), None))
+    .collect()
+}
+
+/// Extract the final text response, a token estimate and the cost
+///
+/// Text written before the last tool call is the model thinking aloud, so
+/// only the text after it is kept.
+fn response_text(messages: Vec<claude_agent_sdk_rs::Message>) -> Reply {
+    use claude_agent_sdk_rs::{ContentBlock, Message};
+
+    let mut output = String::new();
+    let mut tokens_used = None;
+    let mut cost_usd = None;
+
+    for message in messages {
+        match message {
+            Message::Assistant(msg) => {
+                for block in msg.message.content {
+                    match block {
+                        ContentBlock::Text(text) => output.push_str(&text.text),
+                        ContentBlock::ToolUse(_) => output.clear(),
+                        _ => {}
                     }
                 }
-                Message::Result(result) => {
-                    if let Some(cost) = result.total_cost_usd {
-                        // Rough token estimate from cost
-                        tokens_used = Some((cost * 100000.0) as usize);
-                    }
+            }
+            Message::Result(result) => {
+                if let Some(cost) = result.total_cost_usd {
+                    // Rough token estimate from cost
+                    tokens_used = Some((cost * 100000.0) as usize);
+                    cost_usd = Some(cost);
                 }
-                _ => {}
             }
+            _ => {}
         }
+    }
+
+    Reply {
+        text: output.trim().to_string(),
+        tokens_used,
+        cost_usd,
+    }
+}
+
+/// Serves one of the [`ValidationTools`] from the SDK's in-process MCP server
+struct ToolBridge {
+    tools: ValidationTools,
+    name: &'static str,
+}
+
+impl claude_agent_sdk_rs::ToolHandler for ToolBridge {
+    fn handle(
+        &self,
+        args: serde_json::Value,
+    ) -> futures::future::BoxFuture<
+        'static,
+        claude_agent_sdk_rs::Result<claude_agent_sdk_rs::ToolResult>,
+    > {
+        use claude_agent_sdk_rs::{McpToolResultContent, ToolResult};
+
+        // Tool errors go back to the model rather than failing the query
+        let (text, is_error) = match self.tools.call(self.name, &args) {
+            Ok(text) => (text, false),
+            Err(e) => (e.to_string(), true),
+        };
+        Box::pin(async move {
+            Ok(ToolResult {
+                content: vec![McpToolResultContent::Text { text }],
+                is_error,
+            })
+        })
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ClaudeFallback {
+    async fn convert(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        let user_prompt = create_user_prompt(prose, tier, unmapped, partial_output);
+        let reply = self.query(&user_prompt, use_aisp_prompt).await?;
 
         Ok(LlmResult {
-            output: output.trim().to_string(),
+            output: reply.text,
             provider: "claude".to_string(),
             model: self.model.clone(),
-            tokens_used,
+            tokens_used: reply.tokens_used,
+            cost_usd: reply.cost_usd,
         })
     }
 
+    async fn convert_structured(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<Option<LlmResult>> {
+        let user_prompt = create_structured_prompt(prose, tier, unmapped, partial_output);
+        let reply = self.query(&user_prompt, use_aisp_prompt).await?;
+
+        Ok(Some(LlmResult {
+            output: reply.text,
+            provider: "claude".to_string(),
+            model: self.model.clone(),
+            tokens_used: reply.tokens_used,
+            cost_usd: reply.cost_usd,
+        }))
+    }
+
+    async fn convert_agentic(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+        tools: &ValidationTools,
+    ) -> Result<Option<LlmResult>> {
+        let user_prompt = create_agentic_prompt(prose, tier, unmapped, partial_output);
+        let reply = self
+            .query_with_tools(&user_prompt, use_aisp_prompt, tools)
+            .await?;
+
+        Ok(Some(LlmResult {
+            output: reply.text,
+            provider: "claude".to_string(),
+            model: self.model.clone(),
+            tokens_used: reply.tokens_used,
+            cost_usd: reply.cost_usd,
+        }))
+    }
+
+    async fn convert_spans(
+        &self,
+        spans: &[SpanRequest],
+        use_aisp_prompt: bool,
+    ) -> Result<Vec<String>> {
+        // All spans go out in a single request
+        let reply = self
+            .query(&create_span_prompt(spans), use_aisp_prompt)
+            .await?;
+        parse_span_response(&reply.text, spans.len())
+    }
+
+    async fn clarify(&self, prose: &str) -> Result<Vec<ClarificationQuestion>> {
+        let reply = self.query(&create_clarify_prompt(prose), false).await?;
+        parse_clarify_response(&reply.text)
+    }
+
+    async fn align(&self, prose: &str, aisp: &str) -> Result<Vec<SourceQuote>> {
+        let reply = self.query(&create_align_prompt(prose, aisp), false).await?;
+        parse_align_response(&reply.text)
+    }
+
+    fn for_model(&self, model: &str) -> Option<Box<dyn LlmProvider>> {
+        // Shares the warm pools, so rungs of a ladder don't start cold
+        Some(Box::new(Self {
+            model: model.to_string(),
+            pools: self.pools.clone(),
+        }))
+    }
+
     async fn is_available(&self) -> bool {
         // Check if Claude Code CLI is available
         std::process::Command::new("claude")
@@ -526,4 +1012,52 @@ mod tests {
         assert!(prompt.contains("foo"));
         assert!(prompt.contains("bar"));
     }
+
+    #[test]
+    fn test_span_prompt_numbers_spans() {
+        let spans = vec![
+            SpanRequest {
+                text: "entanglement".to_string(),
+                context: "quantum entanglement manifests".to_string(),
+            },
+            SpanRequest {
+                text: "correlation".to_string(),
+                context: "probabilistic correlation".to_string(),
+            },
+        ];
+        let prompt = create_span_prompt(&spans);
+        assert!(prompt.contains("1. span: \"entanglement\""));
+        assert!(prompt.contains("2. span: \"correlation\""));
+    }
+
+    #[test]
+    fn test_parse_span_response() {
+        let response = "1: `⊗`\n2. : ∝\nsome chatter";
+        let translations = parse_span_response(response, 2).unwrap();
+        assert_eq!(translations, vec!["⊗".to_string(), "∝".to_string()]);
+
+        assert!(parse_span_response("1: ⊗", 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_clarify_response() {
+        let response = "Here you go:\n[{\"text\": \"it\", \"question\": \"What is it?\", \
+                        \"interpretations\": [\"the file\", \"the folder\"]}]";
+        let questions = parse_clarify_response(response).unwrap();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].text, "it");
+        assert_eq!(questions[0].kind, None);
+        assert_eq!(questions[0].interpretations, vec!["the file", "the folder"]);
+
+        assert!(parse_clarify_response("[]").unwrap().is_empty());
+        assert!(parse_clarify_response("no ambiguity").is_err());
+    }
+
+    #[test]
+    fn test_parse_align_response() {
+        let response = "```json\n[{\"statement\": \"x≜5\", \"source\": [\"Define x as 5\"]}]\n```";
+        let quotes = parse_align_response(response).unwrap();
+        assert_eq!(quotes[0].statement, "x≜5");
+        assert_eq!(quotes[0].source, vec!["Define x as 5"]);
+    }
 }
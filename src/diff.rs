@@ -0,0 +1,605 @@
+//! Structural Diff
+//!
+//! Compares two AISP documents at the AST level. Statements are matched by
+//! what they define (definitions by name, rules by premise) and compared in
+//! a canonical form, so formatting differences and renamed bound variables
+//! (`λx.x` vs `λy.y`, `∀x∈S:P(x)` vs `∀s∈S:P(s)`) are not reported.
+
+use crate::ast::{Block, Delim, Document, Evidence, Expr, ExprKind, Span, Stmt, StmtKind};
+use crate::formatter::normalize;
+use crate::parser::parse;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How an item differs between the documents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Only in the second document
+    Added,
+    /// Only in the first document
+    Removed,
+    /// In both, with different content
+    Changed,
+}
+
+/// What kind of item changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    /// The `𝔸` header
+    Header,
+    /// The `γ≔` context
+    Context,
+    /// A whole block
+    Block,
+    /// A `≜` or `≔` definition
+    Definition,
+    /// A `⇒` rule
+    Rule,
+    /// Any other statement
+    Statement,
+    /// An `⟦Ε⟧` entry
+    Evidence,
+}
+
+/// A single difference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    /// Block label such as `Λ:Funcs`, `Ε` for evidence, empty for the
+    /// document preamble
+    pub section: String,
+    /// What kind of item changed
+    pub item: ItemKind,
+    /// Defined name, rule premise or evidence field
+    pub name: String,
+    /// How it changed
+    pub kind: ChangeKind,
+    /// Source text in the first document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Source text in the second document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Differences between two documents, in document order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentDiff {
+    /// Every change found
+    pub changes: Vec<Change>,
+}
+
+impl DocumentDiff {
+    /// Whether the documents are structurally equal
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Compare two AISP documents structurally
+pub fn diff(before: &str, after: &str) -> DocumentDiff {
+    let old = Side::new(before);
+    let new = Side::new(after);
+    let mut changes = Vec::new();
+
+    let header = |side: &Side| side.doc.header.as_ref().map(|h| side.text(h.span));
+    let context = |side: &Side| side.doc.context.as_ref().map(|c| c.id.clone());
+    for (item, old_value, new_value) in [
+        (ItemKind::Header, header(&old), header(&new)),
+        (ItemKind::Context, context(&old), context(&new)),
+    ] {
+        if let Some(kind) = change_kind(old_value.as_ref(), new_value.as_ref()) {
+            changes.push(Change {
+                section: String::new(),
+                item,
+                name: String::new(),
+                kind,
+                before: old_value,
+                after: new_value,
+            });
+        }
+    }
+
+    let old_claims: Vec<&Stmt> = old.doc.claims.iter().collect();
+    let new_claims: Vec<&Stmt> = new.doc.claims.iter().collect();
+    changes.extend(statements("", &old, &old_claims, &new, &new_claims));
+
+    let old_blocks = blocks_by_label(&old.doc);
+    let new_blocks = blocks_by_label(&new.doc);
+    let labels = ordered_union(
+        old_blocks.iter().map(|(label, _)| label.clone()),
+        new_blocks.iter().map(|(label, _)| label.clone()),
+    );
+    for label in labels {
+        let in_old = old_blocks.iter().any(|(l, _)| *l == label);
+        let in_new = new_blocks.iter().any(|(l, _)| *l == label);
+        if in_old != in_new {
+            changes.push(Change {
+                section: label.clone(),
+                item: ItemKind::Block,
+                name: format!("⟦{}⟧", label),
+                kind: if in_new {
+                    ChangeKind::Added
+                } else {
+                    ChangeKind::Removed
+                },
+                before: None,
+                after: None,
+            });
+            continue;
+        }
+        let (old_stmts, new_stmts) = (find(&old_blocks, &label), find(&new_blocks, &label));
+        changes.extend(statements(&label, &old, &old_stmts, &new, &new_stmts));
+    }
+
+    changes.extend(evidence(
+        &old,
+        old.doc.evidence.as_ref(),
+        &new,
+        new.doc.evidence.as_ref(),
+    ));
+    DocumentDiff { changes }
+}
+
+/// A parsed document with its source
+struct Side<'a> {
+    source: &'a str,
+    doc: Document,
+}
+
+impl<'a> Side<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            doc: parse(source).document,
+        }
+    }
+
+    fn text(&self, span: Span) -> String {
+        normalize(self.source, span, true)
+    }
+}
+
+fn change_kind<T: PartialEq>(before: Option<&T>, after: Option<&T>) -> Option<ChangeKind> {
+    match (before, after) {
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (Some(a), Some(b)) if a != b => Some(ChangeKind::Changed),
+        _ => None,
+    }
+}
+
+/// Blocks grouped by `Cat:Name` label, in first-seen order
+fn blocks_by_label(doc: &Document) -> Vec<(String, Vec<&Block>)> {
+    let mut grouped: Vec<(String, Vec<&Block>)> = Vec::new();
+    for block in &doc.blocks {
+        let label = match &block.name {
+            Some(name) => format!("{}:{}", block.category, name),
+            None => block.category.clone(),
+        };
+        match grouped.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, blocks)) => blocks.push(block),
+            None => grouped.push((label, vec![block])),
+        }
+    }
+    grouped
+}
+
+/// Statements of every block with `label`
+fn find<'a>(blocks: &[(String, Vec<&'a Block>)], label: &str) -> Vec<&'a Stmt> {
+    blocks
+        .iter()
+        .filter(|(l, _)| l == label)
+        .flat_map(|(_, blocks)| blocks.iter().flat_map(|b| &b.body))
+        .collect()
+}
+
+/// Keys of `a` in order, then keys only in `b`
+fn ordered_union(a: impl Iterator<Item = String>, b: impl Iterator<Item = String>) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for key in a.chain(b) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// A statement reduced to a matching key and a canonical value
+struct Keyed {
+    item: ItemKind,
+    /// Canonical form of what the statement defines or states
+    key: String,
+    /// Canonical form of the rest
+    value: String,
+    /// Name shown in the change
+    name: String,
+    /// Normalized source text
+    text: String,
+}
+
+fn keyed(side: &Side, stmt: &Stmt) -> Option<Keyed> {
+    let text = side.text(stmt.span);
+    let (item, key, value, name) = match &stmt.kind {
+        StmtKind::Def { target, op, value } => {
+            // Parameters of `f(x)≜…` are bound in the value
+            let (key, params) = match &target.kind {
+                ExprKind::Apply { func, args } => (
+                    canonical(func, &mut Vec::new()),
+                    args.iter().flat_map(binder_vars).collect(),
+                ),
+                _ => (canonical(target, &mut Vec::new()), Vec::new()),
+            };
+            let arity = params.len();
+            let mut env = params;
+            let value = format!("{}/{}{}", arity, op, canonical(value, &mut env));
+            (ItemKind::Definition, key.clone(), value, key)
+        }
+        StmtKind::Rule {
+            premise,
+            consequent,
+        } => (
+            ItemKind::Rule,
+            canonical(premise, &mut Vec::new()),
+            canonical(consequent, &mut Vec::new()),
+            side.text(premise.span),
+        ),
+        StmtKind::Expr(expr) => match quantified_rule(expr, &mut Vec::new()) {
+            // `∀x∈S:P(x)⇒Q(x)` is a rule keyed by its binder and premise
+            Some((key, value, premise)) => (
+                ItemKind::Rule,
+                key,
+                value,
+                side.text(Span::new(expr.span.start, premise.span.end)),
+            ),
+            None => {
+                let canonical = canonical(expr, &mut Vec::new());
+                (
+                    ItemKind::Statement,
+                    canonical.clone(),
+                    canonical,
+                    text.clone(),
+                )
+            }
+        },
+        StmtKind::Error(raw) => (ItemKind::Statement, raw.clone(), raw.clone(), text.clone()),
+        StmtKind::Comment(_) => return None,
+    };
+    Some(Keyed {
+        item,
+        key,
+        value,
+        name,
+        text,
+    })
+}
+
+fn statements(
+    section: &str,
+    old: &Side,
+    old_stmts: &[&Stmt],
+    new: &Side,
+    new_stmts: &[&Stmt],
+) -> Vec<Change> {
+    let old_keyed: Vec<Keyed> = old_stmts.iter().filter_map(|s| keyed(old, s)).collect();
+    let mut new_keyed: Vec<Option<Keyed>> = new_stmts
+        .iter()
+        .map(|s| keyed(new, s))
+        .filter(Option::is_some)
+        .collect();
+
+    let change = |keyed: &Keyed, kind, before, after| Change {
+        section: section.to_string(),
+        item: keyed.item,
+        name: keyed.name.clone(),
+        kind,
+        before,
+        after,
+    };
+
+    let mut changes = Vec::new();
+    for old_item in &old_keyed {
+        // Pair with the first unmatched statement of the same kind and key
+        let matched = new_keyed.iter_mut().find(|n| {
+            n.as_ref()
+                .is_some_and(|n| n.item == old_item.item && n.key == old_item.key)
+        });
+        match matched.and_then(Option::take) {
+            Some(new_item) if new_item.value != old_item.value => changes.push(change(
+                old_item,
+                ChangeKind::Changed,
+                Some(old_item.text.clone()),
+                Some(new_item.text),
+            )),
+            Some(_) => {}
+            None => changes.push(change(
+                old_item,
+                ChangeKind::Removed,
+                Some(old_item.text.clone()),
+                None,
+            )),
+        }
+    }
+    for new_item in new_keyed.into_iter().flatten() {
+        changes.push(change(
+            &new_item,
+            ChangeKind::Added,
+            None,
+            Some(new_item.text.clone()),
+        ));
+    }
+    changes
+}
+
+fn evidence(
+    old: &Side,
+    old_evidence: Option<&Evidence>,
+    new: &Side,
+    new_evidence: Option<&Evidence>,
+) -> Vec<Change> {
+    // Named entries are matched by name, claims by their text
+    let entries = |side: &Side, evidence: Option<&Evidence>| -> HashMap<String, (String, String)> {
+        evidence
+            .iter()
+            .flat_map(|e| &e.entries)
+            .map(|entry| {
+                let text = side.text(entry.span);
+                match &entry.name {
+                    Some(name) => (name.clone(), (normalize_value(&entry.value), text)),
+                    None => (text.clone(), (String::new(), text)),
+                }
+            })
+            .collect()
+    };
+    let old_entries = entries(old, old_evidence);
+    let new_entries = entries(new, new_evidence);
+    let order = |side: &Side, evidence: Option<&Evidence>| -> Vec<String> {
+        evidence
+            .iter()
+            .flat_map(|e| &e.entries)
+            .map(|entry| entry.name.clone().unwrap_or_else(|| side.text(entry.span)))
+            .collect::<Vec<_>>()
+    };
+    let names = ordered_union(
+        order(old, old_evidence).into_iter(),
+        order(new, new_evidence).into_iter(),
+    );
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (before, after) = (old_entries.get(&name), new_entries.get(&name));
+            let value = |entry: Option<&(String, String)>| entry.map(|(value, _)| value.clone());
+            let kind = change_kind(value(before).as_ref(), value(after).as_ref())?;
+            Some(Change {
+                section: "Ε".to_string(),
+                item: ItemKind::Evidence,
+                before: before.map(|(_, text)| text.clone()),
+                after: after.map(|(_, text)| text.clone()),
+                name,
+                kind,
+            })
+        })
+        .collect()
+}
+
+fn normalize_value(value: &str) -> String {
+    value.split_whitespace().collect()
+}
+
+/// Variables bound by a quantifier binder or parameter: `x` in `x∈S`,
+/// `x` and `y` in `x,y:T`
+fn binder_vars(binder: &Expr) -> Vec<String> {
+    match &binder.kind {
+        ExprKind::Ident(name) => vec![name.clone()],
+        ExprKind::Binary { op, lhs, rhs } if op == "," => {
+            let mut vars = binder_vars(lhs);
+            vars.extend(binder_vars(rhs));
+            vars
+        }
+        ExprKind::Binary { op, lhs, .. }
+            if matches!(
+                op.as_str(),
+                "∈" | "∉" | "⊆" | "⊂" | ":" | "<" | "≤" | ">" | "≥"
+            ) =>
+        {
+            binder_vars(lhs)
+        }
+        ExprKind::Group { items, .. } => items.iter().flat_map(binder_vars).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Canonical premise and consequent of a quantified rule such as
+/// `∀x∈S:P(x)⇒Q(x)`, along with the premise expression
+///
+/// The premise includes the quantifiers and binders, so rules over
+/// different sets don't match.
+fn quantified_rule<'e>(
+    expr: &'e Expr,
+    env: &mut Vec<String>,
+) -> Option<(String, String, &'e Expr)> {
+    let ExprKind::Quant {
+        quantifier,
+        binder,
+        body,
+    } = &expr.kind
+    else {
+        return None;
+    };
+
+    let depth = env.len();
+    env.extend(binder_vars(binder));
+    let binder = canonical(binder, env);
+    let rule = match &body.kind {
+        ExprKind::Binary { op, lhs, rhs } if op == "⇒" => {
+            Some((canonical(lhs, env), canonical(rhs, env), lhs.as_ref()))
+        }
+        _ => quantified_rule(body, env),
+    };
+    env.truncate(depth);
+
+    let (premise, consequent, premise_expr) = rule?;
+    Some((
+        format!("{}{}:{}", quantifier, binder, premise),
+        consequent,
+        premise_expr,
+    ))
+}
+
+/// Canonical text of an expression
+///
+/// Binary operations are fully parenthesized and redundant parentheses
+/// dropped, so grouping is explicit regardless of how it was written. Bound
+/// variables are replaced by their binding depth (`#0`, `#1`), so renaming
+/// them doesn't change the result. `env` holds the variables in scope.
+fn canonical(expr: &Expr, env: &mut Vec<String>) -> String {
+    let list = |items: &[Expr], env: &mut Vec<String>| -> String {
+        items
+            .iter()
+            .map(|item| canonical(item, env))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    match &expr.kind {
+        ExprKind::Ident(name) => match env.iter().rposition(|bound| bound == name) {
+            Some(depth) => format!("#{}", depth),
+            None => name.clone(),
+        },
+        ExprKind::Number(text) | ExprKind::Text(text) | ExprKind::Symbol(text) => text.clone(),
+        ExprKind::Unary { op, operand } => format!("{}{}", op, canonical(operand, env)),
+        ExprKind::Postfix { op, operand } => format!("{}{}", canonical(operand, env), op),
+        ExprKind::Binary { op, lhs, rhs } => {
+            format!("({}{}{})", canonical(lhs, env), op, canonical(rhs, env))
+        }
+        ExprKind::Lambda { params, body } => {
+            let depth = env.len();
+            env.extend(params.iter().cloned());
+            let params: Vec<String> = (depth..env.len()).map(|i| format!("#{}", i)).collect();
+            let body = canonical(body, env);
+            env.truncate(depth);
+            format!("λ{}.{}", params.join(" "), body)
+        }
+        ExprKind::Quant {
+            quantifier,
+            binder,
+            body,
+        } => {
+            let depth = env.len();
+            env.extend(binder_vars(binder));
+            let binder = canonical(binder, env);
+            let body = canonical(body, env);
+            env.truncate(depth);
+            format!("{}{}:{}", quantifier, binder, body)
+        }
+        ExprKind::Apply { func, args } => format!("{}({})", canonical(func, env), list(args, env)),
+        ExprKind::Index { base, args } => format!("{}[{}]", canonical(base, env), list(args, env)),
+        ExprKind::Juxtapose { func, arg } => {
+            format!("({} {})", canonical(func, env), canonical(arg, env))
+        }
+        ExprKind::Group { delim, items } => match (delim, items.as_slice()) {
+            (Delim::Paren, [inner]) => canonical(inner, env),
+            _ => {
+                let (open, close) = delim.glyphs();
+                format!("{}{}{}", open, list(items, env), close)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "𝔸5.1.x@2026-01-09
+γ≔x
+⟦Σ:Types⟧{T≜⟨a,b⟩}
+⟦Γ:Rules⟧{
+  ∀u∈Users:Auth(u)⇒Allow(u)
+  Admin(u)⇒Allow(u)
+}
+⟦Λ:Funcs⟧{
+  f≜λx.x+1
+  g(n)≜n*2
+  h≜λx.x
+}
+⟦Ε⟧⟨δ≜0.50;τ≜◊;⊢valid⟩";
+
+    #[test]
+    fn test_formatting_and_renaming_are_ignored() {
+        let after = "𝔸5.1.x@2026-01-09\nγ≔x\n\
+                     ⟦Σ:Types⟧{ T ≜ ⟨a, b⟩ }\n\
+                     ⟦Γ:Rules⟧{ ∀v∈Users:Auth(v)⇒Allow(v); Admin(u)⇒Allow(u) }\n\
+                     ⟦Λ:Funcs⟧{ f≜λy.(y+1); g(m)≜m*2; h≜λz.z }\n\
+                     ⟦Ε⟧⟨δ≜0.50; τ≜◊; ⊢valid⟩";
+        let diff = diff(BEFORE, after);
+        assert!(diff.is_empty(), "unexpected changes: {:?}", diff.changes);
+    }
+
+    #[test]
+    fn test_changes_per_block() {
+        let after = "𝔸5.1.x@2026-01-10
+γ≔x
+⟦Γ:Rules⟧{
+  ∀u∈Users:Auth(u)⇒Allow(u)
+  Admin(u)⇒Deny(u)
+}
+⟦Λ:Funcs⟧{
+  f≜λx.x+2
+  h≜λx.x
+  k≜λx.f(x)
+}
+⟦Χ:Errors⟧{ε≜⊥}
+⟦Ε⟧⟨δ≜0.60;τ≜◊;⊢valid;∎⟩";
+        let diff = diff(BEFORE, after);
+        let summary: Vec<(ItemKind, &str, ChangeKind)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.item, c.name.as_str(), c.kind))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (ItemKind::Header, "", ChangeKind::Changed),
+                (ItemKind::Block, "⟦Σ:Types⟧", ChangeKind::Removed),
+                (ItemKind::Rule, "Admin(u)", ChangeKind::Changed),
+                (ItemKind::Definition, "f", ChangeKind::Changed),
+                (ItemKind::Definition, "g", ChangeKind::Removed),
+                (ItemKind::Definition, "k", ChangeKind::Added),
+                (ItemKind::Block, "⟦Χ:Errors⟧", ChangeKind::Added),
+                (ItemKind::Evidence, "δ", ChangeKind::Changed),
+                (ItemKind::Evidence, "∎", ChangeKind::Added),
+            ]
+        );
+        let f = &diff.changes[3];
+        assert_eq!(f.section, "Λ:Funcs");
+        assert_eq!(f.before.as_deref(), Some("f≜λx.x+1"));
+        assert_eq!(f.after.as_deref(), Some("f≜λx.x+2"));
+        assert_eq!(diff.changes[7].after.as_deref(), Some("δ≜0.60"));
+    }
+
+    #[test]
+    fn test_quantified_rules_are_keyed_by_premise() {
+        let diff = diff(
+            "⟦Γ:Rules⟧{∀x∈S:P(x)⇒Q(x); ∀x∈T:P(x)⇒Q(x)}",
+            "⟦Γ:Rules⟧{∀y∈S:P(y)⇒R(y); ∀x∈T:P(x)⇒Q(x)}",
+        );
+        assert_eq!(diff.changes.len(), 1);
+        let change = &diff.changes[0];
+        assert_eq!(
+            (change.item, change.kind),
+            (ItemKind::Rule, ChangeKind::Changed)
+        );
+        assert_eq!(change.name, "∀x∈S:P(x)");
+        assert_eq!(change.after.as_deref(), Some("∀y∈S:P(y)⇒R(y)"));
+    }
+
+    #[test]
+    fn test_free_variables_are_compared_by_name() {
+        let diff = diff("⟦Λ:Funcs⟧{f≜λx.y}", "⟦Λ:Funcs⟧{f≜λx.z}");
+        assert_eq!(diff.changes.len(), 1);
+    }
+}
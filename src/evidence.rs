@@ -0,0 +1,215 @@
+//! Evidence Verification
+//!
+//! The `⟦Ε⟧` block is a self-certification: `⟦Ε⟧⟨δ≜0.82;φ≜100;τ≜◊⁺⁺⟩`.
+//! Models tend to copy the example values regardless of content, so this
+//! module recomputes δ, φ and τ from the document body, reports claims that
+//! don't match, and can rewrite the block with the true values.
+
+use crate::ast::Span;
+use crate::parser::parse;
+use crate::validator::{validate, QualityTier};
+use serde::{Deserialize, Serialize};
+
+/// Largest difference between claimed and recomputed δ that still matches
+///
+/// Claims are usually written with two decimals.
+pub const DENSITY_TOLERANCE: f64 = 0.05;
+
+/// A claimed evidence value that doesn't match the document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceMismatch {
+    /// Evidence field, `δ`, `φ` or `τ`
+    pub field: String,
+    /// Value as claimed in the block
+    pub claimed: String,
+    /// Value recomputed from the document
+    pub actual: String,
+    /// Span of the claiming entry
+    pub span: Span,
+}
+
+/// Recomputed evidence and how the document's claims compare
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceReport {
+    /// Whether the document has an evidence block
+    pub present: bool,
+    /// δ recomputed from the document without its evidence block and comments
+    pub density: f64,
+    /// φ recomputed from the blocks present
+    pub completeness: u8,
+    /// τ recomputed from δ and well-formedness
+    pub tier: QualityTier,
+    /// Claims that don't match the recomputed values
+    pub mismatches: Vec<EvidenceMismatch>,
+}
+
+impl EvidenceReport {
+    /// Whether the evidence block exists and every claim in it holds
+    pub fn is_accurate(&self) -> bool {
+        self.present && self.mismatches.is_empty()
+    }
+}
+
+/// Recompute δ, φ and τ and compare them with the `⟦Ε⟧` claims
+///
+/// Fields that are absent from the block are not reported as mismatches.
+pub fn verify_evidence(source: &str) -> EvidenceReport {
+    let parsed = parse(source);
+    let evidence = parsed.document.evidence.as_ref();
+    let (density, completeness, tier) = recompute(source, evidence.is_some());
+
+    let mut mismatches = Vec::new();
+    for entry in evidence.iter().flat_map(|e| &e.entries) {
+        let actual = match entry.name.as_deref() {
+            Some("δ") => {
+                let matches = entry
+                    .value
+                    .parse::<f64>()
+                    .is_ok_and(|claimed| (claimed - density).abs() <= DENSITY_TOLERANCE);
+                (!matches).then(|| format!("{:.2}", density))
+            }
+            Some("φ") => {
+                let matches = entry.value.parse::<u8>() == Ok(completeness);
+                (!matches).then(|| completeness.to_string())
+            }
+            Some("τ") => {
+                let matches = QualityTier::from_glyph(&entry.value) == Some(tier);
+                (!matches).then(|| tier.to_string())
+            }
+            _ => None,
+        };
+
+        if let (Some(actual), Some(field)) = (actual, &entry.name) {
+            mismatches.push(EvidenceMismatch {
+                field: field.clone(),
+                claimed: entry.value.clone(),
+                actual,
+                span: entry.span,
+            });
+        }
+    }
+
+    EvidenceReport {
+        present: evidence.is_some(),
+        density,
+        completeness,
+        tier,
+        mismatches,
+    }
+}
+
+/// Replace the `⟦Ε⟧` block with one carrying the recomputed δ, φ and τ
+///
+/// Other entries such as `⊢valid` are kept after the recomputed ones, except
+/// that `⊢` claims are dropped when τ is `⊘`, since a rejected document
+/// proves nothing. A document without an evidence block gets one appended.
+pub fn rewrite_evidence(source: &str) -> String {
+    let parsed = parse(source);
+    let evidence = parsed.document.evidence.as_ref();
+    let (density, completeness, tier) = recompute(source, evidence.is_some());
+
+    let mut entries = vec![
+        format!("δ≜{:.2}", density),
+        format!("φ≜{}", completeness),
+        format!("τ≜{}", tier),
+    ];
+    entries.extend(
+        evidence
+            .iter()
+            .flat_map(|e| &e.entries)
+            .filter(|e| !matches!(e.name.as_deref(), Some("δ" | "φ" | "τ")))
+            .filter(|e| tier != QualityTier::Reject || !e.value.starts_with('⊢'))
+            .map(|e| e.span.text(source).to_string()),
+    );
+    let block = format!("⟦Ε⟧⟨{}⟩", entries.join(";"));
+
+    match evidence {
+        Some(evidence) => format!(
+            "{}{}{}",
+            &source[..evidence.span.start],
+            block,
+            &source[evidence.span.end..]
+        ),
+        None => format!("{}\n{}", source.trim_end(), block),
+    }
+}
+
+/// δ, φ and τ of `source`, as `validate` computes them
+fn recompute(source: &str, has_evidence: bool) -> (f64, u8, QualityTier) {
+    // φ and well-formedness credit the evidence block as it will be once
+    // rewritten, so a missing block doesn't lower its own claims. δ leaves
+    // the evidence block out either way.
+    let validation = if has_evidence {
+        validate(source)
+    } else {
+        validate(&format!("{}\n⟦Ε⟧⟨⟩", source.trim_end()))
+    };
+    (validation.density, validation.completeness, validation.tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "𝔸5.1.x@2026-01-09
+⟦Ω:Meta⟧{∀x∈S:⊤}
+⟦Σ:Types⟧{T≜⟨⟩}
+⟦Γ:Rules⟧{∀x:P⇒Q}
+⟦Λ:Funcs⟧{f≜λx.x}";
+
+    #[test]
+    fn test_inflated_claims_are_flagged() {
+        let source = format!("{}\n⟦Ε⟧⟨δ≜0.99;φ≜100;τ≜◊⁺⁺;⊢valid⟩", BODY);
+        let report = verify_evidence(&source);
+
+        assert!(report.present);
+        assert_eq!(report.completeness, 100);
+        let fields: Vec<&str> = report.mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["δ", "τ"]);
+        assert_eq!(report.mismatches[0].span.text(&source), "δ≜0.99");
+        assert!(!report.is_accurate());
+    }
+
+    #[test]
+    fn test_rewrite_makes_claims_accurate() {
+        let source = format!(";; header comment\n{}\n⟦Ε⟧⟨δ≜0.99;τ≜◊⁺⁺;⊢valid;∎⟩\n", BODY);
+        let rewritten = rewrite_evidence(&source);
+
+        assert!(rewritten.starts_with(";; header comment\n"));
+        assert!(rewritten.ends_with(";⊢valid;∎⟩\n"));
+        assert!(verify_evidence(&rewritten).is_accurate());
+        assert_eq!(
+            verify_evidence(&rewritten).density,
+            verify_evidence(&source).density
+        );
+    }
+
+    #[test]
+    fn test_missing_evidence_is_appended() {
+        let report = verify_evidence(BODY);
+        assert!(!report.present);
+        assert!(!report.is_accurate());
+        assert_eq!(report.completeness, 100);
+
+        let rewritten = rewrite_evidence(BODY);
+        assert!(rewritten.starts_with(BODY));
+        assert!(verify_evidence(&rewritten).is_accurate());
+    }
+
+    #[test]
+    fn test_rewrite_agrees_with_validate() {
+        let source = "𝔸5.1.x@2026-01-09
+;; ⟦⟧⟦⟧⟦⟧ ∀∀∀∀ ⇒⇒⇒⇒
+⟦Ω:Meta⟧{the document describes a system}
+⟦Λ:Funcs⟧{run the system every day}
+⟦Ε⟧⟨δ≜0.90;τ≜◊⁺⁺;⊢valid;⊢sound;∎⟩";
+        let rewritten = rewrite_evidence(source);
+        let validation = validate(&rewritten);
+
+        assert_eq!(validation.tier, QualityTier::Reject);
+        assert_eq!(verify_evidence(&rewritten).density, validation.density);
+        assert!(rewritten.contains(&format!("δ≜{:.2};", validation.density)));
+        assert!(rewritten.ends_with("τ≜⊘;∎⟩"));
+        assert!(verify_evidence(&rewritten).is_accurate());
+    }
+}
@@ -0,0 +1,322 @@
+//! Canonical Formatting
+//!
+//! Prints an AISP document in a canonical layout: header, context and
+//! references first, then the blocks in `Doc≜𝔸≫CTX?≫⟦Ω⟧≫⟦Σ⟧≫⟦Γ⟧≫⟦Λ⟧≫⟦Χ⟧?≫⟦Ε⟧`
+//! order and the evidence block last. Statements go one per line, or all on
+//! one line in the compact layout, with whitespace removed wherever the parse
+//! doesn't depend on it.
+
+use crate::ast::{Comment, Delim, Document, Span, Stmt, StmtKind};
+use crate::lexer::{tokenize, Token, TokenKind};
+use crate::parser::{infix_bp, parse, prefix_bp, ParseError, BINDERS};
+use crate::repair::BLOCK_ORDER;
+
+/// Layout produced by [`format_aisp`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatStyle {
+    /// One statement per line, block bodies indented, blank lines between blocks
+    #[default]
+    Pretty,
+    /// The whole document on a single line, sections separated by spaces and
+    /// statements by `;`, comments dropped
+    Compact,
+}
+
+/// Format an AISP document
+///
+/// Documents with parse errors are not formatted, since the parts that
+/// failed to parse could be lost; the errors are returned instead.
+pub fn format_aisp(source: &str, style: FormatStyle) -> Result<String, Vec<ParseError>> {
+    let parsed = parse(source);
+    if !parsed.is_ok() {
+        return Err(parsed.errors);
+    }
+
+    let printer = Printer { source, style };
+    Ok(printer.document(&parsed.document))
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    style: FormatStyle,
+}
+
+impl Printer<'_> {
+    fn pretty(&self) -> bool {
+        self.style == FormatStyle::Pretty
+    }
+
+    fn normalize(&self, span: Span) -> String {
+        normalize(self.source, span, !self.pretty())
+    }
+
+    fn document(&self, doc: &Document) -> String {
+        // Sections are separated by a blank line in pretty mode
+        let mut sections: Vec<Vec<String>> = Vec::new();
+
+        let mut preamble = Vec::new();
+        if let Some(header) = &doc.header {
+            preamble.push(self.normalize(header.span));
+        }
+        if let Some(context) = &doc.context {
+            preamble.push(self.normalize(context.span));
+        }
+        if let Some(references) = &doc.references {
+            preamble.push(self.normalize(references.span));
+        }
+        if self.pretty() {
+            preamble.extend(doc.claims.iter().map(|stmt| self.stmt(stmt)));
+        } else if !doc.claims.is_empty() {
+            preamble.push(self.stmts(&doc.claims));
+        }
+        sections.push(preamble);
+
+        // Stable, so blocks of one category keep their relative order
+        let mut blocks: Vec<_> = doc.blocks.iter().collect();
+        blocks.sort_by_key(|block| rank(&block.category));
+
+        for block in blocks {
+            let mut lines = self.comments(&block.leading_comments);
+            let label = match &block.name {
+                Some(name) => format!("⟦{}:{}⟧", block.category, name),
+                None => format!("⟦{}⟧", block.category),
+            };
+            if !self.pretty() {
+                lines.push(format!("{}{{{}}}", label, self.stmts(&block.body)));
+            } else if block.body.is_empty() {
+                lines.push(format!("{}{{}}", label));
+            } else {
+                lines.push(format!("{}{{", label));
+                lines.extend(
+                    block
+                        .body
+                        .iter()
+                        .map(|stmt| format!("  {}", self.stmt(stmt))),
+                );
+                lines.push("}".to_string());
+            }
+            sections.push(lines);
+        }
+
+        if let Some(evidence) = &doc.evidence {
+            let mut lines = self.comments(&evidence.leading_comments);
+            let entries: Vec<String> = evidence
+                .entries
+                .iter()
+                .map(|entry| self.normalize(entry.span))
+                .collect();
+            lines.push(format!("⟦Ε⟧⟨{}⟩", entries.join(";")));
+            sections.push(lines);
+        }
+
+        sections.push(self.comments(&doc.comments));
+
+        let (separator, line_break) = if self.pretty() {
+            ("\n\n", "\n")
+        } else {
+            (" ", " ")
+        };
+        let mut output = sections
+            .into_iter()
+            .filter(|lines| !lines.is_empty())
+            .map(|lines| lines.join(line_break))
+            .collect::<Vec<_>>()
+            .join(separator);
+        output.push('\n');
+        output
+    }
+
+    /// A statement on its own line, with its trailing comment
+    fn stmt(&self, stmt: &Stmt) -> String {
+        let text = match &stmt.kind {
+            StmtKind::Comment(comment) => return comment_line(comment),
+            _ => self.normalize(stmt.span),
+        };
+        match &stmt.comment {
+            Some(comment) => format!("{} {}", text, comment_line(comment)),
+            None => text,
+        }
+    }
+
+    /// Statements joined with `;`, without comments
+    fn stmts(&self, stmts: &[Stmt]) -> String {
+        stmts
+            .iter()
+            .filter(|stmt| !matches!(stmt.kind, StmtKind::Comment(_)))
+            .map(|stmt| self.normalize(stmt.span))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    fn comments(&self, comments: &[Comment]) -> Vec<String> {
+        if !self.pretty() {
+            return Vec::new();
+        }
+        comments.iter().map(comment_line).collect()
+    }
+}
+
+fn comment_line(comment: &Comment) -> String {
+    if comment.text.is_empty() {
+        ";;".to_string()
+    } else {
+        format!(";; {}", comment.text)
+    }
+}
+
+/// Position of a block category in document order; unknown categories
+/// come after `⟦Χ⟧`
+fn rank(category: &str) -> usize {
+    BLOCK_ORDER
+        .iter()
+        .position(|(c, _)| *c == category)
+        .unwrap_or(BLOCK_ORDER.len() - 1)
+}
+
+/// The source text of `span` on one line with redundant whitespace removed
+///
+/// Whitespace is kept only between words (`hd s`, `fix λf`), where the
+/// tokens would otherwise merge (`∃ !x`) or where they would parse
+/// differently (`f (x)` is juxtaposition, `f(x)` application).
+/// Comments inside the text would swallow the rest of the line, so the text
+/// is returned as written unless `drop_comments` is set.
+pub(crate) fn normalize(source: &str, span: Span, drop_comments: bool) -> String {
+    let text = span.text(source);
+    let tokens = tokenize(text);
+    if !drop_comments && tokens.iter().any(|t| t.kind == TokenKind::Comment) {
+        return text.to_string();
+    }
+    let tokens: Vec<Token> = tokens
+        .into_iter()
+        .filter(|t| !matches!(t.kind, TokenKind::Newline | TokenKind::Comment))
+        .collect();
+
+    let join = |keep: &dyn Fn(&Token, &Token) -> bool| {
+        let mut output = String::with_capacity(text.len());
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 {
+                let prev = &tokens[i - 1];
+                if token.span.start > prev.span.end && keep(prev, token) {
+                    output.push(' ');
+                }
+            }
+            output.push_str(token.text);
+        }
+        output
+    };
+
+    let compact = join(&|prev, next| {
+        let merged = format!("{}{}", prev.text, next.text);
+        ((next.is("(") || next.is("[")) && ends_operand(prev))
+            || (is_word(prev) && (is_word(next) || next.is("λ")))
+            || !same_tokens(&merged, &[prev, next])
+    });
+    if same_tokens(&compact, &tokens.iter().collect::<Vec<_>>()) {
+        compact
+    } else {
+        // Removing whitespace merged tokens across more than two of them
+        join(&|_, _| true)
+    }
+}
+
+fn is_word(token: &Token) -> bool {
+    matches!(
+        token.kind,
+        TokenKind::Ident | TokenKind::Number | TokenKind::Text
+    )
+}
+
+/// Whether `token` can end an operand, so that a `(` or `[` after it
+/// applies (`f(x)`) when touching and juxtaposes (`f (x)`) otherwise
+fn ends_operand(token: &Token) -> bool {
+    let t = token.text;
+    let operator = infix_bp(t).is_some()
+        || prefix_bp(t).is_some()
+        || BINDERS.contains(&t)
+        || t == "λ"
+        || t == ";"
+        || t.chars().next().and_then(Delim::from_open).is_some();
+    // `|` and `‖` close their group as well as open it
+    token.kind != TokenKind::Symbol || t == "|" || t == "‖" || !operator
+}
+
+/// Whether `text` tokenizes to exactly the texts of `tokens`
+fn same_tokens(text: &str, tokens: &[&Token]) -> bool {
+    let retokenized = tokenize(text);
+    retokenized.len() == tokens.len()
+        && retokenized
+            .iter()
+            .zip(tokens)
+            .all(|(a, b)| a.text == b.text && a.kind == b.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let check = |source: &str| normalize(source, Span::new(0, source.len()), false);
+        assert_eq!(check("x ≜ 5"), "x≜5");
+        assert_eq!(check("∀x ∈ S : hd s"), "∀x∈S:hd s");
+        assert_eq!(check("f (x) ∧ g(y)"), "f (x)∧g(y)");
+        assert_eq!(check("a ∧ (b ∨ c)"), "a∧(b∨c)");
+        assert_eq!(check("⟨a,\n   b⟩"), "⟨a,b⟩");
+        assert_eq!(check("∃ !x"), "∃ !x");
+        assert_eq!(check("⟨a, ;; first\n b⟩"), "⟨a, ;; first\n b⟩");
+        assert_eq!(
+            normalize("⟨a, ;; first\n b⟩", Span::new(0, 20), true),
+            "⟨a,b⟩"
+        );
+    }
+
+    #[test]
+    fn test_format_canonical_layout() {
+        let source = "γ≔x\n𝔸5.1.x@2026-01-09\n⟦Λ:Funcs⟧{ f ≜ λx. x ; g≜f }\n;; types\n\
+                      ⟦Σ:Types⟧{T≜⟨⟩}\n⟦Ε⟧⟨δ≜0.5; τ≜◊⟩\n⟦Ω:Meta⟧{ ⊤ ;; always\n}";
+        let pretty = format_aisp(source, FormatStyle::Pretty).unwrap();
+        assert_eq!(
+            pretty,
+            "𝔸5.1.x@2026-01-09\nγ≔x\n\n⟦Ω:Meta⟧{\n  ⊤ ;; always\n}\n\n;; types\n⟦Σ:Types⟧{\n  T≜⟨⟩\n}\n\n\
+             ⟦Λ:Funcs⟧{\n  f≜λx.x\n  g≜f\n}\n\n⟦Ε⟧⟨δ≜0.5;τ≜◊⟩\n"
+        );
+
+        let compact = format_aisp(source, FormatStyle::Compact).unwrap();
+        assert_eq!(
+            compact,
+            "𝔸5.1.x@2026-01-09 γ≔x ⟦Ω:Meta⟧{⊤} ⟦Σ:Types⟧{T≜⟨⟩} ⟦Λ:Funcs⟧{f≜λx.x;g≜f} ⟦Ε⟧⟨δ≜0.5;τ≜◊⟩\n"
+        );
+        // The single line reads back as the same document, less its comments
+        let uncommented = pretty.replace(" ;; always", "").replace(";; types\n", "");
+        assert_eq!(
+            format_aisp(&compact, FormatStyle::Pretty).unwrap(),
+            uncommented
+        );
+        assert_eq!(
+            format_aisp("x ≜ 5\ny ≜ x", FormatStyle::Compact).unwrap(),
+            "x≜5;y≜x\n"
+        );
+    }
+
+    #[test]
+    fn test_format_rejects_parse_errors() {
+        let errors = format_aisp("⟦Λ:Funcs⟧{f≜(x", FormatStyle::Pretty).unwrap_err();
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_format_specification_is_stable() {
+        let source = &crate::claude::AISP_PROMPT;
+        for style in [FormatStyle::Pretty, FormatStyle::Compact] {
+            let formatted = format_aisp(source, style).unwrap();
+            assert_eq!(format_aisp(&formatted, style).unwrap(), formatted);
+
+            let (before, after) = (parse(source).document, parse(&formatted).document);
+            assert_eq!(after.blocks.len(), before.blocks.len());
+            assert_eq!(
+                after.evidence.map(|e| e.entries.len()),
+                before.evidence.map(|e| e.entries.len())
+            );
+        }
+    }
+}
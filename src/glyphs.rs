@@ -0,0 +1,263 @@
+//! Glyph Whitelist
+//!
+//! Classifies the glyphs of an AISP document against the `Σ_512` glossary
+//! and the `rosetta_aisp` category tables, so symbols the model invented
+//! can be reported instead of silently passed on.
+
+use crate::ast::Span;
+use crate::lexer::{tokenize, TokenKind};
+use once_cell::sync::Lazy;
+use rosetta_aisp::{get_all_categories, symbols_by_category};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Glossary categories from `⟦Σ:Glossary⟧`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphCategory {
+    /// `Ω`: transform, derive, prove
+    Transmuter,
+    /// `Γ`: structure, shape, relation
+    Topologic,
+    /// `∀`: scope, range, extent
+    Quantifier,
+    /// `Δ`: binding, state, contract
+    Contractor,
+    /// `𝔻`: domains and primitive types
+    Domain,
+    /// `⟦⟧`: block and document markers
+    Block,
+    /// `∅`: pocket and signal operators
+    Operator,
+    /// Glyphs the specification uses in its own rules outside the glossary
+    Notation,
+}
+
+/// Glossary entries per category, in `⟦Σ:Glossary⟧` order
+///
+/// A glyph listed in several categories is classified by the first one.
+const GLOSSARY: &[(GlyphCategory, &[&str])] = &[
+    (
+        GlyphCategory::Transmuter,
+        &[
+            "⊤", "⊥", "∧", "∨", "¬", "→", "↔", "⇒", "⇐", "⇔", "⊢", "⊨", "⊬", "⊭", "≡", "≢", "≜",
+            "≔", "↦", "←", "≈", "∼", "≅", "≃", "∝", "≪", "≫", "∘", "·", "×", "λ", "Λ", "μ", "ν",
+            "∎", "□", "◇", "⊣", "⊸", "π",
+        ],
+    ),
+    (
+        GlyphCategory::Topologic,
+        &[
+            "∈", "∉", "∋", "∌", "⊂", "⊃", "⊆", "⊇", "⊄", "⊅", "∩", "∪", "∖", "△", "∅", "𝒫", "℘",
+            "ℵ", "ω", "Ω", "ε", "δ", "ι", "κ", "τ", "θ", "φ", "ψ", "χ", "𝔾", "𝕍", "𝔼", "ℰ", "𝒩",
+            "ℋ", "ℳ", "ℛ", "𝔹", "𝕊", "𝕋", "𝕌", "𝕎", "𝔸", "𝔻", "𝔽", "⟨", "⟩", "⟦", "⟧", "⟪", "⟫",
+            "⌈", "⌉", "⌊", "⌋", "‖",
+        ],
+    ),
+    (
+        GlyphCategory::Quantifier,
+        &[
+            "∀",
+            "∃",
+            "∃!",
+            "∄",
+            "⋀",
+            "⋁",
+            "⋂",
+            "⋃",
+            "Σ",
+            "Π",
+            "∏",
+            "∐",
+            "⨁",
+            "⨂",
+            "⨀",
+            "↣",
+            "↠",
+            "⤳",
+            "⊕",
+            "⊗",
+            "⊖",
+            "⊘",
+            "⊙",
+            "⊛",
+            "◊",
+            "◊⁺⁺",
+            "◊⁺",
+            "◊⁻",
+        ],
+    ),
+    (GlyphCategory::Contractor, &["Δ"]),
+    (GlyphCategory::Domain, &["ℝ", "ℕ", "ℤ", "ℚ", "ℂ"]),
+    (
+        GlyphCategory::Block,
+        &["Ω", "Σ", "Γ", "Λ", "Χ", "Ε", "ℭ", "ℜ", "Θ", "ℑ"],
+    ),
+    (
+        GlyphCategory::Operator,
+        &["⊞", "✂", "Φ", "⧺", "∂", "σ", "∇"],
+    ),
+    (
+        GlyphCategory::Notation,
+        &[
+            "≤", "≥", "≠", "≺", "≻", "±", "÷", "¼", "½", "¾", "⅕", "⅖", "⅗", "⅘", "↓", "↑", "∴",
+            "∵", "Ψ", "ℙ", "𝔄", "𝔅", "𝕄", "γ", "ρ", "⁺", "⁻", "′",
+        ],
+    ),
+];
+
+static SPEC_GLYPHS: Lazy<HashMap<&'static str, GlyphCategory>> = Lazy::new(|| {
+    let mut glyphs = HashMap::new();
+    for (category, entries) in GLOSSARY {
+        for glyph in *entries {
+            glyphs.entry(*glyph).or_insert(*category);
+        }
+    }
+    glyphs
+});
+
+static ROSETTA_GLYPHS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut glyphs = HashMap::new();
+    for category in get_all_categories() {
+        for symbol in symbols_by_category(category) {
+            glyphs.entry(symbol).or_insert(category);
+        }
+    }
+    glyphs
+});
+
+/// How a glyph relates to the specification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphClass {
+    /// Listed in the `Σ_512` glossary
+    Spec(GlyphCategory),
+    /// Not in the glossary, but known to a `rosetta_aisp` category
+    Rosetta(String),
+    /// Not known at all
+    Unknown,
+}
+
+/// A glyph occurrence that is not in the glossary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlyphIssue {
+    /// The glyph as written
+    pub glyph: String,
+    /// Classification; never [`GlyphClass::Spec`]
+    pub class: GlyphClass,
+    /// Source span
+    pub span: Span,
+}
+
+/// Classify a single glyph
+pub fn classify(glyph: &str) -> GlyphClass {
+    if let Some(category) = SPEC_GLYPHS.get(glyph) {
+        return GlyphClass::Spec(*category);
+    }
+    match ROSETTA_GLYPHS.get(glyph) {
+        Some(category) => GlyphClass::Rosetta(category.to_string()),
+        None => GlyphClass::Unknown,
+    }
+}
+
+/// Report every glyph in `source` that is not in the glossary
+///
+/// Glyphs are the non-ASCII symbol tokens plus capital Greek, letterlike and
+/// mathematical alphanumeric characters inside names (`𝔸`, `ℕ`, `Σ_512`).
+/// Quoted text and comments are skipped.
+pub fn check_glyphs(source: &str) -> Vec<GlyphIssue> {
+    let mut issues = Vec::new();
+    let mut check = |glyph: &str, span: Span| {
+        let class = classify(glyph);
+        if !matches!(class, GlyphClass::Spec(_)) {
+            issues.push(GlyphIssue {
+                glyph: glyph.to_string(),
+                class,
+                span,
+            });
+        }
+    };
+
+    for token in tokenize(source) {
+        match token.kind {
+            TokenKind::Symbol if !token.text.is_ascii() => {
+                // Multi-character glyphs such as `∃!` are checked whole
+                if token.text.chars().count() > 1 && classify(token.text) != GlyphClass::Unknown {
+                    check(token.text, token.span);
+                    continue;
+                }
+                for (offset, c) in token.text.char_indices() {
+                    if !c.is_ascii() {
+                        let start = token.span.start + offset;
+                        check(
+                            c.encode_utf8(&mut [0; 4]),
+                            Span::new(start, start + c.len_utf8()),
+                        );
+                    }
+                }
+            }
+            TokenKind::Ident => {
+                for (offset, c) in token.text.char_indices().filter(|(_, c)| is_name_glyph(*c)) {
+                    let start = token.span.start + offset;
+                    check(
+                        c.encode_utf8(&mut [0; 4]),
+                        Span::new(start, start + c.len_utf8()),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    issues
+}
+
+/// Whether only glossary or `rosetta_aisp` glyphs are used
+pub(crate) fn known_glyphs_only(source: &str) -> bool {
+    check_glyphs(source)
+        .iter()
+        .all(|issue| issue.class != GlyphClass::Unknown)
+}
+
+/// Characters inside names that count as glyphs rather than letters
+///
+/// Lowercase Greek is left out: it is commonly used for variable names.
+fn is_name_glyph(c: char) -> bool {
+    matches!(c,
+        '\u{0391}'..='\u{03A9}'        // Greek capitals
+        | '\u{2100}'..='\u{214F}'      // Letterlike symbols (ℕ, ℝ, ℭ)
+        | '\u{1D400}'..='\u{1D7FF}'    // Mathematical alphanumerics (𝔸, 𝕊)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("∀"), GlyphClass::Spec(GlyphCategory::Quantifier));
+        assert_eq!(classify("→"), GlyphClass::Spec(GlyphCategory::Transmuter));
+        assert_eq!(classify("◊⁺⁺"), GlyphClass::Spec(GlyphCategory::Quantifier));
+        assert_eq!(classify("ℕ"), GlyphClass::Spec(GlyphCategory::Domain));
+        assert_eq!(classify("⋈"), GlyphClass::Unknown);
+    }
+
+    #[test]
+    fn test_check_glyphs_reports_positions() {
+        let source = "∀x∈ℕ:x⋈y\nT≜𝕏×ℤ ;; ⋈ in comments is fine";
+        let issues = check_glyphs(source);
+
+        let glyphs: Vec<&str> = issues.iter().map(|i| i.glyph.as_str()).collect();
+        assert_eq!(glyphs, vec!["⋈", "𝕏"]);
+        assert_eq!(issues[0].span.line_col(source), (1, 7));
+        assert_eq!(issues[1].span.line_col(source), (2, 3));
+        assert!(!known_glyphs_only(source));
+    }
+
+    #[test]
+    fn test_specification_prompt_uses_known_glyphs() {
+        let issues = check_glyphs(&crate::claude::AISP_PROMPT);
+        assert!(issues.is_empty(), "unexpected glyphs: {:?}", issues);
+    }
+}
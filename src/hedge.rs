@@ -0,0 +1,285 @@
+//! Hedged Requests
+//!
+//! A few LLM requests take many times longer than the typical one. A
+//! [`HedgedProvider`] sends the request to a second provider or model when
+//! the first hasn't answered after a delay, and returns whichever answer
+//! passes validation first. The slower request is cancelled.
+
+use crate::alignment::SourceQuote;
+use crate::clarify::ClarificationQuestion;
+use crate::glyphs;
+use crate::provider::{LlmProvider, LlmResult, SpanRequest};
+use crate::structured::StructuredDocument;
+use crate::tools::ValidationTools;
+use crate::validator::validate;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::{select, BoxFuture, Either};
+use rosetta_aisp::ConversionTier;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// How the requests of a hedged provider were answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct HedgeStats {
+    /// Requests sent to the primary provider
+    pub requests: usize,
+    /// Requests also sent to the hedge provider
+    pub hedged: usize,
+    /// Requests answered by the primary provider
+    pub primary_wins: usize,
+    /// Requests answered by the hedge provider
+    pub hedge_wins: usize,
+    /// Requests where neither answer passed validation
+    pub failed: usize,
+}
+
+/// A provider's answer to a conversion request, `None` if unsupported
+type Answer = Result<Option<LlmResult>>;
+
+/// Which provider answered a request
+enum Winner {
+    Primary,
+    Hedge,
+    Neither,
+}
+
+/// A provider that hedges slow requests with a second provider
+///
+/// Conversions go to `primary` first. If it hasn't produced an output that
+/// passes validation after `delay`, or fails before then, the same request
+/// goes to `hedge`, and the first output to pass wins; the other request is
+/// dropped. When neither passes, the primary's answer is returned so the
+/// caller can still repair or reject it. The [`LlmResult::model`] of the
+/// answer tells which provider won.
+///
+/// Span translation, clarification and alignment go to `primary` only.
+pub struct HedgedProvider<P, H> {
+    primary: P,
+    hedge: H,
+    delay: Duration,
+    requests: AtomicUsize,
+    hedged: AtomicUsize,
+    primary_wins: AtomicUsize,
+    hedge_wins: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl<P: LlmProvider, H: LlmProvider> HedgedProvider<P, H> {
+    /// Hedge requests to `primary` with `hedge` once they take `delay`
+    pub fn new(primary: P, hedge: H, delay: Duration) -> Self {
+        Self {
+            primary,
+            hedge,
+            delay,
+            requests: AtomicUsize::new(0),
+            hedged: AtomicUsize::new(0),
+            primary_wins: AtomicUsize::new(0),
+            hedge_wins: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Requests answered so far
+    pub fn stats(&self) -> HedgeStats {
+        HedgeStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            hedged: self.hedged.load(Ordering::Relaxed),
+            primary_wins: self.primary_wins.load(Ordering::Relaxed),
+            hedge_wins: self.hedge_wins.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Race `primary` against `hedge`, started after the delay
+    ///
+    /// A primary answer of `None` means the provider lacks the capability,
+    /// and is returned without hedging or counting the request.
+    async fn race<'a>(
+        &self,
+        primary: BoxFuture<'a, Answer>,
+        hedge: impl FnOnce() -> BoxFuture<'a, Answer>,
+        accept: impl Fn(&str) -> bool,
+    ) -> Answer {
+        let passes = |answer: &Answer| matches!(answer, Ok(Some(r)) if accept(&r.output));
+
+        let timer = Box::pin(tokio::time::sleep(self.delay));
+        let (answer, winner) = match select(primary, timer).await {
+            Either::Left((Ok(None), _)) => return Ok(None),
+            Either::Left((answer, _)) if passes(&answer) => (answer, Winner::Primary),
+            Either::Left((rejected, _)) => {
+                self.hedged.fetch_add(1, Ordering::Relaxed);
+                let answer = hedge().await;
+                if passes(&answer) {
+                    (answer, Winner::Hedge)
+                } else {
+                    (neither(rejected, answer), Winner::Neither)
+                }
+            }
+            Either::Right((_, primary)) => {
+                self.hedged.fetch_add(1, Ordering::Relaxed);
+                match select(primary, hedge()).await {
+                    Either::Left((answer, _)) if passes(&answer) => (answer, Winner::Primary),
+                    Either::Right((answer, _)) if passes(&answer) => (answer, Winner::Hedge),
+                    Either::Left((rejected, hedge)) => {
+                        let answer = hedge.await;
+                        if passes(&answer) {
+                            (answer, Winner::Hedge)
+                        } else {
+                            (neither(rejected, answer), Winner::Neither)
+                        }
+                    }
+                    Either::Right((rejected, primary)) => {
+                        let answer = primary.await;
+                        if passes(&answer) {
+                            (answer, Winner::Primary)
+                        } else {
+                            (neither(answer, rejected), Winner::Neither)
+                        }
+                    }
+                }
+            }
+        };
+
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let counter = match winner {
+            Winner::Primary => &self.primary_wins,
+            Winner::Hedge => &self.hedge_wins,
+            Winner::Neither => &self.failed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        answer
+    }
+}
+
+/// The answer to return when neither passes: the primary's output if it
+/// has one, else the hedge's, else the primary's error
+fn neither(primary: Answer, hedge: Answer) -> Answer {
+    match (&primary, &hedge) {
+        (Ok(Some(_)), _) => primary,
+        (_, Ok(Some(_))) => hedge,
+        _ => primary,
+    }
+}
+
+/// Whether `output` passes validation at `tier` with known glyphs only
+fn valid_at(tier: ConversionTier) -> impl Fn(&str) -> bool {
+    move |output| validate(output).accepts(tier) && glyphs::known_glyphs_only(output)
+}
+
+#[async_trait]
+impl<P: LlmProvider, H: LlmProvider> LlmProvider for HedgedProvider<P, H> {
+    async fn convert(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        let answer = self
+            .race(
+                Box::pin(async move {
+                    self.primary
+                        .convert(prose, tier, unmapped, partial_output, use_aisp_prompt)
+                        .await
+                        .map(Some)
+                }),
+                || {
+                    Box::pin(async move {
+                        self.hedge
+                            .convert(prose, tier, unmapped, partial_output, use_aisp_prompt)
+                            .await
+                            .map(Some)
+                    })
+                },
+                valid_at(tier),
+            )
+            .await?;
+        Ok(answer.expect("plain conversions always answer"))
+    }
+
+    async fn convert_structured(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<Option<LlmResult>> {
+        let renders = move |output: &str| {
+            StructuredDocument::from_json(output)
+                .and_then(|document| document.to_aisp())
+                .is_ok_and(|aisp| valid_at(tier)(&aisp))
+        };
+        self.race(
+            self.primary
+                .convert_structured(prose, tier, unmapped, partial_output, use_aisp_prompt),
+            || {
+                self.hedge.convert_structured(
+                    prose,
+                    tier,
+                    unmapped,
+                    partial_output,
+                    use_aisp_prompt,
+                )
+            },
+            renders,
+        )
+        .await
+    }
+
+    async fn convert_agentic(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+        tools: &ValidationTools,
+    ) -> Result<Option<LlmResult>> {
+        self.race(
+            self.primary.convert_agentic(
+                prose,
+                tier,
+                unmapped,
+                partial_output,
+                use_aisp_prompt,
+                tools,
+            ),
+            || {
+                self.hedge.convert_agentic(
+                    prose,
+                    tier,
+                    unmapped,
+                    partial_output,
+                    use_aisp_prompt,
+                    tools,
+                )
+            },
+            valid_at(tier),
+        )
+        .await
+    }
+
+    async fn convert_spans(
+        &self,
+        spans: &[SpanRequest],
+        use_aisp_prompt: bool,
+    ) -> Result<Vec<String>> {
+        self.primary.convert_spans(spans, use_aisp_prompt).await
+    }
+
+    async fn clarify(&self, prose: &str) -> Result<Vec<ClarificationQuestion>> {
+        self.primary.clarify(prose).await
+    }
+
+    async fn align(&self, prose: &str, aisp: &str) -> Result<Vec<SourceQuote>> {
+        self.primary.align(prose, aisp).await
+    }
+
+    async fn is_available(&self) -> bool {
+        self.primary.is_available().await || self.hedge.is_available().await
+    }
+}
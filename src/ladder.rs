@@ -0,0 +1,252 @@
+//! Model Ladder
+//!
+//! Most prose that needs the LLM is handled well by the cheapest model. With
+//! a ladder such as haiku → sonnet → opus, each conversion starts on the
+//! first rung and only moves up when the output is rejected or scores too
+//! low, so the expensive models are paid for only where they are needed.
+
+use crate::alignment::SourceQuote;
+use crate::clarify::ClarificationQuestion;
+use crate::provider::{LlmProvider, LlmResult, SpanRequest};
+use crate::quality::QualityScore;
+use crate::tools::ValidationTools;
+use crate::{llm_convert, ConversionOptionsExt};
+use anyhow::Result;
+use async_trait::async_trait;
+use rosetta_aisp::{ConversionResult, ConversionTier};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Why a conversion moved up from a rung
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Escalation {
+    /// The prose is at the full tier, so the first rung was skipped
+    FullTier,
+    /// The request failed or its output was rejected by validation
+    Rejected,
+    /// The output scored below `escalation_quality`
+    LowQuality,
+}
+
+/// One model of the ladder and what it cost
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rung {
+    pub model: String,
+    /// LLM requests sent, including retries; 0 if the rung was skipped
+    pub requests: usize,
+    /// Tokens used, when the provider reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_used: Option<usize>,
+    /// Cost in US dollars, when the provider reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Time spent on the rung, in milliseconds
+    pub elapsed_ms: u64,
+    /// Total quality score of the accepted output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+    /// Why the next rung was tried, if it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<Escalation>,
+}
+
+/// Run the LLM part of the fallback up `opts.model_ladder`
+///
+/// Returns the result of the first rung whose output is accepted and scores
+/// at least `opts.escalation_quality`, or failing that the best-scoring
+/// accepted output, along with the rungs tried. Providers that can't switch
+/// models get a single plain attempt and no rungs.
+pub(crate) async fn convert(
+    prose: &str,
+    result: &ConversionResult,
+    opts: &ConversionOptionsExt,
+    provider: &dyn LlmProvider,
+) -> (Option<ConversionResult>, Vec<Rung>) {
+    let providers: Option<Vec<Box<dyn LlmProvider>>> = opts
+        .model_ladder
+        .iter()
+        .map(|model| provider.for_model(model))
+        .collect();
+    let Some(providers) = providers else {
+        return (llm_convert(prose, result, opts, provider).await, Vec::new());
+    };
+
+    let mut rungs = Vec::with_capacity(providers.len());
+    let mut best: Option<(f64, ConversionResult)> = None;
+    for (i, (model, provider)) in opts.model_ladder.iter().zip(&providers).enumerate() {
+        let last = i + 1 == providers.len();
+        if i == 0 && !last && result.tier == ConversionTier::Full {
+            rungs.push(Rung {
+                model: model.clone(),
+                requests: 0,
+                tokens_used: None,
+                cost_usd: None,
+                elapsed_ms: 0,
+                quality: None,
+                escalation: Some(Escalation::FullTier),
+            });
+            continue;
+        }
+
+        let metered = Metered::new(provider.as_ref());
+        let started = Instant::now();
+        let converted = llm_convert(prose, result, opts, &metered).await;
+        let mut rung = Rung {
+            model: model.clone(),
+            requests: metered.requests.load(Ordering::Relaxed),
+            tokens_used: metered.tokens_used(),
+            cost_usd: *metered.cost.lock().expect("cost lock poisoned"),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            quality: None,
+            escalation: None,
+        };
+
+        match converted {
+            None => rung.escalation = Some(Escalation::Rejected),
+            Some(converted) => {
+                let quality = QualityScore::compute(prose, &converted.output, converted.tier).total;
+                rung.quality = Some(quality);
+                if opts.escalation_quality.is_none_or(|min| quality >= min) {
+                    rungs.push(rung);
+                    return (Some(converted), rungs);
+                }
+                rung.escalation = Some(Escalation::LowQuality);
+                if best.as_ref().is_none_or(|(score, _)| quality > *score) {
+                    best = Some((quality, converted));
+                }
+            }
+        }
+        if last {
+            // Nothing to escalate to
+            rung.escalation = None;
+        }
+        rungs.push(rung);
+    }
+    (best.map(|(_, converted)| converted), rungs)
+}
+
+/// A provider that counts the requests, tokens and cost of one rung
+struct Metered<'a> {
+    inner: &'a dyn LlmProvider,
+    requests: AtomicUsize,
+    tokens: AtomicUsize,
+    /// Requests whose token count was reported
+    counted: AtomicUsize,
+    /// Total of the reported costs, `None` if none was reported
+    cost: Mutex<Option<f64>>,
+}
+
+impl<'a> Metered<'a> {
+    fn new(inner: &'a dyn LlmProvider) -> Self {
+        Self {
+            inner,
+            requests: AtomicUsize::new(0),
+            tokens: AtomicUsize::new(0),
+            counted: AtomicUsize::new(0),
+            cost: Mutex::new(None),
+        }
+    }
+
+    fn tokens_used(&self) -> Option<usize> {
+        (self.counted.load(Ordering::Relaxed) > 0).then(|| self.tokens.load(Ordering::Relaxed))
+    }
+
+    fn record(&self, result: Option<&LlmResult>) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if let Some(tokens) = result.and_then(|result| result.tokens_used) {
+            self.tokens.fetch_add(tokens, Ordering::Relaxed);
+            self.counted.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(cost) = result.and_then(|result| result.cost_usd) {
+            let mut total = self.cost.lock().expect("cost lock poisoned");
+            *total = Some(total.unwrap_or(0.0) + cost);
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Metered<'_> {
+    async fn convert(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        let result = self
+            .inner
+            .convert(prose, tier, unmapped, partial_output, use_aisp_prompt)
+            .await;
+        self.record(result.as_ref().ok());
+        result
+    }
+
+    async fn convert_structured(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<Option<LlmResult>> {
+        let result = self
+            .inner
+            .convert_structured(prose, tier, unmapped, partial_output, use_aisp_prompt)
+            .await;
+        if let Ok(Some(answer)) = &result {
+            self.record(Some(answer));
+        }
+        result
+    }
+
+    async fn convert_agentic(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+        tools: &ValidationTools,
+    ) -> Result<Option<LlmResult>> {
+        let result = self
+            .inner
+            .convert_agentic(
+                prose,
+                tier,
+                unmapped,
+                partial_output,
+                use_aisp_prompt,
+                tools,
+            )
+            .await;
+        if let Ok(Some(answer)) = &result {
+            self.record(Some(answer));
+        }
+        result
+    }
+
+    async fn convert_spans(
+        &self,
+        spans: &[SpanRequest],
+        use_aisp_prompt: bool,
+    ) -> Result<Vec<String>> {
+        self.record(None);
+        self.inner.convert_spans(spans, use_aisp_prompt).await
+    }
+
+    async fn clarify(&self, prose: &str) -> Result<Vec<ClarificationQuestion>> {
+        self.inner.clarify(prose).await
+    }
+
+    async fn align(&self, prose: &str, aisp: &str) -> Result<Vec<SourceQuote>> {
+        self.inner.align(prose, aisp).await
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+}
@@ -0,0 +1,184 @@
+//! AISP Lexer
+//!
+//! Splits AISP source into identifiers, numbers, quoted literals, glyphs,
+//! comments and newlines. Newlines are kept because they separate statements.
+
+use crate::ast::Span;
+
+/// Token categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    /// Word made of letters, digits, `_`, primes, sub/superscripts and combining marks
+    Ident,
+    /// ASCII digits with an optional fractional part
+    Number,
+    /// `"…"` or `'…'` on a single line
+    Text,
+    /// Any other glyph, possibly multi-character (`∃!`, `◊⁺⁺`)
+    Symbol,
+    /// `;;` up to the end of the line
+    Comment,
+    /// Line break
+    Newline,
+}
+
+/// A token with its source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+impl Token<'_> {
+    /// Whether this is the symbol `s`
+    pub fn is(&self, s: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == s
+    }
+}
+
+/// Glyphs that always form a token of their own, even next to letters
+const STANDALONE: &[char] = &['λ'];
+
+/// Tokenize AISP source
+pub(crate) fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            '\n' => TokenKind::Newline,
+            c if c.is_whitespace() => continue,
+            ';' if source[start + 1..].starts_with(';') => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+                TokenKind::Comment
+            }
+            '"' | '\'' => match source[start + 1..].find([c, '\n']) {
+                Some(len) if source[start + 1 + len..].starts_with(c) => {
+                    while chars.next_if(|&(i, _)| i <= start + 1 + len).is_some() {}
+                    TokenKind::Text
+                }
+                _ => TokenKind::Symbol,
+            },
+            c if c.is_ascii_digit() => {
+                while chars.next_if(|&(_, c)| c.is_ascii_digit()).is_some() {}
+                let rest = chars.peek().map_or(source.len(), |&(i, _)| i);
+                let mut after_dot = source[rest..].chars();
+                if after_dot.next() == Some('.')
+                    && after_dot.next().is_some_and(|c| c.is_ascii_digit())
+                {
+                    chars.next();
+                    while chars.next_if(|&(_, c)| c.is_ascii_digit()).is_some() {}
+                }
+                TokenKind::Number
+            }
+            'Π' | 'Σ'
+                if !chars
+                    .peek()
+                    .is_some_and(|&(_, c)| c == '_' || c.is_ascii_digit()) =>
+            {
+                // Binders, unless part of a name such as Σ_512
+                TokenKind::Symbol
+            }
+            c if is_word_start(c) => {
+                while chars.next_if(|&(_, c)| is_word_continue(c)).is_some() {}
+                TokenKind::Ident
+            }
+            '∃' if chars.peek().is_some_and(|&(_, c)| c == '!') => {
+                chars.next();
+                TokenKind::Symbol
+            }
+            '◊' => {
+                while chars.next_if(|&(_, c)| c == '⁺' || c == '⁻').is_some() {}
+                TokenKind::Symbol
+            }
+            _ => TokenKind::Symbol,
+        };
+
+        let end = chars.peek().map_or(source.len(), |&(i, _)| i);
+        tokens.push(Token {
+            kind,
+            text: &source[start..end],
+            span: Span::new(start, end),
+        });
+    }
+
+    tokens
+}
+
+fn is_word_start(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !STANDALONE.contains(&c)
+}
+
+fn is_word_continue(c: char) -> bool {
+    is_word_start(c) || c == '\'' || is_combining(c)
+}
+
+/// Combining marks such as the vector arrow in `a⃗`
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}' | '\u{20D0}'..='\u{20FF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds_and_text(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(source).iter().map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn test_tokenize_symbols_and_words() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds_and_text("∀x∈S:P(x)"),
+            vec![
+                (Symbol, "∀"),
+                (Ident, "x"),
+                (Symbol, "∈"),
+                (Ident, "S"),
+                (Symbol, ":"),
+                (Ident, "P"),
+                (Symbol, "("),
+                (Ident, "x"),
+                (Symbol, ")"),
+            ]
+        );
+        assert_eq!(
+            kinds_and_text("λf a⃗.T₁×T' ;; note\n"),
+            vec![
+                (Symbol, "λ"),
+                (Ident, "f"),
+                (Ident, "a⃗"),
+                (Symbol, "."),
+                (Ident, "T₁"),
+                (Symbol, "×"),
+                (Ident, "T'"),
+                (Comment, ";; note"),
+                (Newline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_special_forms() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds_and_text("∃!x τ≜◊⁺⁺ δ≜0.82 Σ_512 ΠΣ '𝔸'"),
+            vec![
+                (Symbol, "∃!"),
+                (Ident, "x"),
+                (Ident, "τ"),
+                (Symbol, "≜"),
+                (Symbol, "◊⁺⁺"),
+                (Ident, "δ"),
+                (Symbol, "≜"),
+                (Number, "0.82"),
+                (Ident, "Σ_512"),
+                (Symbol, "Π"),
+                (Symbol, "Σ"),
+                (Text, "'𝔸'"),
+            ]
+        );
+    }
+}
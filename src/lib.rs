@@ -17,17 +17,120 @@
 //! # }
 //! ```
 
+mod alignment;
+mod ambiguity;
+pub mod ast;
+mod clarify;
 mod claude;
+mod diff;
+mod evidence;
+mod formatter;
+mod glyphs;
+mod hedge;
+mod ladder;
+mod lexer;
+mod lint;
+mod parser;
+mod pool;
 mod provider;
+mod quality;
+mod render;
+mod repair;
+mod requirements;
+mod sampling;
+mod sarif;
+mod selection;
+mod speculation;
+mod structured;
+mod targeted;
+mod tools;
+mod validator;
 
+pub use alignment::{
+    align, Alignment, AlignmentOrigin, BlockAlignment, SourceQuote, StatementAlignment,
+};
+pub use ambiguity::{
+    aisp_ambiguity, prose_ambiguity, Ambiguity, AmbiguityKind, AmbiguityReport, AmbiguousSpan,
+};
+pub use clarify::{
+    ClarificationAnswer, ClarificationHandler, ClarificationQuestion, ClarificationRequest,
+    ConversionOutcome, FirstInterpretation,
+};
 pub use claude::ClaudeFallback;
-pub use provider::{LlmProvider, LlmResult};
+pub use diff::{diff, Change, ChangeKind, DocumentDiff, ItemKind};
+pub use evidence::{
+    rewrite_evidence, verify_evidence, EvidenceMismatch, EvidenceReport, DENSITY_TOLERANCE,
+};
+pub use formatter::{format_aisp, FormatStyle};
+pub use glyphs::{check_glyphs, classify, GlyphCategory, GlyphClass, GlyphIssue};
+pub use hedge::{HedgeStats, HedgedProvider};
+pub use ladder::{Escalation, Rung};
+pub use lint::{lint, Diagnostic, LintConfig, LintRule, Severity};
+pub use parser::{parse, ParseError, Parsed};
+pub use pool::{PoolStats, Reply, SessionFactory, SessionPool, WarmSession};
+pub use provider::{LlmProvider, LlmResult, SpanRequest};
+pub use quality::QualityScore;
+pub use render::render;
+pub use repair::{repair, Repair, RepairKind, Repaired};
+pub use requirements::{
+    find_requirements, trace_requirements, Requirement, RequirementCoverage, RequirementTrace,
+};
+pub use sampling::SampleReport;
+pub use sarif::lint_to_sarif;
+pub use selection::{Candidate, Selection};
+pub use speculation::{predict_confidence, Speculation, SpeculationOutcome};
+pub use structured::{StructuredBlock, StructuredDocument, StructuredEvidence, StructuredHeader};
+pub use tools::{ToolDefinition, ValidationTools, LOOKUP_SYMBOL, VALIDATE_AISP};
+pub use validator::{
+    conforming_tier, required_blocks, validate, QualityTier, Validation, Violation, ViolationKind,
+    REQUIRED_BLOCKS,
+};
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use validator::tier_rank;
 
 // Re-export rosetta-aisp types for convenience
 pub use rosetta_aisp::{
     AispConverter, ConversionOptions, ConversionResult, ConversionTier, RosettaStone, TokenStats,
 };
 
+/// How the LLM is used once fallback is triggered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FallbackMode {
+    /// Re-convert the whole prose with the LLM
+    #[default]
+    Full,
+    /// Send only the unmapped phrases to the LLM and splice the translations
+    /// into the deterministic output, falling back to `Full` if that fails
+    Targeted,
+}
+
+/// What to do with the `⟦Ε⟧` evidence block of the returned output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvidenceMode {
+    /// Return the evidence block as produced
+    #[default]
+    Keep,
+    /// Recompute δ, φ and τ and report claims that don't match
+    Verify,
+    /// Verify, then replace the block with the recomputed values
+    Rewrite,
+}
+
+/// What to do when an LLM output conforms only to a lower tier than requested,
+/// such as a one-line Minimal answer to a Full request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TierCheck {
+    /// Return the output with the requested tier
+    Off,
+    /// Return the output with the tier it conforms to
+    #[default]
+    Report,
+    /// Ask the LLM once more to complete the output, then report as `Report`
+    Reprompt,
+}
+
 /// Extended conversion options with LLM fallback support
 #[derive(Debug, Clone)]
 pub struct ConversionOptionsExt {
@@ -41,6 +144,55 @@ pub struct ConversionOptionsExt {
     pub llm_model: Option<String>,
     /// Use AISP symbolic prompt instead of English prompt (default: true)
     pub use_aisp_prompt: bool,
+    /// How the LLM is used when fallback triggers (default: full)
+    pub fallback_mode: FallbackMode,
+    /// Score the deterministic and LLM conversions and return the better one
+    /// instead of always preferring the LLM (default: false)
+    pub select_best: bool,
+    /// Number of LLM samples to request and rank (default: 1)
+    pub samples: usize,
+    /// Request samples concurrently instead of one after another (default: false)
+    pub parallel_samples: bool,
+    /// Discard LLM outputs that are not well-formed for the tier or use
+    /// unknown glyphs (default: true)
+    pub validate_llm_output: bool,
+    /// Verify or rewrite the evidence block of Standard and Full outputs
+    /// (default: keep)
+    pub evidence_mode: EvidenceMode,
+    /// Repair structural errors such as unbalanced brackets or a missing
+    /// header before validating and returning the output (default: false)
+    pub repair: bool,
+    /// Check that LLM outputs conform to the requested tier (default: report)
+    pub tier_check: TierCheck,
+    /// Also use the LLM fallback when the prose ambiguity reaches this
+    /// score, however confident the deterministic conversion is (default: none)
+    pub ambiguity_threshold: Option<f64>,
+    /// Map each statement of the output back to the prose it came from,
+    /// asking the LLM when it wrote the output (default: false)
+    pub align: bool,
+    /// Keep `REQ-123:` style requirement labels out of the conversion, tag
+    /// each statement with its requirements as a `;;` comment and report
+    /// coverage (default: false)
+    pub trace_requirements: bool,
+    /// Ask the LLM for a JSON document and render the AISP from it, falling
+    /// back to free text for providers without JSON output (default: false)
+    pub json_mode: bool,
+    /// Let the LLM check its drafts with validation and symbol lookup tools
+    /// over a few turns before answering (default: false)
+    pub agentic: bool,
+    /// Start the LLM request alongside the deterministic pass when the
+    /// prose is predicted to need it, cancelling it if it doesn't; only
+    /// full fallbacks with a single sample and no model ladder start early
+    /// (default: false)
+    pub speculative: bool,
+    /// Models to try in turn, cheapest first, moving up when the output is
+    /// rejected or scores below `escalation_quality`; full-tier prose skips
+    /// the first rung. Replaces `llm_model` for single-sample conversions
+    /// with providers that can switch models (default: empty)
+    pub model_ladder: Vec<String>,
+    /// Lowest quality score accepted before moving up the model ladder
+    /// (default: none)
+    pub escalation_quality: Option<f64>,
 }
 
 impl Default for ConversionOptionsExt {
@@ -51,6 +203,84 @@ impl Default for ConversionOptionsExt {
             enable_llm_fallback: false,
             llm_model: None,
             use_aisp_prompt: true, // AISP prompt is default for accuracy
+            fallback_mode: FallbackMode::Full,
+            select_best: false,
+            samples: 1,
+            parallel_samples: false,
+            validate_llm_output: true,
+            evidence_mode: EvidenceMode::Keep,
+            repair: false,
+            tier_check: TierCheck::Report,
+            ambiguity_threshold: None,
+            align: false,
+            trace_requirements: false,
+            json_mode: false,
+            agentic: false,
+            speculative: false,
+            model_ladder: Vec::new(),
+            escalation_quality: None,
+        }
+    }
+}
+
+/// Conversion result with LLM fallback metadata
+///
+/// Serializes with the fields of the inner `ConversionResult` at the top level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionResultExt {
+    /// The returned conversion
+    #[serde(flatten)]
+    pub result: ConversionResult,
+    /// Scores of both candidates, when best-of selection ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<Selection>,
+    /// Ranking of the LLM samples, when more than one was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub samples: Option<SampleReport>,
+    /// Evidence claims of the output as returned by the converter, when
+    /// evidence was verified or rewritten
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<EvidenceReport>,
+    /// Repairs applied to the output, when repair was enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repairs: Vec<Repair>,
+    /// Tier the conversion was requested at, when the returned output only
+    /// conforms to a lower one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_tier: Option<ConversionTier>,
+    /// Estimated ambiguity of the prose and of the returned output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambiguity: Option<AmbiguityReport>,
+    /// Prose source of each statement and block, when alignment was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<Alignment>,
+    /// Statements traced to each labeled requirement, when requirements
+    /// were traced and the prose had any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<RequirementCoverage>,
+    /// Predicted confidence and what happened to the early LLM request,
+    /// when the conversion was speculative
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speculation: Option<Speculation>,
+    /// Models of the ladder tried, with their cost and why each escalated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ladder: Vec<Rung>,
+}
+
+impl From<ConversionResult> for ConversionResultExt {
+    fn from(result: ConversionResult) -> Self {
+        Self {
+            result,
+            selection: None,
+            samples: None,
+            evidence: None,
+            repairs: Vec::new(),
+            requested_tier: None,
+            ambiguity: None,
+            alignment: None,
+            requirements: None,
+            speculation: None,
+            ladder: Vec::new(),
         }
     }
 }
@@ -73,40 +303,402 @@ pub async fn convert_with_fallback(
     prose: &str,
     options: Option<ConversionOptionsExt>,
 ) -> ConversionResult {
+    convert_with_fallback_ext(prose, options).await.result
+}
+
+/// Convert prose to AISP with optional LLM fallback, returning fallback metadata
+///
+/// Same as [`convert_with_fallback`], but also reports how the result was chosen.
+pub async fn convert_with_fallback_ext(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+) -> ConversionResultExt {
+    let opts = options.unwrap_or_default();
+    let provider = claude_provider(&opts);
+    convert_with_provider_ext(prose, Some(opts), &provider).await
+}
+
+fn claude_provider(opts: &ConversionOptionsExt) -> ClaudeFallback {
+    if let Some(model) = &opts.llm_model {
+        ClaudeFallback::with_model(model)
+    } else {
+        ClaudeFallback::new()
+    }
+}
+
+/// Convert prose to AISP with optional fallback to a custom LLM provider
+///
+/// Behaves like [`convert_with_fallback`] but uses `provider` instead of
+/// Claude. `llm_model` is ignored; the provider decides which model to use.
+pub async fn convert_with_provider(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+    provider: &dyn LlmProvider,
+) -> ConversionResult {
+    convert_with_provider_ext(prose, options, provider)
+        .await
+        .result
+}
+
+/// Convert prose to AISP with a custom LLM provider, returning fallback metadata
+pub async fn convert_with_provider_ext(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+    provider: &dyn LlmProvider,
+) -> ConversionResultExt {
+    let opts = options.unwrap_or_default();
+    let requirements = if opts.trace_requirements {
+        find_requirements(prose)
+    } else {
+        Vec::new()
+    };
+    // Labels would otherwise reach the output as unmapped words; blanking
+    // keeps byte offsets, so spans still point into the original prose
+    let blanked = requirements::blank_labels(prose, &requirements);
+    let prose = blanked.as_str();
+    let mut ext = fallback(prose, &opts, provider).await;
+
+    if opts.repair {
+        let repaired = repair(&ext.result.output, ext.result.tier);
+        if !repaired.repairs.is_empty() {
+            ext.result.output = repaired.output;
+            ext.result.tokens = provider::token_stats(prose.len(), ext.result.output.len());
+            ext.repairs = repaired.repairs;
+        }
+    }
+
+    // Minimal outputs are bare statements without an evidence block
+    if opts.evidence_mode != EvidenceMode::Keep && ext.result.tier != ConversionTier::Minimal {
+        ext.evidence = Some(verify_evidence(&ext.result.output));
+        if opts.evidence_mode == EvidenceMode::Rewrite {
+            ext.result.output = rewrite_evidence(&ext.result.output);
+            ext.result.tokens = provider::token_stats(prose.len(), ext.result.output.len());
+        }
+    }
+
+    ext.ambiguity = Some(AmbiguityReport::compute(prose, &ext.result.output));
+
+    if opts.align || !requirements.is_empty() {
+        // Without quotes the deterministic alignment stands
+        let quotes = if ext.result.used_fallback {
+            provider
+                .align(prose, &ext.result.output)
+                .await
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let aligned = |output: &str| {
+            let mut alignment = align(prose, output);
+            alignment.apply_quotes(prose, output, &quotes);
+            alignment
+        };
+
+        let mut alignment = aligned(&ext.result.output);
+        if !requirements.is_empty() {
+            let output = &ext.result.output;
+            ext.requirements = Some(requirements::coverage(&requirements, &alignment, output));
+            let annotated = requirements::annotate(output, &requirements, &alignment);
+            if annotated != *output {
+                ext.result.output = annotated;
+                ext.result.tokens = provider::token_stats(prose.len(), ext.result.output.len());
+                alignment = aligned(&ext.result.output);
+            }
+        }
+        if opts.align {
+            ext.alignment = Some(alignment);
+        }
+    }
+    ext
+}
+
+/// Convert prose to AISP, or ask for clarification if it is ambiguous
+///
+/// Questions come from the deterministic ambiguity analysis once the prose
+/// reaches `ambiguity_threshold` (any ambiguity if unset) and, with LLM
+/// fallback enabled, from Claude. Answer them and finish the conversion
+/// with [`convert_clarified`].
+pub async fn convert_or_clarify(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+) -> ConversionOutcome {
+    let opts = options.unwrap_or_default();
+    let provider = claude_provider(&opts);
+    convert_or_clarify_with_provider(prose, Some(opts), &provider).await
+}
+
+/// Convert prose to AISP, or ask for clarification, with a custom LLM provider
+pub async fn convert_or_clarify_with_provider(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+    provider: &dyn LlmProvider,
+) -> ConversionOutcome {
     let opts = options.unwrap_or_default();
+    let questions = clarify::questions(prose, &opts, provider).await;
+    if !questions.is_empty() {
+        return ConversionOutcome::NeedsClarification(ClarificationRequest {
+            prose: prose.to_string(),
+            questions,
+        });
+    }
+    let ext = convert_with_provider_ext(prose, Some(opts), provider).await;
+    ConversionOutcome::Converted(Box::new(ext))
+}
 
+/// Finish a conversion that stopped for clarification
+///
+/// Each answer is written into the prose after the text it clarifies
+/// (see [`ClarificationRequest::clarified_prose`]) before converting.
+pub async fn convert_clarified(
+    request: &ClarificationRequest,
+    answers: &[ClarificationAnswer],
+    options: Option<ConversionOptionsExt>,
+) -> ConversionResultExt {
+    let opts = options.unwrap_or_default();
+    let provider = claude_provider(&opts);
+    convert_clarified_with_provider(request, answers, Some(opts), &provider).await
+}
+
+/// Finish a conversion that stopped for clarification, with a custom LLM provider
+pub async fn convert_clarified_with_provider(
+    request: &ClarificationRequest,
+    answers: &[ClarificationAnswer],
+    options: Option<ConversionOptionsExt>,
+    provider: &dyn LlmProvider,
+) -> ConversionResultExt {
+    let prose = request.clarified_prose(answers);
+    convert_with_provider_ext(&prose, options, provider).await
+}
+
+/// Convert prose to AISP, asking `handler` about ambiguous spans on the way
+///
+/// Questions are found as in [`convert_or_clarify`]; each one is passed to
+/// `handler` and the answers are written into the prose before converting.
+pub async fn convert_interactive(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+    handler: &dyn ClarificationHandler,
+) -> ConversionResultExt {
+    let opts = options.unwrap_or_default();
+    let provider = claude_provider(&opts);
+    convert_interactive_with_provider(prose, Some(opts), &provider, handler).await
+}
+
+/// Convert prose to AISP with a custom LLM provider, asking `handler` about
+/// ambiguous spans on the way
+pub async fn convert_interactive_with_provider(
+    prose: &str,
+    options: Option<ConversionOptionsExt>,
+    provider: &dyn LlmProvider,
+    handler: &dyn ClarificationHandler,
+) -> ConversionResultExt {
+    let opts = options.unwrap_or_default();
+    let request = ClarificationRequest {
+        prose: prose.to_string(),
+        questions: clarify::questions(prose, &opts, provider).await,
+    };
+    let answers = clarify::ask(&request, handler).await;
+    convert_clarified_with_provider(&request, &answers, Some(opts), provider).await
+}
+
+/// Run the deterministic conversion and, if needed, the LLM fallback
+async fn fallback(
+    prose: &str,
+    opts: &ConversionOptionsExt,
+    provider: &dyn LlmProvider,
+) -> ConversionResultExt {
     // Convert using rosetta-aisp's ConversionOptions
     let base_options = ConversionOptions {
         tier: opts.tier,
         confidence_threshold: opts.confidence_threshold,
     };
 
-    let result = AispConverter::convert(prose, Some(base_options));
     let threshold = opts.confidence_threshold.unwrap_or(0.8);
+    let ambiguous = opts
+        .ambiguity_threshold
+        .is_some_and(|limit| prose_ambiguity(prose).score >= limit);
+    let needs_llm = |result: &ConversionResult| result.confidence < threshold || ambiguous;
+
+    let prediction = (opts.speculative && opts.enable_llm_fallback)
+        .then(|| speculation::predict(prose, opts, ambiguous));
+    let (result, early) = match &prediction {
+        Some(prediction) if prediction.start => {
+            speculation::race(prose, base_options, prediction, opts, provider, needs_llm).await
+        }
+        _ => (AispConverter::convert(prose, Some(base_options)), None),
+    };
+    let speculation = prediction.map(|prediction| Speculation {
+        predicted_confidence: prediction.confidence,
+        outcome: match (prediction.start, needs_llm(&result)) {
+            (true, true) if matches!(early, Some(Some(_))) => SpeculationOutcome::Used,
+            (true, true) => SpeculationOutcome::Failed,
+            (true, false) => SpeculationOutcome::Cancelled,
+            (false, true) => SpeculationOutcome::Missed,
+            (false, false) => SpeculationOutcome::Skipped,
+        },
+    });
+    let with_speculation = |result: ConversionResult| ConversionResultExt {
+        speculation,
+        ..result.into()
+    };
 
     // Check if LLM fallback is needed
-    if opts.enable_llm_fallback && result.confidence < threshold {
-        let provider = if let Some(model) = &opts.llm_model {
-            ClaudeFallback::with_model(model)
+    if !opts.enable_llm_fallback || !needs_llm(&result) {
+        return with_speculation(result);
+    }
+    if early.is_none() && !provider.is_available().await {
+        return with_speculation(result);
+    }
+
+    let mut ladder = Vec::new();
+    let (llm_result, samples) = if opts.samples > 1 {
+        let candidates = if opts.parallel_samples {
+            join_all((0..opts.samples).map(|_| llm_convert(prose, &result, opts, provider)))
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
         } else {
-            ClaudeFallback::new()
+            let mut candidates = Vec::with_capacity(opts.samples);
+            for _ in 0..opts.samples {
+                candidates.extend(llm_convert(prose, &result, opts, provider).await);
+            }
+            candidates
         };
 
-        if provider.is_available().await {
-            if let Ok(llm_result) = provider
-                .convert(
-                    prose,
-                    result.tier,
-                    &result.unmapped,
-                    Some(&result.output),
-                    opts.use_aisp_prompt,
-                )
-                .await
-            {
-                return llm_result.to_conversion_result(result.tier, prose.len());
+        match sampling::rank(prose, candidates) {
+            Some((best, report)) => (best, Some(report)),
+            None => return with_speculation(result),
+        }
+    } else {
+        let llm_result = match early {
+            Some(early) => early,
+            None if !opts.model_ladder.is_empty() => {
+                let (llm_result, rungs) = ladder::convert(prose, &result, opts, provider).await;
+                ladder = rungs;
+                llm_result
+            }
+            None => llm_convert(prose, &result, opts, provider).await,
+        };
+        match llm_result {
+            Some(llm_result) => (llm_result, None),
+            None => {
+                return ConversionResultExt {
+                    ladder,
+                    ..with_speculation(result)
+                }
+            }
+        }
+    };
+
+    let requested = result.tier;
+    let (result, selection) = if opts.select_best {
+        let (chosen, selection) = selection::select_best(prose, result, llm_result);
+        (chosen, Some(selection))
+    } else {
+        (llm_result, None)
+    };
+
+    ConversionResultExt {
+        requested_tier: (result.tier != requested).then_some(requested),
+        result,
+        selection,
+        samples,
+        evidence: None,
+        repairs: Vec::new(),
+        ambiguity: None,
+        alignment: None,
+        requirements: None,
+        speculation,
+        ladder,
+    }
+}
+
+/// Run the LLM part of the fallback according to `opts.fallback_mode`
+///
+/// With `opts.validate_llm_output`, outputs not accepted for the tier or using glyphs
+/// unknown to the specification are treated like provider errors: targeted
+/// results fall back to a full conversion and full conversions to the
+/// deterministic result. With `opts.repair`, outputs are validated as they
+/// will be after repair. Full conversions are checked against the requested
+/// tier according to `opts.tier_check` first, and validated for the tier they
+/// are reported with, so a short answer is downgraded rather than discarded.
+async fn llm_convert(
+    prose: &str,
+    result: &ConversionResult,
+    opts: &ConversionOptionsExt,
+    provider: &dyn LlmProvider,
+) -> Option<ConversionResult> {
+    // The output as it will be returned
+    let returned = |output: &str| {
+        if opts.repair {
+            repair(output, result.tier).output
+        } else {
+            output.to_string()
+        }
+    };
+    // Requested tier, or the lower one the output conforms to
+    let tier = |output: &str| {
+        let conforming = conforming_tier(&returned(output));
+        if opts.tier_check != TierCheck::Off && tier_rank(conforming) < tier_rank(result.tier) {
+            conforming
+        } else {
+            result.tier
+        }
+    };
+    let accepted = |output: &str, tier: ConversionTier| {
+        if !opts.validate_llm_output {
+            return true;
+        }
+        let output = returned(output);
+        validate(&output).accepts(tier) && glyphs::known_glyphs_only(&output)
+    };
+
+    if opts.fallback_mode == FallbackMode::Targeted {
+        if let Some(targeted) =
+            targeted::convert(prose, result, provider, opts.use_aisp_prompt).await
+        {
+            if accepted(&targeted.output, result.tier) {
+                return Some(targeted);
+            }
+        }
+    }
+
+    let mut llm_result = structured::convert(
+        provider,
+        prose,
+        result.tier,
+        &result.unmapped,
+        // A speculative request starts before there is deterministic output
+        Some(result.output.as_str()).filter(|output| !output.is_empty()),
+        opts,
+    )
+    .await
+    .ok()
+    .filter(|llm_result| accepted(&llm_result.output, tier(&llm_result.output)))?;
+    let mut llm_tier = tier(&llm_result.output);
+
+    // Hand the short output back as the attempt to complete
+    if opts.tier_check == TierCheck::Reprompt && llm_tier != result.tier {
+        if let Some(retry) = structured::convert(
+            provider,
+            prose,
+            result.tier,
+            &result.unmapped,
+            Some(&llm_result.output),
+            opts,
+        )
+        .await
+        .ok()
+        .filter(|retry| accepted(&retry.output, tier(&retry.output)))
+        {
+            let retry_tier = tier(&retry.output);
+            if tier_rank(retry_tier) > tier_rank(llm_tier) {
+                llm_result = retry;
+                llm_tier = retry_tier;
             }
         }
     }
 
-    result
+    Some(llm_result.to_conversion_result(llm_tier, prose.len()))
 }
@@ -0,0 +1,601 @@
+//! AISP Linter
+//!
+//! Named rules over a parsed document, each with a configurable severity.
+//! Diagnostics carry source spans and render in the rustc style:
+//!
+//! ```text
+//! warning[duplicate-definition]: `f` is already defined
+//!  --> spec.aisp:4:3
+//!   |
+//! 4 |   f≜λx.x
+//!   |   ^^^^^^
+//!   = help: first defined at line 2
+//! ```
+
+use crate::ast::{Document, Expr, ExprKind, Span, Stmt, StmtKind};
+use crate::evidence::verify_evidence;
+use crate::glyphs::{check_glyphs, GlyphClass};
+use crate::lexer::{tokenize, TokenKind};
+use crate::parser::parse;
+use crate::validator::required_blocks;
+use rosetta_aisp::ConversionTier;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// How a rule's findings are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Not reported
+    Allow,
+    /// Reported, but the document passes
+    Warning,
+    /// Reported and the document fails
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Allow => "allow",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Lint rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// The document does not parse
+    ParseError,
+    /// A glyph neither the glossary nor `rosetta_aisp` knows
+    UnknownGlyph,
+    /// A block required for the tier is absent
+    MissingBlock,
+    /// A name defined with `≜` more than once
+    DuplicateDefinition,
+    /// A name defined but never referenced
+    UnusedDefinition,
+    /// An `⟦Ε⟧` claim that doesn't match the document
+    EvidenceMismatch,
+    /// A run of plain English words inside a statement
+    ProseLeakage,
+    /// `∧`/`∨` or different arrows mixed without parentheses
+    AmbiguousPrecedence,
+}
+
+impl LintRule {
+    /// Every rule, in the order they run
+    pub const ALL: &'static [LintRule] = &[
+        LintRule::ParseError,
+        LintRule::UnknownGlyph,
+        LintRule::MissingBlock,
+        LintRule::DuplicateDefinition,
+        LintRule::UnusedDefinition,
+        LintRule::EvidenceMismatch,
+        LintRule::ProseLeakage,
+        LintRule::AmbiguousPrecedence,
+    ];
+
+    /// Rule name as used in diagnostics and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::ParseError => "parse-error",
+            LintRule::UnknownGlyph => "unknown-glyph",
+            LintRule::MissingBlock => "missing-block",
+            LintRule::DuplicateDefinition => "duplicate-definition",
+            LintRule::UnusedDefinition => "unused-definition",
+            LintRule::EvidenceMismatch => "evidence-mismatch",
+            LintRule::ProseLeakage => "prose-leakage",
+            LintRule::AmbiguousPrecedence => "ambiguous-precedence",
+        }
+    }
+
+    /// One-line description of what the rule checks
+    pub fn description(self) -> &'static str {
+        match self {
+            LintRule::ParseError => "The document does not parse",
+            LintRule::UnknownGlyph => "A glyph neither the glossary nor rosetta_aisp knows",
+            LintRule::MissingBlock => "A block required for the tier is absent",
+            LintRule::DuplicateDefinition => "A name defined with ≜ more than once",
+            LintRule::UnusedDefinition => "A name defined but never referenced",
+            LintRule::EvidenceMismatch => "An ⟦Ε⟧ claim that doesn't match the document",
+            LintRule::ProseLeakage => "A run of plain English words inside a statement",
+            LintRule::AmbiguousPrecedence => "∧/∨ or different arrows mixed without parentheses",
+        }
+    }
+
+    /// Severity when not configured
+    ///
+    /// Unused definitions are allowed: the functions of a `⟦Λ⟧` block are
+    /// usually the document's interface rather than helpers.
+    pub fn default_severity(self) -> Severity {
+        match self {
+            LintRule::ParseError | LintRule::UnknownGlyph | LintRule::MissingBlock => {
+                Severity::Error
+            }
+            LintRule::UnusedDefinition => Severity::Allow,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LintRule::ALL
+            .iter()
+            .copied()
+            .find(|rule| rule.name() == s)
+            .ok_or_else(|| format!("unknown lint rule `{}`", s))
+    }
+}
+
+/// Which tier to check against and how each rule is reported
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+    /// Tier whose required blocks must be present (default: standard)
+    pub tier: ConversionTier,
+    /// Severity overrides; rules not listed use their default
+    pub severities: HashMap<LintRule, Severity>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            tier: ConversionTier::Standard,
+            severities: HashMap::new(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Effective severity of `rule`
+    pub fn severity(&self, rule: LintRule) -> Severity {
+        self.severities
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+/// A lint finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Rule that produced it
+    pub rule: LintRule,
+    /// Configured severity; never [`Severity::Allow`]
+    pub severity: Severity,
+    /// Human-readable description
+    pub message: String,
+    /// Where in the source, if the finding has a location
+    pub span: Option<Span>,
+    /// Suggestion or related location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(rule: LintRule, message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            rule,
+            severity: rule.default_severity(),
+            message: message.into(),
+            span,
+            help: None,
+        }
+    }
+
+    fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Render in the rustc style, with `file` naming the source
+    pub fn render(&self, source: &str, file: &str) -> String {
+        let mut out = format!("{}[{}]: {}\n", self.severity, self.rule, self.message);
+
+        let Some(span) = self.span else {
+            out.push_str(&format!(" --> {}\n", file));
+            if let Some(help) = &self.help {
+                out.push_str(&format!(" = help: {}\n", help));
+            }
+            return out;
+        };
+
+        let (line, col) = span.line_col(source);
+        let gutter = " ".repeat(line.to_string().len());
+        let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_text = source[line_start..].lines().next().unwrap_or("");
+
+        // Underline up to the end of the first line of the span
+        let underlined = span.text(source).lines().next().unwrap_or("");
+        let width = underlined.chars().count().max(1);
+
+        out.push_str(&format!("{}--> {}:{}:{}\n", gutter, file, line, col));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", line, line_text));
+        out.push_str(&format!(
+            "{} | {}{}\n",
+            gutter,
+            " ".repeat(col - 1),
+            "^".repeat(width)
+        ));
+        if let Some(help) = &self.help {
+            out.push_str(&format!("{} = help: {}\n", gutter, help));
+        }
+        out
+    }
+}
+
+/// Lint an AISP document
+///
+/// Diagnostics of allowed rules are left out; the rest are in rule order,
+/// then source order.
+pub fn lint(source: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let parsed = parse(source);
+    let doc = &parsed.document;
+    let enabled = |rule: LintRule| config.severity(rule) != Severity::Allow;
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for rule in LintRule::ALL.iter().copied().filter(|rule| enabled(*rule)) {
+        match rule {
+            LintRule::ParseError => diagnostics.extend(
+                parsed
+                    .errors
+                    .iter()
+                    .map(|e| Diagnostic::new(rule, e.message.clone(), Some(e.span))),
+            ),
+            LintRule::UnknownGlyph => diagnostics.extend(
+                check_glyphs(source)
+                    .into_iter()
+                    .filter(|issue| issue.class == GlyphClass::Unknown)
+                    .map(|issue| {
+                        Diagnostic::new(
+                            rule,
+                            format!("`{}` is not in the Σ_512 glossary", issue.glyph),
+                            Some(issue.span),
+                        )
+                    }),
+            ),
+            LintRule::MissingBlock => diagnostics.extend(
+                required_blocks(config.tier)
+                    .iter()
+                    .filter(|category| !doc.has_block(category))
+                    .map(|category| {
+                        Diagnostic::new(
+                            rule,
+                            format!("missing required block ⟦{}⟧", category),
+                            None,
+                        )
+                        .with_help(format!("required at the {:?} tier", config.tier))
+                    }),
+            ),
+            LintRule::DuplicateDefinition => duplicate_definitions(source, doc, &mut diagnostics),
+            LintRule::UnusedDefinition => unused_definitions(doc, &mut diagnostics),
+            LintRule::EvidenceMismatch => {
+                if doc.evidence.is_some() {
+                    diagnostics.extend(verify_evidence(source).mismatches.into_iter().map(|m| {
+                        Diagnostic::new(
+                            rule,
+                            format!(
+                                "{} is claimed as {}, but is {}",
+                                m.field, m.claimed, m.actual
+                            ),
+                            Some(m.span),
+                        )
+                        .with_help("run `rosetta evidence --rewrite` to recompute the block")
+                    }));
+                }
+            }
+            LintRule::ProseLeakage => prose_leakage(source, doc, &mut diagnostics),
+            LintRule::AmbiguousPrecedence => ambiguous_precedence(source, doc, &mut diagnostics),
+        }
+    }
+
+    for diagnostic in &mut diagnostics {
+        diagnostic.severity = config.severity(diagnostic.rule);
+    }
+    diagnostics
+}
+
+/// Every statement of the document: top-level claims, then block bodies
+pub(crate) fn statements(doc: &Document) -> impl Iterator<Item = &Stmt> {
+    doc.claims
+        .iter()
+        .chain(doc.blocks.iter().flat_map(|b| &b.body))
+}
+
+/// The name a definition target defines: `x` in `x≜…` and `f` in `f(x)≜…`
+fn defined_name(target: &Expr) -> Option<(&str, Span)> {
+    match &target.kind {
+        ExprKind::Ident(name) => Some((name, target.span)),
+        ExprKind::Apply { func, .. } => match &func.kind {
+            ExprKind::Ident(name) => Some((name, func.span)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn duplicate_definitions(source: &str, doc: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let mut first: HashMap<&str, Span> = HashMap::new();
+    for stmt in statements(doc) {
+        let StmtKind::Def { target, op, .. } = &stmt.kind else {
+            continue;
+        };
+        // `≔` assigns and may legitimately be repeated
+        let Some((name, span)) = defined_name(target).filter(|_| op == "≜") else {
+            continue;
+        };
+        match first.get(name) {
+            Some(earlier) => diagnostics.push(
+                Diagnostic::new(
+                    LintRule::DuplicateDefinition,
+                    format!("`{}` is already defined", name),
+                    Some(stmt.span),
+                )
+                .with_help(format!(
+                    "first defined at line {}",
+                    earlier.line_col(source).0
+                )),
+            ),
+            None => {
+                first.insert(name, span);
+            }
+        }
+    }
+}
+
+fn unused_definitions(doc: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let mut defined: Vec<(&str, Span)> = Vec::new();
+    for stmt in statements(doc) {
+        if let StmtKind::Def { target, .. } = &stmt.kind {
+            defined.extend(defined_name(target));
+        }
+    }
+    let definition_spans: HashSet<Span> = defined.iter().map(|(_, span)| *span).collect();
+
+    let used: HashSet<&str> = statements(doc)
+        .flat_map(|stmt| stmt.exprs())
+        .flat_map(|expr| expr.walk())
+        .filter(|expr| !definition_spans.contains(&expr.span))
+        .filter_map(|expr| match &expr.kind {
+            ExprKind::Ident(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut reported = HashSet::new();
+    for (name, span) in defined {
+        if !used.contains(name) && reported.insert(name) {
+            diagnostics.push(Diagnostic::new(
+                LintRule::UnusedDefinition,
+                format!("`{}` is defined but never used", name),
+                Some(span),
+            ));
+        }
+    }
+}
+
+/// Number of consecutive plain words that count as prose
+const PROSE_RUN: usize = 3;
+
+fn prose_leakage(source: &str, doc: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    for stmt in statements(doc) {
+        if matches!(stmt.kind, StmtKind::Comment(_)) {
+            continue;
+        }
+        let offset = stmt.span.start;
+        let mut runs: Vec<Vec<Span>> = vec![Vec::new()];
+
+        // Consecutive words are always separated by whitespace
+        for token in tokenize(stmt.span.text(source)) {
+            let span = Span::new(offset + token.span.start, offset + token.span.end);
+            if token.kind == TokenKind::Ident && token.text.chars().all(|c| c.is_ascii_alphabetic())
+            {
+                runs.last_mut().expect("never empty").push(span);
+            } else if runs.last().is_some_and(|run| !run.is_empty()) {
+                runs.push(Vec::new());
+            }
+        }
+
+        for run in runs.into_iter().filter(|run| run.len() >= PROSE_RUN) {
+            let span = run[0].to(run[run.len() - 1]);
+            diagnostics.push(
+                Diagnostic::new(
+                    LintRule::ProseLeakage,
+                    format!("prose `{}` inside a statement", span.text(source)),
+                    Some(span),
+                )
+                .with_help("translate it to symbols, or quote it as a literal"),
+            );
+        }
+    }
+}
+
+/// Operators whose relative precedence readers commonly misjudge
+const CONFUSABLE: &[(&[&str], &[&str])] = &[
+    (&["∧", "⋀"], &["∨", "⋁"]),
+    (&["→", "↦"], &["⇒", "⇔", "↔", "⇐"]),
+];
+
+fn ambiguous_precedence(source: &str, doc: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    for expr in statements(doc)
+        .flat_map(|stmt| stmt.exprs())
+        .flat_map(|expr| expr.walk())
+    {
+        if let Some((inner, outer)) = mixed_precedence(expr) {
+            diagnostics.push(
+                Diagnostic::new(
+                    LintRule::AmbiguousPrecedence,
+                    format!("`{}` and `{}` mixed without parentheses", inner, outer),
+                    Some(expr.span),
+                )
+                .with_help(format!(
+                    "parenthesize to make the grouping explicit: `{}`",
+                    parenthesized(source, expr)
+                )),
+            );
+        }
+    }
+}
+
+/// The inner and outer operator when `expr` is a binary expression with a
+/// direct binary child whose operator is confusable with its own
+pub(crate) fn mixed_precedence(expr: &Expr) -> Option<(&str, &str)> {
+    fn binary_op(expr: &Expr) -> Option<&str> {
+        match &expr.kind {
+            ExprKind::Binary { op, .. } => Some(op),
+            _ => None,
+        }
+    }
+
+    let outer = binary_op(expr)?;
+    let inner = expr
+        .children()
+        .into_iter()
+        .filter_map(binary_op)
+        .find(|inner| {
+            CONFUSABLE.iter().any(|(a, b)| {
+                (a.contains(&outer) && b.contains(inner))
+                    || (b.contains(&outer) && a.contains(inner))
+            })
+        })?;
+    Some((inner, outer))
+}
+
+/// `expr` with its binary children in parentheses
+fn parenthesized(source: &str, expr: &Expr) -> String {
+    let ExprKind::Binary { op, lhs, rhs } = &expr.kind else {
+        return expr.span.text(source).to_string();
+    };
+    let side = |e: &Expr| match e.kind {
+        ExprKind::Binary { .. } => format!("({})", e.span.text(source)),
+        _ => e.span.text(source).to_string(),
+    };
+    format!("{}{}{}", side(lhs), op, side(rhs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "𝔸5.1.x@2026-01-09
+γ≔x
+⟦Λ:Funcs⟧{
+  f≜λx.x
+  f≜λy.y
+  g≜f(a∧b∨c)
+  the user must log in
+}
+⟦Ε⟧⟨δ≜0.99;τ≜◊⁺⁺⟩";
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<LintRule> {
+        diagnostics.iter().map(|d| d.rule).collect()
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let diagnostics = lint(DOC, &LintConfig::default());
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                LintRule::DuplicateDefinition,
+                LintRule::EvidenceMismatch,
+                LintRule::EvidenceMismatch,
+                LintRule::ProseLeakage,
+                LintRule::AmbiguousPrecedence,
+            ]
+        );
+        assert_eq!(diagnostics[0].span.unwrap().text(DOC), "f≜λy.y");
+        assert_eq!(
+            diagnostics[3].span.unwrap().text(DOC),
+            "the user must log in"
+        );
+        assert_eq!(diagnostics[4].span.unwrap().text(DOC), "a∧b∨c");
+        assert_eq!(
+            diagnostics[4].help.as_deref(),
+            Some("parenthesize to make the grouping explicit: `(a∧b)∨c`")
+        );
+        assert!(!lint("hd s≜⟨a,b⟩", &LintConfig::default())
+            .iter()
+            .any(|d| d.rule == LintRule::ProseLeakage));
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let config = LintConfig {
+            tier: ConversionTier::Full,
+            severities: HashMap::from([
+                (LintRule::EvidenceMismatch, Severity::Allow),
+                (LintRule::UnusedDefinition, Severity::Warning),
+                (LintRule::ProseLeakage, Severity::Error),
+            ]),
+        };
+        let diagnostics = lint(DOC, &config);
+
+        assert!(!rules(&diagnostics).contains(&LintRule::EvidenceMismatch));
+        let missing = diagnostics
+            .iter()
+            .filter(|d| d.rule == LintRule::MissingBlock)
+            .count();
+        assert_eq!(missing, 3);
+        let unused: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.rule == LintRule::UnusedDefinition)
+            .map(|d| d.span.unwrap().text(DOC))
+            .collect();
+        assert_eq!(unused, vec!["g"]);
+        let prose = diagnostics
+            .iter()
+            .find(|d| d.rule == LintRule::ProseLeakage);
+        assert_eq!(prose.unwrap().severity, Severity::Error);
+        assert_eq!("prose-leakage".parse(), Ok(LintRule::ProseLeakage));
+    }
+
+    #[test]
+    fn test_render() {
+        let diagnostics = lint(DOC, &LintConfig::default());
+        assert_eq!(
+            diagnostics[0].render(DOC, "spec.aisp"),
+            "warning[duplicate-definition]: `f` is already defined
+ --> spec.aisp:5:3
+  |
+5 |   f≜λy.y
+  |   ^^^^^^
+  = help: first defined at line 4
+"
+        );
+
+        let missing = Diagnostic::new(LintRule::MissingBlock, "missing required block ⟦Ω⟧", None);
+        assert_eq!(
+            missing.render(DOC, "spec.aisp"),
+            "error[missing-block]: missing required block ⟦Ω⟧\n --> spec.aisp\n"
+        );
+    }
+
+    #[test]
+    fn test_specification_has_no_errors() {
+        let config = LintConfig {
+            tier: ConversionTier::Full,
+            ..Default::default()
+        };
+        let errors: Vec<Diagnostic> = lint(&crate::claude::AISP_PROMPT, &config)
+            .into_iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect();
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    }
+}
@@ -0,0 +1,1401 @@
+//! AISP Parser
+//!
+//! Parses AISP source into the typed [`ast`](crate::ast) representation.
+//!
+//! Document structure (header, context, references, blocks, evidence) is
+//! recognized line by line, or after whitespace on the same line as in the
+//! single-line compact layout. Statements inside blocks are parsed with a
+//! precedence-climbing parser following `Prec` and `Assoc` from the
+//! specification's `⟦Σ:Grammar⟧` block. Errors are collected rather than
+//! returned: a malformed statement becomes [`StmtKind::Error`] and parsing
+//! resumes at the next statement, and an unclosed block ends at the next
+//! line that opens a block.
+
+use crate::ast::{
+    Block, Comment, Context, Delim, Document, Evidence, EvidenceEntry, Expr, ExprKind, Header,
+    References, Span, Stmt, StmtKind, AST_VERSION,
+};
+use crate::lexer::{tokenize, Token, TokenKind};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// A parse error with the source span it applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+    /// Human-readable description
+    pub message: String,
+    /// Where the error was detected
+    pub span: Span,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Result of parsing: the (possibly partial) document and every error found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parsed {
+    /// The parsed document
+    pub document: Document,
+    /// Errors in source order
+    pub errors: Vec<ParseError>,
+}
+
+impl Parsed {
+    /// Whether the source parsed without errors
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Versioned JSON form: `{"version": …, "document": …, "errors": […]}`
+    ///
+    /// Enum nodes are tagged as `{"type": "binary", "data": {…}}`, and every
+    /// node has a `span` of byte offsets into the source.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "version": AST_VERSION,
+            "document": self.document,
+            "errors": self.errors,
+        })
+    }
+
+    /// Read the form produced by [`Parsed::to_json`]
+    ///
+    /// Fails on malformed JSON and on a version other than [`AST_VERSION`].
+    /// `errors` may be left out.
+    pub fn from_json(json: &str) -> Result<Parsed> {
+        let mut value: Value = serde_json::from_str(json)?;
+        match value.get("version").and_then(Value::as_u64) {
+            Some(version) if version == u64::from(AST_VERSION) => {}
+            Some(version) => bail!(
+                "Unsupported AST version {} (expected {})",
+                version,
+                AST_VERSION
+            ),
+            None => bail!("Missing AST version"),
+        }
+        Ok(Parsed {
+            document: serde_json::from_value(value["document"].take())?,
+            errors: match value.get_mut("errors") {
+                Some(errors) => serde_json::from_value(errors.take())?,
+                None => Vec::new(),
+            },
+        })
+    }
+}
+
+/// Parse an AISP document
+///
+/// Never fails: syntax errors are reported in [`Parsed::errors`] and the
+/// affected parts are kept as [`StmtKind::Error`] or left out.
+pub fn parse(source: &str) -> Parsed {
+    DocParser {
+        source,
+        tokens: tokenize(source),
+        pos: 0,
+        errors: Vec::new(),
+    }
+    .document()
+}
+
+/// Binding power of juxtaposition and adjacent `(`/`[` application
+pub(crate) const APPLY_BP: u8 = 24;
+
+/// Minimum binding power of group items and binder bodies, which stop at `,`
+pub(crate) const ITEM_BP: u8 = 1;
+
+/// Binding power of a quantifier's binder, just above `:`
+pub(crate) const BINDER_BP: u8 = 5;
+
+/// Left and right binding powers of infix operators
+///
+/// Levels follow `Prec≜[λ∀∃:1,→⇒↔:2,∨⋁:3,∧⋀:4,¬:5,≡≜∈⊆:6,⊕⊖:7,⊗×:8,∘:9,.:10]`,
+/// with `,` (sequence), `|` (alternative) and `:` (typing) below the binders. A right binding
+/// power equal to the left one makes the operator right-associative.
+pub(crate) fn infix_bp(op: &str) -> Option<(u8, u8)> {
+    Some(match op {
+        "," => (0, 1),
+        "|" => (2, 3),
+        ":" => (4, 4),
+        "→" | "↦" => (6, 6),
+        "⇒" | "↔" | "⇐" | "⇔" | "⊢" | "⊨" | "⊣" | "⊸" | "≫" | "⤳" | "↣" | "↠" | "←" => {
+            (6, 7)
+        }
+        "∨" | "⋁" => (8, 9),
+        "∧" | "⋀" => (10, 11),
+        "≡" | "≢" | "≜" | "≔" | "∈" | "∉" | "∋" | "∌" | "⊆" | "⊇" | "⊂" | "⊃" | "⊄" | "⊅" | "="
+        | "≠" | "<" | ">" | "≤" | "≥" | "≈" | "∼" | "≅" | "≃" | "∝" | "≺" | "≻" | "≪" => {
+            (14, 15)
+        }
+        "⊕" | "⊖" | "+" | "-" | "∪" | "∖" | "⧺" | "△" => (16, 17),
+        "⊗" | "×" | "*" | "/" | "÷" | "∩" | "·" | "⊙" => (18, 19),
+        "∘" => (20, 20),
+        "." => (22, 23),
+        _ => return None,
+    })
+}
+
+/// Right binding power of prefix operators
+pub(crate) fn prefix_bp(op: &str) -> Option<u8> {
+    Some(match op {
+        "⊢" | "⊨" => 6,
+        "¬" | "◇" | "□" => 12,
+        "-" | "+" => 19,
+        _ => return None,
+    })
+}
+
+/// Operators that bind a variable: `Q binder : body`
+pub(crate) const BINDERS: &[&str] = &["∀", "∃", "∃!", "∄", "Π", "Σ"];
+
+/// Operators that are only ever postfix
+const POSTFIX: &[&str] = &["?", "!", "′"];
+
+const CLOSERS: &[&str] = &[")", "⟩", "}", "]", "⌉", "⌋", "⟫", "⟧"];
+
+const SEPARATORS: &[&str] = &[",", ";"];
+
+/// Whether a token can begin an operand
+fn starts_operand(token: &Token) -> bool {
+    match token.kind {
+        TokenKind::Ident | TokenKind::Number | TokenKind::Text => true,
+        TokenKind::Comment | TokenKind::Newline => false,
+        TokenKind::Symbol => {
+            let t = token.text;
+            !CLOSERS.contains(&t)
+                && !SEPARATORS.contains(&t)
+                && !POSTFIX.contains(&t)
+                && (infix_bp(t).is_none() || prefix_bp(t).is_some() || t == "|")
+        }
+    }
+}
+
+/// Closing glyph for an opening bracket token
+fn closer_for(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "(" => ")",
+        "⟨" => "⟩",
+        "{" => "}",
+        "[" => "]",
+        "⌈" => "⌉",
+        "⌊" => "⌋",
+        "⟪" => "⟫",
+        "⟦" => "⟧",
+        _ => return None,
+    })
+}
+
+/// Document-level parser over the full token stream
+struct DocParser<'a> {
+    source: &'a str,
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> DocParser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.pos + offset).copied()
+    }
+
+    /// Whether the token at `index` is the first on its line
+    fn line_start(&self, index: usize) -> bool {
+        index == 0 || self.tokens[index - 1].kind == TokenKind::Newline
+    }
+
+    fn block_start(&self, index: usize) -> bool {
+        is_block_start(&self.tokens, index)
+    }
+
+    /// Whether a block header follows whitespace at `index` mid-line
+    ///
+    /// Only checked at document level, where such a `⟦` can't continue an
+    /// expression.
+    fn inline_block_start(&self, index: usize) -> bool {
+        index > 0
+            && self.tokens[index].span.start > self.tokens[index - 1].span.end
+            && is_block_header(&self.tokens, index, false)
+    }
+
+    /// Whether a `γ≔` or `ρ≔` line starts after whitespace at `index`
+    fn inline_preamble_start(&self, index: usize) -> bool {
+        index > 0
+            && self.tokens[index].span.start > self.tokens[index - 1].span.end
+            && matches!(self.tokens[index].text, "γ" | "ρ")
+            && self.tokens.get(index + 1).is_some_and(|t| t.is("≔"))
+    }
+
+    /// Index of the next newline (or end of input) from `self.pos`
+    fn line_end(&self) -> usize {
+        self.tokens[self.pos..]
+            .iter()
+            .position(|t| t.kind == TokenKind::Newline)
+            .map_or(self.tokens.len(), |i| self.pos + i)
+    }
+
+    /// Span and trimmed text from token `from` up to (excluding) token `to`
+    fn raw(&self, from: usize, to: usize) -> (Span, &'a str) {
+        let mut tokens = self.tokens[from..to]
+            .iter()
+            .filter(|t| !matches!(t.kind, TokenKind::Newline | TokenKind::Comment));
+        let Some(first) = tokens.next() else {
+            let at = self
+                .tokens
+                .get(from)
+                .map_or(self.source.len(), |t| t.span.start);
+            return (Span::new(at, at), "");
+        };
+        let last = tokens.next_back().unwrap_or(first);
+        let span = first.span.to(last.span);
+        (span, span.text(self.source))
+    }
+
+    fn comment(token: Token) -> Comment {
+        Comment {
+            text: token.text.trim_start_matches(';').trim().to_string(),
+            span: token.span,
+        }
+    }
+
+    fn document(mut self) -> Parsed {
+        let mut doc = Document {
+            span: Span::new(0, self.source.len()),
+            ..Default::default()
+        };
+        let mut pending_comments = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Newline => self.pos += 1,
+                TokenKind::Comment => {
+                    pending_comments.push(Self::comment(token));
+                    self.pos += 1;
+                }
+                TokenKind::Ident
+                    if token.text.starts_with('𝔸')
+                        && doc.header.is_none()
+                        && doc.blocks.is_empty()
+                        && self.line_start(self.pos) =>
+                {
+                    doc.header = Some(self.header());
+                }
+                TokenKind::Ident
+                    if token.text == "γ" && self.peek_at(1).is_some_and(|t| t.is("≔")) =>
+                {
+                    self.pos += 2;
+                    let end = self.word_end();
+                    let (span, id) = self.raw(self.pos, end);
+                    doc.context = Some(Context {
+                        id: id.to_string(),
+                        span: token.span.to(span),
+                    });
+                    self.pos = end;
+                }
+                TokenKind::Ident
+                    if token.text == "ρ" && self.peek_at(1).is_some_and(|t| t.is("≔")) =>
+                {
+                    doc.references = Some(self.references());
+                }
+                TokenKind::Symbol
+                    if self.block_start(self.pos) || self.inline_block_start(self.pos) =>
+                {
+                    let comments = std::mem::take(&mut pending_comments);
+                    self.block(&mut doc, comments);
+                }
+                _ => {
+                    if let Some(stmt) = self.statement(false) {
+                        doc.claims.push(stmt);
+                    }
+                }
+            }
+        }
+
+        doc.comments = pending_comments;
+        Parsed {
+            document: doc,
+            errors: self.errors,
+        }
+    }
+
+    /// Index of the first comment or newline on the current line, or of the
+    /// next header, context, references or block on the same line
+    fn section_end(&self) -> usize {
+        (self.pos..self.tokens.len())
+            .find(|&i| {
+                matches!(self.tokens[i].kind, TokenKind::Newline | TokenKind::Comment)
+                    || (i > self.pos
+                        && (self.inline_block_start(i) || self.inline_preamble_start(i)))
+            })
+            .unwrap_or(self.tokens.len())
+    }
+
+    /// Index of the first token after a space, or the end of the section
+    ///
+    /// The header and `Id` of the context are single words, so anything
+    /// after a space is a claim.
+    fn word_end(&self) -> usize {
+        let end = self.section_end();
+        (self.pos + 1..end)
+            .find(|&i| self.tokens[i].span.start > self.tokens[i - 1].span.end)
+            .unwrap_or(end)
+    }
+
+    /// Parse `𝔸Ver.Name@Date`
+    fn header(&mut self) -> Header {
+        let end = self.word_end();
+        let (span, raw) = self.raw(self.pos, end);
+        self.pos = end;
+
+        let rest = raw.trim_start_matches('𝔸');
+        let (before_at, date) = match rest.rsplit_once('@') {
+            Some((before, date)) => (before, date.trim()),
+            None => {
+                self.errors
+                    .push(ParseError::new("header is missing `@Date`", span));
+                (rest, "")
+            }
+        };
+
+        let parts: Vec<&str> = before_at.split('.').collect();
+        let numeric = parts
+            .iter()
+            .take_while(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+            .count();
+        let version = parts[..numeric].join(".");
+        let name = parts[numeric..].join(".");
+
+        if version.is_empty() {
+            self.errors
+                .push(ParseError::new("header is missing a version", span));
+        }
+        if name.is_empty() {
+            self.errors
+                .push(ParseError::new("header is missing a name", span));
+        }
+        if !date.is_empty() && !is_date(date) {
+            self.errors.push(ParseError::new(
+                format!("header date `{}` is not YYYY-MM-DD", date),
+                span,
+            ));
+        }
+
+        Header {
+            version,
+            name,
+            date: date.to_string(),
+            span,
+        }
+    }
+
+    /// Parse `ρ≔⟨a,b,c⟩`
+    fn references(&mut self) -> References {
+        let start = self.peek().map(|t| t.span).unwrap_or_default();
+        self.pos += 2;
+        let end = self.section_end();
+
+        if !self.peek().is_some_and(|t| t.is("⟨")) {
+            let (span, raw) = self.raw(self.pos, end);
+            self.errors
+                .push(ParseError::new("expected `⟨` after `ρ≔`", start.to(span)));
+            self.pos = end;
+            return References {
+                items: vec![raw.to_string()]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                span: start.to(span),
+            };
+        }
+
+        let (items, close) = self.delimited_items(self.pos, end, "⟩", &[","]);
+        // Up to the closing `⟩`, or the last token on the line if unclosed
+        let last = if close < end { close } else { end - 1 };
+        let span = start.to(self.tokens[last].span);
+        if close >= end {
+            self.errors
+                .push(ParseError::new("unclosed `⟨` in reference list", span));
+        }
+        self.pos = (close + 1).min(end);
+
+        References {
+            items: items.into_iter().map(|(_, raw)| raw.to_string()).collect(),
+            span,
+        }
+    }
+
+    /// Split the tokens after the opener at `open` into raw items
+    ///
+    /// Returns the items and the index of the closing token (`limit` if unclosed).
+    fn delimited_items(
+        &self,
+        open: usize,
+        limit: usize,
+        close: &str,
+        separators: &[&str],
+    ) -> (Vec<(Span, &'a str)>, usize) {
+        let mut items = Vec::new();
+        let mut depth = 0usize;
+        let mut item_start = open + 1;
+        let mut i = open + 1;
+
+        while i < limit {
+            let token = self.tokens[i];
+            if token.kind == TokenKind::Symbol {
+                if depth == 0 && (token.text == close || separators.contains(&token.text)) {
+                    let (span, raw) = self.raw(item_start, i);
+                    if !raw.is_empty() {
+                        items.push((span, raw));
+                    }
+                    if token.text == close {
+                        return (items, i);
+                    }
+                    item_start = i + 1;
+                } else if closer_for(token.text).is_some() {
+                    depth += 1;
+                } else if CLOSERS.contains(&token.text) {
+                    depth = depth.saturating_sub(1);
+                }
+            }
+            i += 1;
+        }
+
+        let (span, raw) = self.raw(item_start, limit);
+        if !raw.is_empty() {
+            items.push((span, raw));
+        }
+        (items, limit)
+    }
+
+    /// Parse a `⟦Cat:Name⟧{…}` block or the `⟦Ε⟧⟨…⟩` evidence block
+    fn block(&mut self, doc: &mut Document, leading_comments: Vec<Comment>) {
+        let open = self.peek().expect("block starts at `⟦`");
+        let line_end = self.line_end();
+        let Some(close) = (self.pos + 1..line_end).find(|&i| self.tokens[i].is("⟧")) else {
+            self.errors
+                .push(ParseError::new("unclosed `⟦` in block header", open.span));
+            self.pos = line_end;
+            return;
+        };
+
+        let (_, label) = self.raw(self.pos + 1, close);
+        let (category, name) = match label.split_once(':') {
+            Some((category, name)) => (category.trim(), Some(name.trim().to_string())),
+            None => (label, None),
+        };
+        let header_span = open.span.to(self.tokens[close].span);
+        self.pos = close + 1;
+
+        if category == "Ε" && self.peek().is_some_and(|t| t.is("⟨")) {
+            doc.evidence = Some(self.evidence_tuple(header_span, leading_comments));
+            return;
+        }
+
+        if !self.peek().is_some_and(|t| t.is("{")) {
+            self.errors.push(ParseError::new(
+                format!("expected `{{` after `⟦{}⟧`", label),
+                header_span,
+            ));
+            let block = Block {
+                category: category.to_string(),
+                name,
+                body: Vec::new(),
+                leading_comments,
+                span: header_span,
+            };
+            doc.blocks.push(block);
+            return;
+        }
+
+        let open_brace = self.peek().expect("checked above").span;
+        self.pos += 1;
+        let (body, end) = self.block_body(open_brace);
+        let span = header_span.to(end);
+
+        if category == "Ε" {
+            doc.evidence = Some(Evidence {
+                entries: body
+                    .iter()
+                    .filter(|s| !matches!(s.kind, StmtKind::Comment(_)))
+                    .map(|s| self.evidence_entry(s.span))
+                    .collect(),
+                leading_comments,
+                span,
+            });
+            return;
+        }
+
+        doc.blocks.push(Block {
+            category: category.to_string(),
+            name,
+            body,
+            leading_comments,
+            span,
+        });
+    }
+
+    /// Parse statements up to the closing `}`
+    fn block_body(&mut self, open: Span) -> (Vec<Stmt>, Span) {
+        let mut body = Vec::new();
+
+        loop {
+            let Some(token) = self.peek() else {
+                self.errors
+                    .push(ParseError::new("unclosed block: expected `}`", open));
+                let end = self.tokens.last().map_or(open, |t| t.span);
+                return (body, end);
+            };
+
+            match token.kind {
+                TokenKind::Newline => self.pos += 1,
+                TokenKind::Comment => {
+                    let comment = Self::comment(token);
+                    body.push(Stmt {
+                        span: comment.span,
+                        kind: StmtKind::Comment(comment),
+                        comment: None,
+                    });
+                    self.pos += 1;
+                }
+                TokenKind::Symbol if token.text == ";" => self.pos += 1,
+                TokenKind::Symbol if token.text == "}" => {
+                    self.pos += 1;
+                    return (body, token.span);
+                }
+                TokenKind::Symbol if self.block_start(self.pos) => {
+                    self.errors
+                        .push(ParseError::new("unclosed block: expected `}`", open));
+                    let end = self.tokens[self.pos - 1].span;
+                    return (body, end);
+                }
+                _ => {
+                    if let Some(stmt) = self.statement(true) {
+                        body.push(stmt);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse `⟨entry;entry;…⟩` after `⟦Ε⟧`
+    fn evidence_tuple(&mut self, header: Span, leading_comments: Vec<Comment>) -> Evidence {
+        // The tuple may span lines, up to the next block
+        let limit = (self.pos + 1..self.tokens.len())
+            .find(|&i| self.block_start(i))
+            .unwrap_or(self.tokens.len());
+        let (items, close) = self.delimited_items(self.pos, limit, "⟩", &[";"]);
+
+        let span = if close < limit {
+            header.to(self.tokens[close].span)
+        } else {
+            self.errors
+                .push(ParseError::new("unclosed `⟨` in evidence block", header));
+            header.to(self.raw(self.pos, limit).0)
+        };
+        self.pos = (close + 1).min(limit);
+
+        Evidence {
+            entries: items
+                .into_iter()
+                .map(|(span, _)| self.evidence_entry(span))
+                .collect(),
+            leading_comments,
+            span,
+        }
+    }
+
+    /// Split an evidence entry into `name≜value` or keep it as a claim
+    fn evidence_entry(&self, span: Span) -> EvidenceEntry {
+        let raw = span.text(self.source);
+        let split = tokenize(raw)
+            .into_iter()
+            .scan(0isize, |depth, t| {
+                if closer_for(t.text).is_some() {
+                    *depth += 1;
+                } else if CLOSERS.contains(&t.text) {
+                    *depth -= 1;
+                }
+                Some((*depth, t))
+            })
+            .find(|(depth, t)| *depth == 0 && (t.is("≜") || t.is("≔") || t.is("=")));
+
+        match split {
+            Some((_, op)) if op.span.start > 0 => EvidenceEntry {
+                name: Some(raw[..op.span.start].trim().to_string()),
+                value: raw[op.span.end..].trim().to_string(),
+                span,
+            },
+            _ => EvidenceEntry {
+                name: None,
+                value: raw.to_string(),
+                span,
+            },
+        }
+    }
+
+    /// Collect and parse one statement starting at the current token
+    ///
+    /// The statement ends at `;` or a newline outside brackets, at a comment,
+    /// or (inside a block) at the `}` closing the block.
+    fn statement(&mut self, in_block: bool) -> Option<Stmt> {
+        let start = self.pos;
+        let mut stack: Vec<&'static str> = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Newline if stack.is_empty() => break,
+                // A new block on the next line means this bracket was never closed
+                TokenKind::Newline
+                    if self.block_start(self.pos + 1)
+                        || self.peek_at(1).is_some_and(|t| t.is("}")) =>
+                {
+                    break
+                }
+                TokenKind::Comment if stack.is_empty() => break,
+                TokenKind::Symbol
+                    if !in_block && stack.is_empty() && self.inline_block_start(self.pos) =>
+                {
+                    break
+                }
+                TokenKind::Symbol => {
+                    let t = token.text;
+                    if stack.is_empty() && (t == ";" || (in_block && t == "}")) {
+                        break;
+                    }
+                    if let Some(close) = closer_for(t) {
+                        stack.push(close);
+                    } else if stack.last() == Some(&t) {
+                        stack.pop();
+                    }
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+
+        let tokens: Vec<Token<'a>> = self.tokens[start..self.pos]
+            .iter()
+            .filter(|t| !matches!(t.kind, TokenKind::Newline | TokenKind::Comment))
+            .copied()
+            .collect();
+        if tokens.is_empty() {
+            // Only reachable at top level on a stray separator
+            self.pos = self.pos.max(start + 1);
+            return None;
+        }
+
+        let span = tokens[0].span.to(tokens[tokens.len() - 1].span);
+        let kind = match statement_kind(&tokens) {
+            Ok(kind) => kind,
+            Err(error) => {
+                self.errors.push(error);
+                StmtKind::Error(span.text(self.source).to_string())
+            }
+        };
+
+        let comment = match self.peek() {
+            Some(token) if token.kind == TokenKind::Comment => {
+                self.pos += 1;
+                Some(Self::comment(token))
+            }
+            _ => None,
+        };
+
+        Some(Stmt {
+            kind,
+            comment,
+            span,
+        })
+    }
+}
+
+/// Whether a `⟦Cat⟧{` or `⟦Ε⟧⟨` block header starts at `tokens[index]`
+///
+/// `⟦` at the start of a line can also begin an expression such as
+/// `⟦⟧≜{…}`, so the header must be closed on the same line, non-empty,
+/// and followed by the block body.
+pub(crate) fn is_block_start(tokens: &[Token], index: usize) -> bool {
+    let line_start = index == 0 || tokens[index - 1].kind == TokenKind::Newline;
+    line_start && is_block_header(tokens, index, true)
+}
+
+/// Whether `tokens[index]` opens a block header, wherever it is on the line,
+/// counting a header left unclosed on its line if `unclosed` is set
+fn is_block_header(tokens: &[Token], index: usize, unclosed: bool) -> bool {
+    if !tokens.get(index).is_some_and(|t| t.is("⟦")) {
+        return false;
+    }
+    let Some(close) = tokens[index + 1..]
+        .iter()
+        .take_while(|t| t.kind != TokenKind::Newline)
+        .position(|t| t.is("⟧"))
+        .map(|i| index + 1 + i)
+    else {
+        // Unclosed header: still a block, reported when parsed
+        return unclosed;
+    };
+    close > index + 1
+        && tokens
+            .get(close + 1)
+            .is_some_and(|t| t.is("{") || t.is("⟨"))
+}
+
+/// Classify a statement as definition, rule or plain expression
+fn statement_kind(tokens: &[Token]) -> Result<StmtKind, ParseError> {
+    // Def≜Sym∘('≜'|'≔')∘Expr: split at the first top-level definition operator,
+    // unless a binder, lambda or sequence comma comes first and owns it
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Symbol {
+            continue;
+        }
+        let t = token.text;
+        if closer_for(t).is_some() {
+            depth += 1;
+        } else if CLOSERS.contains(&t) {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && (BINDERS.contains(&t) || t == "λ" || t == ",") {
+            break;
+        } else if depth == 0 && (t == "≜" || t == "≔") && i > 0 {
+            if i + 1 == tokens.len() {
+                return Err(ParseError::new(
+                    format!("expected a value after `{}`", t),
+                    token.span,
+                ));
+            }
+            return Ok(StmtKind::Def {
+                target: parse_expr(&tokens[..i])?,
+                op: t.to_string(),
+                value: parse_expr(&tokens[i + 1..])?,
+            });
+        }
+    }
+
+    let expr = parse_expr(tokens)?;
+    Ok(match expr.kind {
+        ExprKind::Binary { op, lhs, rhs } if op == "⇒" => StmtKind::Rule {
+            premise: *lhs,
+            consequent: *rhs,
+        },
+        kind => StmtKind::Expr(Expr {
+            kind,
+            span: expr.span,
+        }),
+    })
+}
+
+/// Parse a token slice as a single expression
+fn parse_expr(tokens: &[Token]) -> Result<Expr, ParseError> {
+    let mut parser = ExprParser {
+        tokens,
+        pos: 0,
+        bar_depth: 0,
+        norm_depth: 0,
+    };
+    let expr = parser.expr(0)?;
+    match parser.peek() {
+        Some(token) => Err(ParseError::new(
+            format!("unexpected `{}`", token.text),
+            token.span,
+        )),
+        None => Ok(expr),
+    }
+}
+
+/// Precedence-climbing expression parser over one statement's tokens
+struct ExprParser<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    pos: usize,
+    /// Non-zero inside `|…|`, where a bare `|` closes instead of alternating
+    bar_depth: usize,
+    /// Non-zero inside `‖…‖`
+    norm_depth: usize,
+}
+
+impl<'a> ExprParser<'_, 'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    /// Span used for errors at the end of the statement
+    fn end_span(&self) -> Span {
+        self.tokens
+            .last()
+            .map_or(Span::default(), |t| Span::new(t.span.end, t.span.end))
+    }
+
+    fn expr(&mut self, min_bp: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.prefix()?;
+
+        while let Some(token) = self.peek() {
+            let t = token.text;
+            if token.kind == TokenKind::Symbol {
+                if (t == "|" && self.bar_depth > 0) || (t == "‖" && self.norm_depth > 0) {
+                    break;
+                }
+
+                if let Some((lbp, rbp)) = infix_bp(t) {
+                    if lbp < min_bp {
+                        break;
+                    }
+                    self.pos += 1;
+                    // An operator with nothing after it is a postfix use, as in `𝔄*→Sym`
+                    if !self.peek().is_some_and(|t| starts_operand(&t)) {
+                        lhs = postfix(t, lhs, token.span);
+                        continue;
+                    }
+                    let rhs = self.expr(rbp)?;
+                    lhs = Expr {
+                        span: lhs.span.to(rhs.span),
+                        kind: ExprKind::Binary {
+                            op: t.to_string(),
+                            lhs: Box::new(lhs),
+                            rhs: Box::new(rhs),
+                        },
+                    };
+                    continue;
+                }
+
+                if POSTFIX.contains(&t) {
+                    if APPLY_BP < min_bp {
+                        break;
+                    }
+                    self.pos += 1;
+                    lhs = postfix(t, lhs, token.span);
+                    continue;
+                }
+
+                // `f(x)` and `T[d]` when the bracket touches the callee
+                if (t == "(" || t == "[") && token.span.start == lhs.span.end {
+                    if APPLY_BP < min_bp {
+                        break;
+                    }
+                    self.pos += 1;
+                    let (args, end) = self.group_items(if t == "(" { ")" } else { "]" }, token)?;
+                    let span = lhs.span.to(end);
+                    let func = Box::new(lhs);
+                    lhs = Expr {
+                        kind: if t == "(" {
+                            ExprKind::Apply { func, args }
+                        } else {
+                            ExprKind::Index { base: func, args }
+                        },
+                        span,
+                    };
+                    continue;
+                }
+            }
+
+            if starts_operand(&token) {
+                if APPLY_BP < min_bp {
+                    break;
+                }
+                let arg = self.expr(APPLY_BP + 1)?;
+                lhs = Expr {
+                    span: lhs.span.to(arg.span),
+                    kind: ExprKind::Juxtapose {
+                        func: Box::new(lhs),
+                        arg: Box::new(arg),
+                    },
+                };
+                continue;
+            }
+
+            break;
+        }
+
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, ParseError> {
+        let Some(token) = self.next() else {
+            return Err(ParseError::new("expected an expression", self.end_span()));
+        };
+        let text = token.text.to_string();
+        let atom = |kind| {
+            Ok(Expr {
+                kind,
+                span: token.span,
+            })
+        };
+
+        match token.kind {
+            TokenKind::Ident => atom(ExprKind::Ident(text)),
+            TokenKind::Number => atom(ExprKind::Number(text)),
+            TokenKind::Text => atom(ExprKind::Text(text)),
+            TokenKind::Comment | TokenKind::Newline => {
+                Err(ParseError::new("expected an expression", token.span))
+            }
+            TokenKind::Symbol => {
+                let t = token.text;
+                let separator_follows = self.peek().is_some_and(|t| SEPARATORS.contains(&t.text));
+                // Bracket glyphs listed as values, as in `{⟨,⟩,⟦,⟧}`
+                if (CLOSERS.contains(&t) || closer_for(t).is_some()) && separator_follows {
+                    return atom(ExprKind::Symbol(text));
+                }
+                if CLOSERS.contains(&t) || SEPARATORS.contains(&t) {
+                    return Err(ParseError::new(format!("unexpected `{}`", t), token.span));
+                }
+                let operand_follows = self.peek().is_some_and(|t| starts_operand(&t));
+
+                if t == "λ" {
+                    return self.lambda(token);
+                }
+                if BINDERS.contains(&t) && operand_follows {
+                    return self.quantifier(token);
+                }
+                if let Some(close) = closer_for(t) {
+                    let (items, end) = self.group_items(close, token)?;
+                    let delim = t.chars().next().and_then(Delim::from_open);
+                    return Ok(Expr {
+                        kind: ExprKind::Group {
+                            delim: delim.expect("opening bracket has a delimiter"),
+                            items,
+                        },
+                        span: token.span.to(end),
+                    });
+                }
+                if (t == "|" || t == "‖") && operand_follows {
+                    return self.self_closing_group(token);
+                }
+                if let (Some(bp), true) = (prefix_bp(t), operand_follows) {
+                    let operand = self.expr(bp)?;
+                    return Ok(Expr {
+                        span: token.span.to(operand.span),
+                        kind: ExprKind::Unary {
+                            op: text,
+                            operand: Box::new(operand),
+                        },
+                    });
+                }
+
+                // Glyphs used as values, and operator sections such as `(≥)`
+                atom(ExprKind::Symbol(text))
+            }
+        }
+    }
+
+    /// `λx y.body`; a bare `λ` is kept as a symbol
+    fn lambda(&mut self, token: Token) -> Result<Expr, ParseError> {
+        let mut params = Vec::new();
+        while let Some(param) = self.peek().filter(|t| t.kind == TokenKind::Ident) {
+            params.push(param.text.to_string());
+            self.pos += 1;
+        }
+        if params.is_empty() {
+            return Ok(Expr {
+                kind: ExprKind::Symbol(token.text.to_string()),
+                span: token.span,
+            });
+        }
+
+        match self.next() {
+            Some(dot) if dot.is(".") => {}
+            other => {
+                let span = other.map_or(self.end_span(), |t| t.span);
+                return Err(ParseError::new(
+                    "expected `.` after lambda parameters",
+                    span,
+                ));
+            }
+        }
+
+        let body = self.expr(ITEM_BP)?;
+        Ok(Expr {
+            span: token.span.to(body.span),
+            kind: ExprKind::Lambda {
+                params,
+                body: Box::new(body),
+            },
+        })
+    }
+
+    /// `∀x∈S:body`; without `:` the binder is kept as a prefix operand
+    fn quantifier(&mut self, token: Token) -> Result<Expr, ParseError> {
+        let binder = self.expr(BINDER_BP)?;
+        let quantifier = token.text.to_string();
+
+        if !self.peek().is_some_and(|t| t.is(":")) {
+            return Ok(Expr {
+                span: token.span.to(binder.span),
+                kind: ExprKind::Unary {
+                    op: quantifier,
+                    operand: Box::new(binder),
+                },
+            });
+        }
+        self.pos += 1;
+
+        let body = self.expr(ITEM_BP)?;
+        Ok(Expr {
+            span: token.span.to(body.span),
+            kind: ExprKind::Quant {
+                quantifier,
+                binder: Box::new(binder),
+                body: Box::new(body),
+            },
+        })
+    }
+
+    /// `|x|` and `‖x‖`, whose opening and closing glyphs are the same
+    fn self_closing_group(&mut self, token: Token) -> Result<Expr, ParseError> {
+        let is_bar = token.text == "|";
+        if is_bar {
+            self.bar_depth += 1;
+        } else {
+            self.norm_depth += 1;
+        }
+        let result = self.group_items(token.text, token);
+        if is_bar {
+            self.bar_depth -= 1;
+        } else {
+            self.norm_depth -= 1;
+        }
+
+        let (items, end) = result?;
+        Ok(Expr {
+            kind: ExprKind::Group {
+                delim: if is_bar { Delim::Bar } else { Delim::Norm },
+                items,
+            },
+            span: token.span.to(end),
+        })
+    }
+
+    /// Comma- or semicolon-separated items up to `close`
+    ///
+    /// The opening token has already been consumed. Returns the items and
+    /// the span of the closing token.
+    fn group_items(&mut self, close: &str, open: Token) -> Result<(Vec<Expr>, Span), ParseError> {
+        // A nested bracket resets the bar context: `|{t|t∈S}|`
+        let saved = (self.bar_depth, self.norm_depth);
+        if close != "|" && close != "‖" {
+            self.bar_depth = 0;
+            self.norm_depth = 0;
+        }
+
+        let result = self.group_items_inner(close, open);
+        (self.bar_depth, self.norm_depth) = saved;
+        result
+    }
+
+    fn group_items_inner(
+        &mut self,
+        close: &str,
+        open: Token,
+    ) -> Result<(Vec<Expr>, Span), ParseError> {
+        let mut items = Vec::new();
+
+        if let Some(token) = self.peek().filter(|t| t.is(close)) {
+            self.pos += 1;
+            return Ok((items, token.span));
+        }
+
+        loop {
+            items.push(self.expr(ITEM_BP)?);
+            match self.next() {
+                Some(token) if token.is(close) => return Ok((items, token.span)),
+                Some(token) if token.is(",") || token.is(";") => {}
+                Some(token) => {
+                    return Err(ParseError::new(
+                        format!("expected `,` or `{}`, found `{}`", close, token.text),
+                        token.span,
+                    ));
+                }
+                None => {
+                    return Err(ParseError::new(
+                        format!("unclosed `{}`: expected `{}`", open.text, close),
+                        open.span,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Wrap `operand` in a postfix operator
+fn postfix(op: &str, operand: Expr, op_span: Span) -> Expr {
+    Expr {
+        span: operand.span.to(op_span),
+        kind: ExprKind::Postfix {
+            op: op.to_string(),
+            operand: Box::new(operand),
+        },
+    }
+}
+
+/// Whether `s` looks like `YYYY-MM-DD`
+fn is_date(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    parts.len() == 3
+        && [4, 2, 2]
+            .iter()
+            .zip(&parts)
+            .all(|(len, part)| part.len() == *len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "𝔸5.1.auth@2026-01-09
+γ≔auth.rules
+ρ≔⟨auth,types,rules⟩
+⊢ND∧CAT
+
+;; Invariants
+⟦Ω:Meta⟧{ domain≜auth; ∀D∈AISP:Ambig(D)<0.02 }
+⟦Σ:Types⟧{
+  User≜⟨id:ℕ,name:𝕊⟩
+}
+⟦Γ:Rules⟧{
+  ∀u∈User:valid(u)⇒access(u)  ;; [allow]
+  authenticated(u)⇒allow(u)
+}
+⟦Λ:Funcs⟧{ f≜λx.x+1 }
+⟦Ε⟧⟨δ≜0.82;φ≜100;τ≜◊⁺⁺;⊢valid;∎⟩";
+
+    fn expr(source: &str) -> Expr {
+        parse_expr(&tokenize(source)).expect("expression should parse")
+    }
+
+    /// Render an expression with explicit parentheses to check structure
+    fn sexpr(e: &Expr) -> String {
+        match &e.kind {
+            ExprKind::Ident(s) | ExprKind::Number(s) | ExprKind::Text(s) | ExprKind::Symbol(s) => {
+                s.clone()
+            }
+            ExprKind::Unary { op, operand } => format!("({} {})", op, sexpr(operand)),
+            ExprKind::Postfix { op, operand } => format!("({} {})", sexpr(operand), op),
+            ExprKind::Binary { op, lhs, rhs } => {
+                format!("({} {} {})", op, sexpr(lhs), sexpr(rhs))
+            }
+            ExprKind::Lambda { params, body } => {
+                format!("(λ {} {})", params.join(" "), sexpr(body))
+            }
+            ExprKind::Quant {
+                quantifier,
+                binder,
+                body,
+            } => format!("({} {} {})", quantifier, sexpr(binder), sexpr(body)),
+            ExprKind::Apply { func, args } | ExprKind::Index { base: func, args } => format!(
+                "({} {})",
+                sexpr(func),
+                args.iter().map(sexpr).collect::<Vec<_>>().join(" ")
+            ),
+            ExprKind::Juxtapose { func, arg } => format!("({} {})", sexpr(func), sexpr(arg)),
+            ExprKind::Group { delim, items } => {
+                let (open, close) = delim.glyphs();
+                format!(
+                    "{}{}{}",
+                    open,
+                    items.iter().map(sexpr).collect::<Vec<_>>().join(","),
+                    close
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_document_structure() {
+        let parsed = parse(DOC);
+        assert!(parsed.is_ok(), "unexpected errors: {:?}", parsed.errors);
+        let doc = parsed.document;
+
+        let header = doc.header.unwrap();
+        assert_eq!(header.version, "5.1");
+        assert_eq!(header.name, "auth");
+        assert_eq!(header.date, "2026-01-09");
+        assert_eq!(doc.context.unwrap().id, "auth.rules");
+        assert_eq!(
+            doc.references.unwrap().items,
+            vec!["auth", "types", "rules"]
+        );
+        assert_eq!(doc.claims.len(), 1);
+
+        let categories: Vec<&str> = doc.blocks.iter().map(|b| b.category.as_str()).collect();
+        assert_eq!(categories, vec!["Ω", "Σ", "Γ", "Λ"]);
+        assert_eq!(doc.blocks[0].name.as_deref(), Some("Meta"));
+        assert_eq!(doc.blocks[0].leading_comments[0].text, "Invariants");
+        assert_eq!(doc.blocks[0].body.len(), 2);
+
+        let rules = &doc.blocks[2].body;
+        assert!(
+            matches!(rules[0].kind, StmtKind::Expr(_)),
+            "quantified rule"
+        );
+        assert_eq!(rules[0].comment.as_ref().unwrap().text, "[allow]");
+        assert!(matches!(rules[1].kind, StmtKind::Rule { .. }));
+
+        let evidence = doc.evidence.unwrap();
+        assert_eq!(evidence.get("δ"), Some("0.82"));
+        assert_eq!(evidence.get("τ"), Some("◊⁺⁺"));
+        assert_eq!(evidence.entries.len(), 5);
+        assert_eq!(evidence.entries[3].value, "⊢valid");
+    }
+
+    #[test]
+    fn test_parse_single_line_document() {
+        let source = "𝔸5.1.x@2026-01-09 γ≔x ρ≔⟨a,b⟩ ⊢sound ⟦Λ:Funcs⟧{f≜λx.x;g≜f} ⟦Ε⟧⟨δ≜0.5⟩";
+        let parsed = parse(source);
+        assert!(parsed.is_ok(), "unexpected errors: {:?}", parsed.errors);
+        let doc = parsed.document;
+
+        assert_eq!(doc.header.unwrap().date, "2026-01-09");
+        assert_eq!(doc.context.unwrap().id, "x");
+        assert_eq!(doc.references.unwrap().items, vec!["a", "b"]);
+        assert_eq!(doc.claims.len(), 1);
+        assert_eq!(doc.blocks.len(), 1);
+        assert_eq!(doc.blocks[0].body.len(), 2);
+        assert_eq!(doc.evidence.unwrap().get("δ"), Some("0.5"));
+
+        // Inside a block, `⟦` after a space stays part of the statement
+        let parsed = parse("⟦Σ:Types⟧{T≜ ⟦Ω⟧{a}}");
+        assert_eq!(parsed.document.blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert_eq!(sexpr(&expr("A∧B∨C")), "(∨ (∧ A B) C)");
+        assert_eq!(sexpr(&expr("A∨B∧C")), "(∨ A (∧ B C))");
+        assert_eq!(sexpr(&expr("A→B→C")), "(→ A (→ B C))");
+        assert_eq!(sexpr(&expr("A∧B∧C")), "(∧ (∧ A B) C)");
+        assert_eq!(sexpr(&expr("f∘g∘h")), "(∘ f (∘ g h))");
+        assert_eq!(sexpr(&expr("¬A∧B")), "(∧ (¬ A) B)");
+        assert_eq!(sexpr(&expr("x∈S⇒x⊕y≡z")), "(⇒ (∈ x S) (≡ (⊕ x y) z))");
+        assert_eq!(sexpr(&expr("A→B|C")), "(| (→ A B) C)");
+    }
+
+    #[test]
+    fn test_binders_and_application() {
+        assert_eq!(sexpr(&expr("∀x∈S:P(x)∧Q")), "(∀ (∈ x S) (∧ (P x) Q))");
+        assert_eq!(sexpr(&expr("∃!x:f(x)≡0")), "(∃! x (≡ (f x) 0))");
+        assert_eq!(sexpr(&expr("λf s.hd s")), "(λ f s (hd s))");
+        assert_eq!(sexpr(&expr("|b⃗|≥2")), "(≥ |b⃗| 2)");
+        assert_eq!(
+            sexpr(&expr("|{t∈τ⃗|t.k∈𝔄}|")),
+            "|{(| (∈ t τ⃗) (∈ (. t k) 𝔄))}|"
+        );
+        assert_eq!(sexpr(&expr("𝔄*→Sym")), "(→ (𝔄 *) Sym)");
+        assert_eq!(sexpr(&expr("ℝ[0,1]")), "(ℝ 0 1)");
+    }
+
+    #[test]
+    fn test_definitions_and_rules() {
+        let kind = statement_kind(&tokenize("f≜λx.x")).unwrap();
+        assert!(matches!(kind, StmtKind::Def { ref op, .. } if op == "≜"));
+
+        let kind = statement_kind(&tokenize("∀x:x≜5")).unwrap();
+        assert!(matches!(kind, StmtKind::Expr(_)), "binder owns the ≜");
+
+        let kind = statement_kind(&tokenize("A∧B⇒C")).unwrap();
+        assert!(matches!(kind, StmtKind::Rule { .. }));
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let source = "𝔸5.1.x@2026-01-09
+⟦Σ:Types⟧{
+  U≜ℕ
+  T≜(a,b
+⟦Γ:Rules⟧{ x≜ ; y≜1 }
+⟦Ε⟧⟨δ≜0.5";
+        let parsed = parse(source);
+        let messages: Vec<&str> = parsed.errors.iter().map(|e| e.message.as_str()).collect();
+
+        assert!(messages.iter().any(|m| m.contains("unclosed `(`")));
+        assert!(messages.iter().any(|m| m.contains("unclosed block")));
+        assert!(messages.iter().any(|m| m.contains("expected a value")));
+        assert!(messages.iter().any(|m| m.contains("evidence")));
+
+        // Parsing continued past every error
+        let doc = parsed.document;
+        assert_eq!(doc.blocks.len(), 2);
+        assert!(matches!(doc.blocks[0].body[0].kind, StmtKind::Def { .. }));
+        assert!(matches!(doc.blocks[0].body[1].kind, StmtKind::Error(_)));
+        assert!(matches!(doc.blocks[1].body[1].kind, StmtKind::Def { .. }));
+        assert_eq!(doc.evidence.unwrap().get("δ"), Some("0.5"));
+    }
+
+    #[test]
+    fn test_header_errors() {
+        let parsed = parse("𝔸5.1.x\n⟦Ω⟧{}");
+        assert_eq!(parsed.errors.len(), 1);
+        assert!(parsed.errors[0].message.contains("@Date"));
+
+        let parsed = parse("𝔸x@2026-1-9");
+        assert_eq!(parsed.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_spans() {
+        let parsed = parse(DOC);
+        let block = &parsed.document.blocks[1];
+        assert_eq!(block.span.line_col(DOC), (8, 1));
+        assert!(block.span.text(DOC).starts_with("⟦Σ:Types⟧{"));
+        assert!(block.span.text(DOC).ends_with('}'));
+
+        let StmtKind::Def { value, .. } = &block.body[0].kind else {
+            panic!("expected a definition");
+        };
+        assert_eq!(value.span.text(DOC), "⟨id:ℕ,name:𝕊⟩");
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let parsed = parse("⟦Λ:Funcs⟧{f≜λx.x+1}\n⟦Γ:Rules⟧{a⇒(b");
+        let json = parsed.to_json();
+        assert_eq!(json["version"], AST_VERSION);
+        let value = &json["document"]["blocks"][0]["body"][0]["kind"];
+        assert_eq!(value["type"], "def");
+        assert_eq!(value["data"]["op"], "≜");
+        assert_eq!(value["data"]["value"]["kind"]["type"], "lambda");
+
+        assert_eq!(Parsed::from_json(&json.to_string()).unwrap(), parsed);
+
+        let mut old = json.clone();
+        old["version"] = json!(AST_VERSION + 1);
+        assert!(Parsed::from_json(&old.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_specification_prompt() {
+        let source: &str = &crate::claude::AISP_PROMPT;
+        let parsed = parse(source);
+        assert!(parsed.is_ok(), "unexpected errors: {:?}", parsed.errors);
+
+        let doc = parsed.document;
+        assert_eq!(doc.header.as_ref().unwrap().name, "complete");
+        for category in ["Ω", "Σ", "Γ", "Λ", "Ε"] {
+            assert!(doc.has_block(category), "missing ⟦{}⟧", category);
+        }
+    }
+}
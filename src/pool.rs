@@ -0,0 +1,194 @@
+//! Warm Sessions
+//!
+//! Starting the Claude CLI and sending it the multi-kilobyte system prompt
+//! costs more than many conversions do. A [`SessionPool`] keeps a few
+//! sessions running and hands them out one request at a time, so batch jobs
+//! pay for start-up once per session instead of once per conversion. Every
+//! request starts from a clean context; sessions only keep the system prompt.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, Semaphore};
+
+/// A session's answer to one prompt
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reply {
+    /// Text response
+    pub text: String,
+    /// Approximate tokens used (if available)
+    pub tokens_used: Option<usize>,
+    /// Cost of the request in US dollars (if available)
+    pub cost_usd: Option<f64>,
+}
+
+/// A running LLM session that answers one prompt at a time
+#[async_trait]
+pub trait WarmSession: Send {
+    /// Answer `prompt` without the context of earlier prompts
+    async fn ask(&mut self, prompt: &str) -> Result<Reply>;
+
+    /// Shut the session down
+    async fn close(&mut self);
+}
+
+/// Starts the sessions of a [`SessionPool`]
+#[async_trait]
+pub trait SessionFactory: Send + Sync {
+    type Session: WarmSession + 'static;
+
+    /// Start a session, ready to be asked
+    async fn start(&self) -> Result<Self::Session>;
+}
+
+/// How the sessions of a pool have been used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// Sessions started, including replacements for failed ones
+    pub started: usize,
+    /// Requests answered by a session that was already running
+    pub reused: usize,
+    /// Sessions shut down after a failed or cancelled request
+    pub failed: usize,
+}
+
+impl std::ops::Add for PoolStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            started: self.started + other.started,
+            reused: self.reused + other.reused,
+            failed: self.failed + other.failed,
+        }
+    }
+}
+
+/// Up to `size` warm sessions shared by concurrent requests
+///
+/// Sessions are started on demand, or ahead of time with
+/// [`SessionPool::warm_up`]. Requests beyond `size` wait for a session to
+/// become idle. A session whose request fails or is cancelled is shut down
+/// rather than reused, and replaced on the next request.
+pub struct SessionPool<F: SessionFactory> {
+    factory: F,
+    size: usize,
+    idle: Mutex<Vec<F::Session>>,
+    slots: Semaphore,
+    started: AtomicUsize,
+    reused: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl<F: SessionFactory> SessionPool<F> {
+    /// Create an empty pool of at most `size` sessions (at least one)
+    pub fn new(factory: F, size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            factory,
+            size,
+            idle: Mutex::new(Vec::new()),
+            slots: Semaphore::new(size),
+            started: AtomicUsize::new(0),
+            reused: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Maximum number of sessions
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Start sessions until the pool is full, waiting for running requests
+    pub async fn warm_up(&self) -> Result<()> {
+        let _all = self
+            .slots
+            .acquire_many(self.size as u32)
+            .await
+            .expect("pool semaphore closed");
+        let mut idle = self.idle.lock().await;
+        while idle.len() < self.size {
+            idle.push(self.factory.start().await?);
+            self.started.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Answer `prompt` on an idle session, starting one if there is none
+    pub async fn ask(&self, prompt: &str) -> Result<Reply> {
+        let _slot = self.slots.acquire().await.expect("pool semaphore closed");
+        let idle = self.idle.lock().await.pop();
+        let session = match idle {
+            Some(session) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                session
+            }
+            None => {
+                let session = self.factory.start().await?;
+                self.started.fetch_add(1, Ordering::Relaxed);
+                session
+            }
+        };
+
+        let mut checkout = Checkout {
+            pool: self,
+            session: Some(session),
+        };
+        let answer = checkout.session().ask(prompt).await;
+        if answer.is_ok() {
+            let mut idle = self.idle.lock().await;
+            idle.extend(checkout.session.take());
+        }
+        // Otherwise the checkout shuts the session down
+        answer
+    }
+
+    /// Usage so far
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            started: self.started.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Shut down the idle sessions; the pool starts new ones if asked again
+    pub async fn close(&self) {
+        let sessions: Vec<F::Session> = self.idle.lock().await.drain(..).collect();
+        for mut session in sessions {
+            session.close().await;
+        }
+    }
+}
+
+/// A session taken out of a pool for one request
+///
+/// Dropping the checkout with the session still in it, because the request
+/// failed or its future was dropped mid-exchange, counts the session as
+/// failed and shuts it down in the background, since it may still be
+/// answering.
+struct Checkout<'a, F: SessionFactory> {
+    pool: &'a SessionPool<F>,
+    session: Option<F::Session>,
+}
+
+impl<F: SessionFactory> Checkout<'_, F> {
+    fn session(&mut self) -> &mut F::Session {
+        self.session.as_mut().expect("session already returned")
+    }
+}
+
+impl<F: SessionFactory> Drop for Checkout<'_, F> {
+    fn drop(&mut self) {
+        let Some(mut session) = self.session.take() else {
+            return;
+        };
+        self.pool.failed.fetch_add(1, Ordering::Relaxed);
+        // Without a runtime the session is dropped as is
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move { session.close().await });
+        }
+    }
+}
@@ -2,10 +2,16 @@
 //!
 //! Defines the interface for LLM-based AISP conversion providers.
 
+use crate::alignment::SourceQuote;
+use crate::clarify::ClarificationQuestion;
+use crate::tools::ValidationTools;
 use anyhow::Result;
 use async_trait::async_trait;
 use rosetta_aisp::{ConversionResult, ConversionTier, TokenStats};
 
+/// Confidence assigned to LLM-produced conversions
+pub(crate) const LLM_CONFIDENCE: f64 = 0.95;
+
 /// LLM provider trait for fallback conversions
 ///
 /// Implement this trait to add support for different LLM providers.
@@ -29,10 +35,109 @@ pub trait LlmProvider: Send + Sync {
         use_aisp_prompt: bool,
     ) -> Result<LlmResult>;
 
+    /// Convert prose to a JSON [`crate::StructuredDocument`]
+    ///
+    /// Used in JSON mode, where the crate renders the AISP itself. Takes the
+    /// same arguments as [`LlmProvider::convert`] and returns the raw JSON
+    /// as the output. The default implementation returns `None` for
+    /// providers that can't produce JSON, which get the free-text path.
+    async fn convert_structured(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+    ) -> Result<Option<LlmResult>> {
+        Ok(None)
+    }
+
+    /// Convert prose to AISP over several turns, checking drafts with `tools`
+    ///
+    /// Used in agentic mode. Takes the same arguments as
+    /// [`LlmProvider::convert`] plus the tools to offer the model. The
+    /// default implementation returns `None` for providers without tool
+    /// use, which get a single-turn conversion.
+    async fn convert_agentic(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+        _tools: &ValidationTools,
+    ) -> Result<Option<LlmResult>> {
+        Ok(None)
+    }
+
+    /// Translate isolated prose spans to AISP
+    ///
+    /// Used by targeted fallback to fill in only the phrases the deterministic
+    /// pass could not map. Returns one translation per span, in order.
+    ///
+    /// The default implementation converts each span independently with
+    /// [`LlmProvider::convert`] at the minimal tier; providers that can
+    /// batch several spans into one request should override it.
+    async fn convert_spans(
+        &self,
+        spans: &[SpanRequest],
+        use_aisp_prompt: bool,
+    ) -> Result<Vec<String>> {
+        let mut translations = Vec::with_capacity(spans.len());
+        for span in spans {
+            let result = self
+                .convert(
+                    &span.text,
+                    ConversionTier::Minimal,
+                    &[],
+                    None,
+                    use_aisp_prompt,
+                )
+                .await?;
+            translations.push(result.output);
+        }
+        Ok(translations)
+    }
+
+    /// Ask about the parts of `prose` that need a human decision
+    ///
+    /// Returns one question per ambiguity, each with at least two candidate
+    /// interpretations. The default implementation finds none, leaving
+    /// clarification to the deterministic analysis.
+    async fn clarify(&self, _prose: &str) -> Result<Vec<ClarificationQuestion>> {
+        Ok(Vec::new())
+    }
+
+    /// Quote the prose each statement of `aisp` was converted from
+    ///
+    /// Used to align LLM outputs, whose statements need not resemble the
+    /// Rosetta conversion of their source. The default implementation quotes
+    /// nothing, keeping the deterministic alignment.
+    async fn align(&self, _prose: &str, _aisp: &str) -> Result<Vec<SourceQuote>> {
+        Ok(Vec::new())
+    }
+
+    /// The same provider, but using `model`
+    ///
+    /// Used to move up the model ladder. The default implementation returns
+    /// `None` for providers with a fixed model, which ignore the ladder.
+    fn for_model(&self, _model: &str) -> Option<Box<dyn LlmProvider>> {
+        None
+    }
+
     /// Check if provider is available
     async fn is_available(&self) -> bool;
 }
 
+/// A prose span sent to the LLM during targeted fallback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanRequest {
+    /// The unmapped phrase to translate
+    pub text: String,
+    /// Surrounding prose, for disambiguation only
+    pub context: String,
+}
+
 /// LLM conversion result
 #[derive(Debug, Clone)]
 pub struct LlmResult {
@@ -44,26 +149,33 @@ pub struct LlmResult {
     pub model: String,
     /// Approximate tokens used (if available)
     pub tokens_used: Option<usize>,
+    /// Cost of the request in US dollars (if available)
+    pub cost_usd: Option<f64>,
 }
 
 impl LlmResult {
     /// Convert to ConversionResult
     pub fn to_conversion_result(self, tier: ConversionTier, input_len: usize) -> ConversionResult {
         ConversionResult {
-            output: self.output.clone(),
-            confidence: 0.95, // LLM output assumed high confidence
+            tokens: token_stats(input_len, self.output.len()),
+            output: self.output,
+            confidence: LLM_CONFIDENCE, // LLM output assumed high confidence
             unmapped: vec![],
             tier,
-            tokens: TokenStats {
-                input: input_len,
-                output: self.output.len(),
-                ratio: if input_len == 0 {
-                    0.0
-                } else {
-                    (self.output.len() as f64 / input_len as f64 * 100.0).round() / 100.0
-                },
-            },
             used_fallback: true,
         }
     }
 }
+
+/// Compute token statistics for an output produced from `input_len` bytes of prose
+pub(crate) fn token_stats(input_len: usize, output_len: usize) -> TokenStats {
+    TokenStats {
+        input: input_len,
+        output: output_len,
+        ratio: if input_len == 0 {
+            0.0
+        } else {
+            (output_len as f64 / input_len as f64 * 100.0).round() / 100.0
+        },
+    }
+}
@@ -0,0 +1,130 @@
+//! Conversion Quality Metrics
+//!
+//! Scores a candidate AISP output against the prose it was produced from,
+//! so deterministic and LLM conversions can be compared on equal terms.
+
+use crate::validator::{validate, ViolationKind};
+use rosetta_aisp::{AispConverter, ConversionTier, RosettaStone};
+use serde::{Deserialize, Serialize};
+
+/// Core AISP symbols counted by the density metric (𝔄 in the specification)
+const CORE_SYMBOLS: &[char] = &[
+    '⊤', '⊥', '∧', '∨', '¬', '→', '↔', '∀', '∃', 'λ', 'Π', 'Σ', '≜', '≡', '≢', '∈', '∉', '⊂', '⊃',
+    '∪', '∩', '∘', '⊕', '⊖', '⊗', '⟨', '⟩', '⟦', '⟧', '⊢', '⊨', '↦', '⇒', '∎',
+];
+
+/// Weights of validity, density and similarity in the total score
+const WEIGHTS: (f64, f64, f64) = (0.4, 0.3, 0.3);
+
+/// Quality score of a single AISP candidate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityScore {
+    /// Fraction of validation checks passed for the tier (0.0 - 1.0)
+    pub validity: f64,
+    /// Symbol density δ (0.0 - 1.0)
+    pub density: f64,
+    /// Round-trip semantic similarity to the original prose (0.0 - 1.0)
+    pub similarity: f64,
+    /// Weighted combination of the above (0.0 - 1.0)
+    pub total: f64,
+}
+
+impl QualityScore {
+    /// Score `output` as a conversion of `prose` at `tier`
+    pub fn compute(prose: &str, output: &str, tier: ConversionTier) -> Self {
+        let validity = validity(output, tier);
+        let density = density(output);
+        let similarity = RosettaStone::semantic_similarity(prose, &AispConverter::to_prose(output));
+        let (wv, wd, ws) = WEIGHTS;
+
+        Self {
+            validity,
+            density,
+            similarity,
+            total: wv * validity + wd * density + ws * similarity,
+        }
+    }
+}
+
+/// Symbol density: share of non-whitespace tokens that are core AISP symbols
+///
+/// Runs of alphanumeric characters count as a single token, every other
+/// character is a token of its own.
+pub fn density(text: &str) -> f64 {
+    let mut symbols = 0usize;
+    let mut tokens = 0usize;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if CORE_SYMBOLS.contains(&c) {
+            // Checked first: λ, Π and Σ are alphabetic but still symbols
+            symbols += 1;
+            tokens += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || c == '_' {
+            if !in_word {
+                tokens += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+    }
+
+    if tokens == 0 {
+        0.0
+    } else {
+        symbols as f64 / tokens as f64
+    }
+}
+
+/// Fraction of the validation checks expected at `tier` that `output` passes
+fn validity(output: &str, tier: ConversionTier) -> f64 {
+    let validation = validate(output);
+    let mut checks = vec![
+        !output.trim().is_empty(),
+        !validation.has(ViolationKind::Parse),
+    ];
+
+    if tier != ConversionTier::Minimal {
+        checks.push(!validation.has(ViolationKind::MissingHeader));
+        checks.push(!validation.has(ViolationKind::TooFewBlocks));
+    }
+    if tier == ConversionTier::Full {
+        checks.push(!validation.has(ViolationKind::MissingBlock));
+    }
+
+    checks.iter().filter(|passed| **passed).count() as f64 / checks.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_density() {
+        assert_eq!(density(""), 0.0);
+        assert_eq!(density("x≜5"), 1.0 / 3.0);
+        assert_eq!(density("∀x∈S:P(x)"), 2.0 / 9.0);
+        assert!(density("∀x∈S") > density("for all x in S"));
+    }
+
+    #[test]
+    fn test_validity_unbalanced() {
+        assert_eq!(validity("f≜λx.⟨x,x⟩", ConversionTier::Minimal), 1.0);
+        assert_eq!(validity("f≜λx.⟨x,x", ConversionTier::Minimal), 0.5);
+        assert!(validity("⟦Λ:Funcs", ConversionTier::Standard) < 0.5);
+    }
+
+    #[test]
+    fn test_validity_by_tier() {
+        let doc = "𝔸5.1.x@2026-01-01\n⟦Λ:Funcs⟧{x≜5}\n⟦Ε⟧⟨δ≜0.5⟩";
+        assert_eq!(validity("x≜5", ConversionTier::Minimal), 1.0);
+        assert_eq!(validity("x≜5", ConversionTier::Standard), 0.5);
+        assert_eq!(validity(doc, ConversionTier::Standard), 1.0);
+        assert_eq!(validity(doc, ConversionTier::Full), 0.8);
+    }
+}
@@ -0,0 +1,306 @@
+//! AST Rendering
+//!
+//! Turns a [`Document`] back into AISP text without the source it was parsed
+//! from, so that trees built or edited by other tools (for example through
+//! [`crate::Parsed::from_json`]) can be written out. Parentheses are added
+//! wherever the tree would otherwise parse differently, and the result is
+//! laid out by [`crate::format`].
+
+use crate::ast::{Comment, Document, Expr, ExprKind, Stmt, StmtKind};
+use crate::formatter::{format_aisp, FormatStyle};
+use crate::parser::{infix_bp, prefix_bp, APPLY_BP, BINDERS, BINDER_BP, ITEM_BP};
+
+/// Binding power of an operator nothing can absorb
+const CLOSED: u8 = u8::MAX;
+
+/// Render a document as AISP text
+///
+/// Spans are ignored. The output is in canonical format unless the tree
+/// contains statements that could not be parsed, which are written as
+/// their raw text.
+pub fn render(doc: &Document) -> String {
+    let mut lines = Vec::new();
+
+    if let Some(header) = &doc.header {
+        lines.push(format!(
+            "𝔸{}.{}@{}",
+            header.version, header.name, header.date
+        ));
+    }
+    if let Some(context) = &doc.context {
+        lines.push(format!("γ≔{}", context.id));
+    }
+    if let Some(references) = &doc.references {
+        lines.push(format!("ρ≔⟨{}⟩", references.items.join(",")));
+    }
+    lines.extend(doc.claims.iter().map(stmt));
+
+    for block in &doc.blocks {
+        lines.extend(block.leading_comments.iter().map(comment));
+        let label = match &block.name {
+            Some(name) => format!("⟦{}:{}⟧", block.category, name),
+            None => format!("⟦{}⟧", block.category),
+        };
+        lines.push(format!("{}{{", label));
+        lines.extend(block.body.iter().map(stmt));
+        lines.push("}".to_string());
+    }
+
+    if let Some(evidence) = &doc.evidence {
+        lines.extend(evidence.leading_comments.iter().map(comment));
+        let entries: Vec<String> = evidence
+            .entries
+            .iter()
+            .map(|entry| match &entry.name {
+                Some(name) => format!("{}≜{}", name, entry.value),
+                None => entry.value.clone(),
+            })
+            .collect();
+        lines.push(format!("⟦Ε⟧⟨{}⟩", entries.join(";")));
+    }
+    lines.extend(doc.comments.iter().map(comment));
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    format_aisp(&text, FormatStyle::Pretty).unwrap_or(text)
+}
+
+fn comment(comment: &Comment) -> String {
+    format!(";; {}", comment.text)
+}
+
+fn stmt(stmt: &Stmt) -> String {
+    let text = match &stmt.kind {
+        StmtKind::Def { target, op, value } => {
+            // A binder, lambda or sequence before `≜` would take it over
+            let owns_op = match &target.kind {
+                ExprKind::Lambda { .. } | ExprKind::Quant { .. } => true,
+                ExprKind::Unary { op, .. } => BINDERS.contains(&op.as_str()),
+                ExprKind::Binary { op, .. } => op == ",",
+                _ => false,
+            };
+            let target = render_expr(target);
+            let target = if owns_op { target.wrap() } else { target };
+            format!("{} {} {}", target.text, op, render_expr(value).text)
+        }
+        StmtKind::Rule {
+            premise,
+            consequent,
+        } => binary("⇒", render_expr(premise), render_expr(consequent)).text,
+        StmtKind::Expr(expr) => render_expr(expr).text,
+        StmtKind::Comment(c) => return comment(c),
+        StmtKind::Error(raw) => raw.clone(),
+    };
+    match &stmt.comment {
+        Some(c) => format!("{} {}", text, comment(c)),
+        None => text,
+    }
+}
+
+/// Rendered expression text and how it binds to its surroundings
+struct Rendered {
+    text: String,
+    /// Lowest binding power on the left edge: an enclosing parser at a
+    /// higher minimum stops before reaching the whole expression
+    left: u8,
+    /// Lowest binding power on the right edge: a following operator at
+    /// least this strong is absorbed into the expression
+    open: u8,
+}
+
+impl Rendered {
+    fn atom(text: String) -> Self {
+        Self {
+            text,
+            left: CLOSED,
+            open: CLOSED,
+        }
+    }
+
+    fn wrap(self) -> Self {
+        Self::atom(format!("({})", self.text))
+    }
+
+    /// Parenthesize unless the expression can be parsed at `min_bp`
+    fn at_least(self, min_bp: u8) -> Self {
+        if self.left >= min_bp {
+            self
+        } else {
+            self.wrap()
+        }
+    }
+
+    /// Parenthesize unless an operator of strength `bp` would follow it
+    fn closed_to(self, bp: u8) -> Self {
+        if self.open > bp {
+            self
+        } else {
+            self.wrap()
+        }
+    }
+}
+
+fn render_expr(expr: &Expr) -> Rendered {
+    match &expr.kind {
+        ExprKind::Ident(s) | ExprKind::Number(s) | ExprKind::Text(s) | ExprKind::Symbol(s) => {
+            Rendered::atom(s.clone())
+        }
+        ExprKind::Unary { op, operand } => {
+            let bp = if BINDERS.contains(&op.as_str()) {
+                BINDER_BP
+            } else {
+                prefix_bp(op).unwrap_or(APPLY_BP + 1)
+            };
+            let operand = render_expr(operand).at_least(bp);
+            Rendered {
+                text: format!("{} {}", op, operand.text),
+                left: CLOSED,
+                open: bp.min(operand.open),
+            }
+        }
+        ExprKind::Postfix { op, operand } => {
+            let bp = infix_bp(op).map_or(APPLY_BP, |(lbp, _)| lbp);
+            let operand = render_expr(operand).closed_to(bp);
+            Rendered {
+                text: format!("{}{}", operand.text, op),
+                left: bp.min(operand.left),
+                open: operand.open,
+            }
+        }
+        ExprKind::Binary { op, lhs, rhs } => binary(op, render_expr(lhs), render_expr(rhs)),
+        ExprKind::Lambda { params, body } => {
+            let body = render_expr(body).at_least(ITEM_BP);
+            Rendered {
+                text: format!("λ{} . {}", params.join(" "), body.text),
+                left: CLOSED,
+                open: ITEM_BP.min(body.open),
+            }
+        }
+        ExprKind::Quant {
+            quantifier,
+            binder,
+            body,
+        } => {
+            let colon = infix_bp(":").map_or(0, |(lbp, _)| lbp);
+            let binder = render_expr(binder).at_least(BINDER_BP).closed_to(colon);
+            let body = render_expr(body).at_least(ITEM_BP);
+            Rendered {
+                text: format!("{} {} : {}", quantifier, binder.text, body.text),
+                left: CLOSED,
+                open: ITEM_BP.min(body.open),
+            }
+        }
+        ExprKind::Apply { func, args } | ExprKind::Index { base: func, args } => {
+            let (open, close) = match &expr.kind {
+                ExprKind::Apply { .. } => ("(", ")"),
+                _ => ("[", "]"),
+            };
+            let func = render_expr(func).closed_to(APPLY_BP);
+            Rendered {
+                text: format!("{}{}{}{}", func.text, open, items(args), close),
+                left: APPLY_BP.min(func.left),
+                open: CLOSED,
+            }
+        }
+        ExprKind::Juxtapose { func, arg } => {
+            let func = render_expr(func).closed_to(APPLY_BP);
+            let arg = render_expr(arg).at_least(APPLY_BP + 1);
+            Rendered {
+                text: format!("{} {}", func.text, arg.text),
+                left: APPLY_BP.min(func.left),
+                open: (APPLY_BP + 1).min(arg.open),
+            }
+        }
+        ExprKind::Group {
+            delim,
+            items: group,
+        } => {
+            let (open, close) = delim.glyphs();
+            Rendered::atom(format!("{}{}{}", open, items(group), close))
+        }
+    }
+}
+
+fn binary(op: &str, lhs: Rendered, rhs: Rendered) -> Rendered {
+    // Unknown operators are treated as relations
+    let (lbp, rbp) = infix_bp(op).unwrap_or((14, 15));
+    let lhs = lhs.closed_to(lbp);
+    let rhs = rhs.at_least(rbp);
+    Rendered {
+        text: format!("{} {} {}", lhs.text, op, rhs.text),
+        left: lbp.min(lhs.left),
+        open: rbp.min(rhs.open),
+    }
+}
+
+fn items(items: &[Expr]) -> String {
+    items
+        .iter()
+        .map(|item| render_expr(item).at_least(ITEM_BP).text)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Span;
+    use crate::diff::diff;
+    use crate::parser::parse;
+
+    fn node(kind: ExprKind) -> Expr {
+        Expr {
+            kind,
+            span: Span::default(),
+        }
+    }
+
+    fn ident(name: &str) -> Expr {
+        node(ExprKind::Ident(name.to_string()))
+    }
+
+    fn binary_node(op: &str, lhs: Expr, rhs: Expr) -> Expr {
+        node(ExprKind::Binary {
+            op: op.to_string(),
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        })
+    }
+
+    #[test]
+    fn test_parentheses_follow_precedence() {
+        let or = binary_node("∨", ident("a"), ident("b"));
+        let check = |expr: Expr| normalized(&render_expr(&expr).text);
+        assert_eq!(check(binary_node("∧", or.clone(), ident("c"))), "(a∨b)∧c");
+        assert_eq!(check(binary_node("∨", ident("c"), or.clone())), "c∨(a∨b)");
+        assert_eq!(check(binary_node("∨", or.clone(), ident("c"))), "a∨b∨c");
+
+        // A lambda on the left would swallow the rest
+        let lambda = node(ExprKind::Lambda {
+            params: vec!["x".to_string()],
+            body: Box::new(ident("x")),
+        });
+        let eq = binary_node("=", ident("f"), lambda);
+        assert_eq!(check(binary_node("∧", eq, ident("c"))), "(f=λx.x)∧c");
+
+        let apply = node(ExprKind::Apply {
+            func: Box::new(or),
+            args: vec![ident("x")],
+        });
+        assert_eq!(check(apply), "(a∨b)(x)");
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let source = &crate::claude::AISP_PROMPT;
+        let parsed = parse(source);
+        let rendered = render(&parsed.document);
+        assert!(parse(&rendered).is_ok());
+        assert!(diff(source, &rendered).is_empty());
+        assert_eq!(render(&parse(&rendered).document), rendered);
+    }
+
+    fn normalized(text: &str) -> String {
+        crate::formatter::normalize(text, Span::new(0, text.len()), true)
+    }
+}
@@ -0,0 +1,371 @@
+//! Structural Repair
+//!
+//! Deterministic recoveries in the spirit of the `⟦Χ:Errors⟧` algebra
+//! (`ε_H≜⟨¬(↓₁≡𝔸),λd.𝔸⊕d⟩`): each repair checks one condition and, if it
+//! holds, rewrites the document. They are applied in order, like `ρ*`, and
+//! every repair applied is reported.
+
+use crate::ast::Span;
+use crate::evidence::rewrite_evidence;
+use crate::lexer::{tokenize, TokenKind};
+use crate::parser::{is_block_start, parse};
+use crate::validator::required_blocks;
+use rosetta_aisp::ConversionTier;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Block order per `Doc≜𝔸≫CTX?≫REF?≫⟦Ω⟧≫⟦Σ⟧≫⟦Γ⟧≫⟦Λ⟧≫⟦Χ⟧?≫⟦Ε⟧`, with the
+/// name used for stubs
+pub(crate) const BLOCK_ORDER: &[(&str, &str)] = &[
+    ("Ω", "Meta"),
+    ("Σ", "Types"),
+    ("Γ", "Rules"),
+    ("Λ", "Funcs"),
+    ("Χ", "Errors"),
+    ("Ε", ""),
+];
+
+const BRACKETS: &[(&str, &str)] = &[("⟦", "⟧"), ("{", "}"), ("⟨", "⟩"), ("(", ")"), ("[", "]")];
+
+/// Kinds of repairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RepairKind {
+    /// Closed an unclosed bracket or removed a stray closing one
+    Brackets,
+    /// Prepended a `𝔸` header (`ε_H`)
+    Header,
+    /// Inserted a `γ≔` context
+    Context,
+    /// Inserted an empty stub for a required block
+    Block,
+    /// Appended an evidence block with recomputed values
+    Evidence,
+}
+
+/// A repair that was applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Repair {
+    /// What was repaired
+    pub kind: RepairKind,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// The repaired document and the repairs applied to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Repaired {
+    /// The document after all repairs
+    pub output: String,
+    /// Repairs in the order they were applied
+    pub repairs: Vec<Repair>,
+}
+
+/// Repair common structural errors in a document converted at `tier`
+///
+/// Brackets are balanced at every tier. Standard and Full documents also
+/// get a missing header, context, required blocks (as empty stubs) and
+/// evidence block. A document needing no repair is returned unchanged.
+pub fn repair(source: &str, tier: ConversionTier) -> Repaired {
+    let mut repairs = Vec::new();
+    let mut output = balance_brackets(source, &mut repairs);
+
+    if tier != ConversionTier::Minimal {
+        output = add_header_and_context(&output, &mut repairs);
+        for category in required_blocks(tier) {
+            output = add_block(&output, category, &mut repairs);
+        }
+    }
+
+    Repaired { output, repairs }
+}
+
+/// One edit of the bracket repair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BracketEdit {
+    /// The unclosed opening bracket or the unmatched closing one
+    pub cause: Span,
+    /// Region replaced: empty where a closing bracket is inserted
+    pub at: Span,
+    /// Replacement text: the closing bracket, or empty to remove
+    pub text: &'static str,
+    pub repair: Repair,
+}
+
+/// Close unclosed brackets and drop closing brackets that match nothing
+fn balance_brackets(source: &str, repairs: &mut Vec<Repair>) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut pos = 0;
+    for edit in bracket_edits(source) {
+        output.push_str(&source[pos..edit.at.start]);
+        output.push_str(edit.text);
+        pos = edit.at.end;
+        repairs.push(edit.repair);
+    }
+    output.push_str(&source[pos..]);
+    output
+}
+
+/// The edits that balance the brackets of `source`, in source order
+///
+/// Brackets still open when the next block starts are closed at the end of
+/// the previous statement, so one unclosed block doesn't swallow the rest.
+pub(crate) fn bracket_edits(source: &str) -> Vec<BracketEdit> {
+    let tokens = tokenize(source);
+    let mut edits: Vec<BracketEdit> = Vec::new();
+    let mut stack: Vec<(&'static str, Span)> = Vec::new();
+    let mut content_end = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if matches!(token.kind, TokenKind::Newline | TokenKind::Comment) {
+            continue;
+        }
+
+        if is_block_start(&tokens, i) {
+            close_all(&mut stack, content_end, source, &mut edits);
+        }
+        if token.kind == TokenKind::Symbol {
+            if let Some((_, close)) = BRACKETS.iter().find(|(open, _)| token.is(open)) {
+                stack.push((close, token.span));
+            } else if BRACKETS.iter().any(|(_, close)| token.is(close)) {
+                match stack.iter().rposition(|(close, _)| token.is(close)) {
+                    Some(depth) => {
+                        // Close whatever was left open inside this pair
+                        let mut inner = stack.split_off(depth + 1);
+                        stack.pop();
+                        close_all(&mut inner, token.span.start, source, &mut edits);
+                    }
+                    None => edits.push(BracketEdit {
+                        cause: token.span,
+                        at: token.span,
+                        text: "",
+                        repair: Repair {
+                            kind: RepairKind::Brackets,
+                            message: format!(
+                                "removed unmatched `{}` at line {}",
+                                token.text,
+                                token.span.line_col(source).0
+                            ),
+                        },
+                    }),
+                }
+            }
+        }
+        content_end = token.span.end;
+    }
+    close_all(&mut stack, content_end, source, &mut edits);
+    edits
+}
+
+/// Insert the closing brackets of everything on `stack` at byte `at`
+fn close_all(
+    stack: &mut Vec<(&'static str, Span)>,
+    at: usize,
+    source: &str,
+    edits: &mut Vec<BracketEdit>,
+) {
+    while let Some((close, open)) = stack.pop() {
+        edits.push(BracketEdit {
+            cause: open,
+            at: Span::new(at, at),
+            text: close,
+            repair: Repair {
+                kind: RepairKind::Brackets,
+                message: format!(
+                    "closed `{}` opened at line {}",
+                    open.text(source),
+                    open.line_col(source).0
+                ),
+            },
+        });
+    }
+}
+
+/// Prepend a `𝔸` header (`ε_H`) and insert a `γ≔` context if missing
+fn add_header_and_context(source: &str, repairs: &mut Vec<Repair>) -> String {
+    let doc = parse(source).document;
+    let name = doc
+        .header
+        .as_ref()
+        .map(|h| h.name.clone())
+        .or_else(|| doc.context.as_ref().map(|c| c.id.clone()))
+        .unwrap_or_else(|| "document".to_string());
+
+    let mut output = source.to_string();
+    if doc.context.is_none() {
+        let at = doc.header.as_ref().map_or(0, |h| h.span.end);
+        let context = format!("γ≔{}", name);
+        if at == 0 {
+            output.insert_str(0, &format!("{}\n", context));
+        } else {
+            output.insert_str(at, &format!("\n{}", context));
+        }
+        repairs.push(Repair {
+            kind: RepairKind::Context,
+            message: format!("inserted context `{}`", context),
+        });
+    }
+
+    if doc.header.is_none() {
+        // Name taken from the context keeps the header a single Name segment
+        let name = name.split('.').next().unwrap_or(&name).to_string();
+        let header = format!("𝔸5.1.{}@{}", name, today());
+        output.insert_str(0, &format!("{}\n", header));
+        repairs.push(Repair {
+            kind: RepairKind::Header,
+            message: format!("prepended header `{}`", header),
+        });
+    }
+
+    output
+}
+
+/// Insert a stub for a required block, or a recomputed evidence block
+pub(crate) fn add_block(source: &str, category: &str, repairs: &mut Vec<Repair>) -> String {
+    let doc = parse(source).document;
+    if doc.has_block(category) {
+        return source.to_string();
+    }
+
+    if category == "Ε" {
+        repairs.push(Repair {
+            kind: RepairKind::Evidence,
+            message: "appended evidence block with recomputed δ, φ and τ".to_string(),
+        });
+        return rewrite_evidence(source);
+    }
+
+    let rank = |cat: &str| BLOCK_ORDER.iter().position(|(c, _)| *c == cat);
+    let name = BLOCK_ORDER
+        .iter()
+        .find(|(c, _)| *c == category)
+        .map_or("", |(_, name)| name);
+    let stub = if name.is_empty() {
+        format!("⟦{}⟧{{}}", category)
+    } else {
+        format!("⟦{}:{}⟧{{}}", category, name)
+    };
+
+    // Before the first block that comes later in document order
+    let later_block = doc
+        .blocks
+        .iter()
+        .filter(|b| rank(&b.category) > rank(category))
+        .map(|b| {
+            b.leading_comments
+                .first()
+                .map_or(b.span.start, |c| c.span.start)
+        })
+        .chain(doc.evidence.as_ref().map(|e| {
+            e.leading_comments
+                .first()
+                .map_or(e.span.start, |c| c.span.start)
+        }))
+        .min();
+
+    repairs.push(Repair {
+        kind: RepairKind::Block,
+        message: format!("inserted empty `{}` stub", stub),
+    });
+    match later_block {
+        Some(at) => format!("{}{}\n{}", &source[..at], stub, &source[at..]),
+        None => format!("{}\n{}", source.trim_end(), stub),
+    }
+}
+
+/// Today's date as `YYYY-MM-DD` (UTC)
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::validate;
+
+    fn kinds(repaired: &Repaired) -> Vec<RepairKind> {
+        repaired.repairs.iter().map(|r| r.kind).collect()
+    }
+
+    #[test]
+    fn test_balance_brackets() {
+        let repaired = repair("f≜λx.⟨x,(x⟩)", ConversionTier::Minimal);
+        assert_eq!(repaired.output, "f≜λx.⟨x,(x)⟩");
+        assert_eq!(
+            kinds(&repaired),
+            vec![RepairKind::Brackets, RepairKind::Brackets]
+        );
+
+        let source = "⟦Σ:Types⟧{\n  T≜⟨a,b ;; open\n⟦Λ:Funcs⟧{ f≜λx.x }";
+        let repaired = repair(source, ConversionTier::Minimal);
+        assert_eq!(
+            repaired.output,
+            "⟦Σ:Types⟧{\n  T≜⟨a,b⟩} ;; open\n⟦Λ:Funcs⟧{ f≜λx.x }"
+        );
+        assert!(parse(&repaired.output).is_ok());
+    }
+
+    #[test]
+    fn test_minimal_tier_only_balances() {
+        let repaired = repair("x≜5", ConversionTier::Minimal);
+        assert_eq!(repaired.output, "x≜5");
+        assert!(repaired.repairs.is_empty());
+    }
+
+    #[test]
+    fn test_repair_standard_document() {
+        let repaired = repair("⟦Λ:Funcs⟧{ f≜λx.x }", ConversionTier::Standard);
+        assert_eq!(
+            kinds(&repaired),
+            vec![
+                RepairKind::Context,
+                RepairKind::Header,
+                RepairKind::Evidence
+            ]
+        );
+        assert!(repaired.output.starts_with("𝔸5.1.document@"));
+        assert!(validate(&repaired.output).valid, "{}", repaired.output);
+    }
+
+    #[test]
+    fn test_repair_full_document_inserts_stubs_in_order() {
+        let source = "𝔸5.1.auth@2026-01-09\nγ≔auth\n⟦Σ:Types⟧{T≜ℕ}\n⟦Λ:Funcs⟧{f≜λx.x}";
+        let repaired = repair(source, ConversionTier::Full);
+        let doc = parse(&repaired.output).document;
+
+        let categories: Vec<&str> = doc.blocks.iter().map(|b| b.category.as_str()).collect();
+        assert_eq!(categories, vec!["Ω", "Σ", "Γ", "Λ"]);
+        assert!(doc.evidence.is_some());
+        assert_eq!(validate(&repaired.output).completeness, 100);
+
+        // Repairing again changes nothing
+        let again = repair(&repaired.output, ConversionTier::Full);
+        assert_eq!(again.output, repaired.output);
+        assert!(again.repairs.is_empty());
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(20_462), (2026, 1, 9));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+}
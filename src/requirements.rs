@@ -0,0 +1,241 @@
+//! Requirement Traceability
+//!
+//! Specs label requirements as `REQ-123:` or `SEC-AUTH-7:`. The labels are
+//! blanked out before conversion, so they don't leak into the AISP as
+//! unmapped words, and afterwards each statement is tagged with the
+//! requirements it came from as a trailing `;; REQ-123` comment. Statements
+//! are traced to requirements through the [`crate::align`]ment of the output
+//! with the prose.
+
+use crate::alignment::{align, Alignment};
+use crate::ast::Span;
+use crate::parser::parse;
+use serde::{Deserialize, Serialize};
+
+/// A labeled requirement in prose
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Requirement {
+    /// The identifier, e.g. `REQ-123`
+    pub id: String,
+    /// Byte range of the label, including the colon
+    pub label: Span,
+    /// Byte range of the requirement text, up to the next label or line break
+    pub text: Span,
+}
+
+/// The AISP statements produced from one requirement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementTrace {
+    /// The identifier, e.g. `REQ-123`
+    pub id: String,
+    /// Byte range of the requirement text in the prose
+    pub prose: Span,
+    /// Statements traced to the requirement, on one line each
+    pub statements: Vec<String>,
+}
+
+/// Which requirements produced AISP
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementCoverage {
+    /// Every requirement, in prose order
+    pub requirements: Vec<RequirementTrace>,
+}
+
+impl RequirementCoverage {
+    /// Requirements that produced no statement
+    pub fn uncovered(&self) -> impl Iterator<Item = &RequirementTrace> {
+        self.requirements.iter().filter(|r| r.statements.is_empty())
+    }
+
+    /// Whether every requirement produced at least one statement
+    pub fn is_complete(&self) -> bool {
+        self.uncovered().next().is_none()
+    }
+}
+
+/// Find the labeled requirements in `prose`
+///
+/// A label is an uppercase identifier with a numbered last part
+/// (`REQ-123`, `SEC-AUTH-7`, `FR-1.2`) followed by a colon, at the start of
+/// the prose or after whitespace or an opening bracket.
+pub fn find_requirements(prose: &str) -> Vec<Requirement> {
+    let mut labels: Vec<(String, Span)> = Vec::new();
+    for (start, c) in prose.char_indices() {
+        let boundary = prose[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|p| p.is_whitespace() || p == '(' || p == '[');
+        if !boundary || !c.is_ascii_uppercase() {
+            continue;
+        }
+        let len = prose[start..]
+            .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-' || c == '.'))
+            .unwrap_or(prose.len() - start);
+        let id = prose[start..start + len].trim_end_matches('.');
+        if prose[start + id.len()..].starts_with(':') && is_requirement_id(id) {
+            labels.push((id.to_string(), Span::new(start, start + id.len() + 1)));
+        }
+    }
+
+    let mut requirements = Vec::new();
+    for (i, (id, label)) in labels.iter().enumerate() {
+        let limit = labels
+            .get(i + 1)
+            .map_or(prose.len(), |(_, next)| next.start);
+        let rest = &prose[label.end..limit];
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let start = label.end + (line.len() - line.trim_start().len());
+        let end = label.end + line.trim_end().len();
+        requirements.push(Requirement {
+            id: id.clone(),
+            label: *label,
+            text: Span::new(start, end.max(start)),
+        });
+    }
+    requirements
+}
+
+fn is_requirement_id(id: &str) -> bool {
+    let parts: Vec<&str> = id.split('-').collect();
+    parts.len() >= 2
+        && parts.iter().all(|p| !p.is_empty())
+        && parts[0].starts_with(|c: char| c.is_ascii_uppercase())
+        && parts[parts.len() - 1].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// `prose` with requirement labels replaced by spaces, keeping byte offsets
+pub(crate) fn blank_labels(prose: &str, requirements: &[Requirement]) -> String {
+    let mut blanked = prose.to_string();
+    for requirement in requirements {
+        let label = requirement.label;
+        // Labels are ASCII, so the byte length is the character count
+        blanked.replace_range(label.start..label.end, &" ".repeat(label.end - label.start));
+    }
+    blanked
+}
+
+/// Trace the requirements of `prose` to the statements of `aisp`
+///
+/// Uses the deterministic alignment only; conversions with
+/// `trace_requirements` set also use the LLM's quotes for LLM outputs.
+pub fn trace_requirements(prose: &str, aisp: &str) -> RequirementCoverage {
+    let requirements = find_requirements(prose);
+    let alignment = align(&blank_labels(prose, &requirements), aisp);
+    coverage(&requirements, &alignment, aisp)
+}
+
+pub(crate) fn coverage(
+    requirements: &[Requirement],
+    alignment: &Alignment,
+    aisp: &str,
+) -> RequirementCoverage {
+    let requirements = requirements
+        .iter()
+        .map(|requirement| RequirementTrace {
+            id: requirement.id.clone(),
+            prose: requirement.text,
+            statements: alignment
+                .statements
+                .iter()
+                .filter(|s| s.prose.iter().any(|p| overlaps(*p, requirement.text)))
+                .map(|s| {
+                    s.aisp
+                        .text(aisp)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect(),
+        })
+        .collect();
+    RequirementCoverage { requirements }
+}
+
+/// `aisp` with each statement tagged with the ids of its requirements
+///
+/// The tag is a trailing `;;` comment, so anything after the statement on
+/// the same line moves to the next one. The document is returned unchanged
+/// if tagging would break its parse.
+pub(crate) fn annotate(aisp: &str, requirements: &[Requirement], alignment: &Alignment) -> String {
+    let mut tags: Vec<(usize, String)> = alignment
+        .statements
+        .iter()
+        .filter_map(|statement| {
+            let mut ids: Vec<&str> = requirements
+                .iter()
+                .filter(|r| statement.prose.iter().any(|p| overlaps(*p, r.text)))
+                .map(|r| r.id.as_str())
+                .collect();
+            ids.dedup();
+            (!ids.is_empty()).then(|| (statement.aisp.end, ids.join(", ")))
+        })
+        .collect();
+    // From the end, so earlier offsets stay valid
+    tags.sort_by_key(|(end, _)| std::cmp::Reverse(*end));
+
+    let mut output = aisp.to_string();
+    for (end, ids) in tags {
+        let rest = &output[end..];
+        let line = rest[..rest.find('\n').unwrap_or(rest.len())].trim_start();
+        if line.is_empty() || line.starts_with(";;") {
+            output.insert_str(end, &format!(" ;; {}", ids));
+        } else {
+            // Drop the separator the line break replaces
+            let mut skip = rest.len() - rest.trim_start().len();
+            if line.starts_with(';') {
+                skip += 1;
+            }
+            output.replace_range(end..end + skip, &format!(" ;; {}\n", ids));
+        }
+    }
+
+    if parse(&output).errors.len() > parse(aisp).errors.len() {
+        return aisp.to_string();
+    }
+    output
+}
+
+fn overlaps(a: Span, b: Span) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_requirements() {
+        let prose = "REQ-1: Define x as 5.\n(SEC-AUTH-7: Log in. FR-1.2: Log out.) \
+                     See RFC-2119 and NOTE: x.";
+        let found = find_requirements(prose);
+        let ids: Vec<&str> = found.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["REQ-1", "SEC-AUTH-7", "FR-1.2"]);
+        assert_eq!(found[0].text.text(prose), "Define x as 5.");
+        assert_eq!(found[1].text.text(prose), "Log in.");
+        assert_eq!(
+            found[2].text.text(prose),
+            "Log out.) See RFC-2119 and NOTE: x."
+        );
+
+        let blanked = blank_labels(prose, &found);
+        assert!(blanked.starts_with("       Define x as 5."));
+        assert_eq!(blanked.len(), prose.len());
+    }
+
+    #[test]
+    fn test_trace_and_annotate() {
+        let prose = "REQ-1: Define x as 5.\nREQ-2: The weather is nice.\nREQ-3: Define y as x.";
+        let aisp = "⟦Λ:Funcs⟧{x≜5;y≜x}";
+        let coverage = trace_requirements(prose, aisp);
+        assert_eq!(coverage.requirements[0].statements, vec!["x≜5"]);
+        let uncovered: Vec<&str> = coverage.uncovered().map(|r| r.id.as_str()).collect();
+        assert_eq!(uncovered, vec!["REQ-2"]);
+        assert!(!coverage.is_complete());
+
+        let found = find_requirements(prose);
+        let alignment = align(&blank_labels(prose, &found), aisp);
+        let annotated = annotate(aisp, &found, &alignment);
+        assert_eq!(annotated, "⟦Λ:Funcs⟧{x≜5 ;; REQ-1\ny≜x ;; REQ-3\n}");
+        assert!(parse(&annotated).is_ok());
+    }
+}
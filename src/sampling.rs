@@ -0,0 +1,119 @@
+//! Multi-Sample Ranking
+//!
+//! Ranks several LLM samples for the same prose by quality score and
+//! measures how much they agree with each other (self-consistency).
+
+use crate::quality::QualityScore;
+use rosetta_aisp::ConversionResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Outcome of ranking several LLM samples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleReport {
+    /// Score of each sample that the provider returned, in request order
+    pub scores: Vec<QualityScore>,
+    /// Index of the returned sample in `scores`
+    pub chosen: usize,
+    /// Mean pairwise token overlap between samples (0.0 - 1.0)
+    pub self_consistency: f64,
+}
+
+/// Return the highest-scoring sample along with the ranking report
+///
+/// Returns `None` if `samples` is empty. Ties go to the earliest sample.
+pub(crate) fn rank(
+    prose: &str,
+    mut samples: Vec<ConversionResult>,
+) -> Option<(ConversionResult, SampleReport)> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let scores: Vec<QualityScore> = samples
+        .iter()
+        .map(|s| QualityScore::compute(prose, &s.output, s.tier))
+        .collect();
+
+    let mut chosen = 0;
+    for (i, score) in scores.iter().enumerate().skip(1) {
+        if score.total > scores[chosen].total {
+            chosen = i;
+        }
+    }
+
+    let outputs: Vec<&str> = samples.iter().map(|s| s.output.as_str()).collect();
+    let report = SampleReport {
+        self_consistency: self_consistency(&outputs),
+        scores,
+        chosen,
+    };
+
+    Some((samples.swap_remove(chosen), report))
+}
+
+/// Mean pairwise Jaccard similarity of the samples' token sets
+///
+/// A single sample is trivially consistent with itself.
+fn self_consistency(outputs: &[&str]) -> f64 {
+    let token_sets: Vec<HashSet<String>> = outputs.iter().map(|o| tokens(o)).collect();
+
+    let mut total = 0.0;
+    let mut pairs = 0usize;
+    for (i, a) in token_sets.iter().enumerate() {
+        for b in &token_sets[i + 1..] {
+            let union = a.union(b).count();
+            total += if union == 0 {
+                1.0
+            } else {
+                a.intersection(b).count() as f64 / union as f64
+            };
+            pairs += 1;
+        }
+    }
+
+    if pairs == 0 {
+        1.0
+    } else {
+        total / pairs as f64
+    }
+}
+
+/// Split AISP into words (alphanumeric runs) and single-character symbols
+fn tokens(text: &str) -> HashSet<String> {
+    let mut tokens = HashSet::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.insert(std::mem::take(&mut word));
+        }
+        if !c.is_whitespace() {
+            tokens.insert(c.to_string());
+        }
+    }
+    if !word.is_empty() {
+        tokens.insert(word);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_consistency() {
+        assert_eq!(self_consistency(&["x≜5"]), 1.0);
+        assert_eq!(self_consistency(&["x≜5", "x ≜ 5"]), 1.0);
+        assert_eq!(self_consistency(&["x≜5", "∀y∈S"]), 0.0);
+
+        let partial = self_consistency(&["x≜5", "x≜6"]);
+        assert!(partial > 0.0 && partial < 1.0);
+    }
+}
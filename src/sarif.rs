@@ -0,0 +1,322 @@
+//! SARIF Output
+//!
+//! Converts lint results into a SARIF 2.1.0 log for code-scanning tools.
+//! Findings the repair pass or the evidence rewrite can fix carry a `fixes`
+//! entry with the smallest replacement that fixes them, so fixes of
+//! different findings don't conflict. Missing headers are not fixed, since
+//! the repair dates them.
+
+use crate::ast::Span;
+use crate::evidence::rewrite_evidence;
+use crate::lint::{lint, Diagnostic, LintConfig, LintRule, Severity};
+use crate::parser::parse;
+use crate::repair::{add_block, bracket_edits, BracketEdit};
+use crate::validator::required_blocks;
+use serde_json::{json, Value};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Lint `files` (path and source pairs) and report the results as a SARIF log
+///
+/// Rules are listed with the severity configured in `config`; allowed rules
+/// are left out.
+pub fn lint_to_sarif(files: &[(&str, &str)], config: &LintConfig) -> Value {
+    let rules: Vec<LintRule> = LintRule::ALL
+        .iter()
+        .copied()
+        .filter(|rule| config.severity(*rule) != Severity::Allow)
+        .collect();
+
+    let mut results = Vec::new();
+    for (path, source) in files {
+        let mut fixes = Fixes::new(source, config);
+        for diagnostic in lint(source, config) {
+            let fix = fixes.take(&diagnostic);
+            results.push(result(path, source, &diagnostic, &rules, fix));
+        }
+    }
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rosetta",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.iter().map(|rule| json!({
+                        "id": rule.name(),
+                        "shortDescription": { "text": rule.description() },
+                        "defaultConfiguration": { "level": level(config.severity(*rule)) },
+                    })).collect::<Vec<_>>(),
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    })
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Allow => "none",
+    }
+}
+
+fn result(
+    path: &str,
+    source: &str,
+    diagnostic: &Diagnostic,
+    rules: &[LintRule],
+    fix: Option<Fix>,
+) -> Value {
+    let mut physical = json!({ "artifactLocation": { "uri": path } });
+    if let Some(span) = diagnostic.span {
+        physical["region"] = region(source, span);
+    }
+
+    let mut message = diagnostic.message.clone();
+    if let Some(help) = &diagnostic.help {
+        message = format!("{} ({})", message, help);
+    }
+
+    let mut result = json!({
+        "ruleId": diagnostic.rule.name(),
+        "ruleIndex": rules.iter().position(|rule| *rule == diagnostic.rule),
+        "level": level(diagnostic.severity),
+        "message": { "text": message },
+        "locations": [{ "physicalLocation": physical }],
+    });
+
+    if let Some(fix) = fix {
+        let replacements: Vec<Value> = fix
+            .replacements
+            .iter()
+            .map(|(span, inserted)| {
+                json!({
+                    "deletedRegion": region(source, *span),
+                    "insertedContent": { "text": inserted },
+                })
+            })
+            .collect();
+        result["fixes"] = json!([{
+            "description": { "text": fix.description },
+            "artifactChanges": [{
+                "artifactLocation": { "uri": path },
+                "replacements": replacements,
+            }],
+        }]);
+    }
+
+    result
+}
+
+/// A fix for one finding
+struct Fix {
+    description: String,
+    /// Regions of the source and their replacements
+    replacements: Vec<(Span, String)>,
+}
+
+/// The fixes of one file, each handed to a single finding
+struct Fixes<'a> {
+    source: &'a str,
+    /// Bracket edits not yet attached to a parse error
+    brackets: Vec<Option<BracketEdit>>,
+    /// Missing blocks, in the order they are reported
+    missing: std::vec::IntoIter<&'static str>,
+    /// Whether the evidence rewrite is still to be attached
+    evidence: bool,
+}
+
+impl<'a> Fixes<'a> {
+    fn new(source: &'a str, config: &LintConfig) -> Self {
+        let doc = parse(source).document;
+        let missing: Vec<&'static str> = required_blocks(config.tier)
+            .iter()
+            .copied()
+            .filter(|category| !doc.has_block(category))
+            .collect();
+        Self {
+            source,
+            brackets: bracket_edits(source).into_iter().map(Some).collect(),
+            missing: missing.into_iter(),
+            evidence: true,
+        }
+    }
+
+    /// The fix for `diagnostic`, unless it has none or was already handed out
+    fn take(&mut self, diagnostic: &Diagnostic) -> Option<Fix> {
+        match diagnostic.rule {
+            // The bracket edits whose bracket or insertion point the error covers
+            LintRule::ParseError => {
+                let span = diagnostic.span?;
+                let covers = |at: usize| span.start <= at && at < span.end.max(span.start + 1);
+                let edits: Vec<BracketEdit> = self
+                    .brackets
+                    .iter_mut()
+                    .filter(|slot| {
+                        slot.as_ref()
+                            .is_some_and(|edit| covers(edit.cause.start) || covers(edit.at.start))
+                    })
+                    .filter_map(Option::take)
+                    .collect();
+                if edits.is_empty() {
+                    return None;
+                }
+                let messages: Vec<&str> = edits.iter().map(|e| e.repair.message.as_str()).collect();
+                Some(Fix {
+                    description: format!("Repair: {}", messages.join("; ")),
+                    replacements: edits
+                        .iter()
+                        .map(|edit| (edit.at, edit.text.to_string()))
+                        .collect(),
+                })
+            }
+            // Missing blocks are reported in `required_blocks` order
+            LintRule::MissingBlock => {
+                let category = self.missing.next()?;
+                let mut repairs = Vec::new();
+                let fixed = add_block(self.source, category, &mut repairs);
+                let messages: Vec<&str> = repairs.iter().map(|r| r.message.as_str()).collect();
+                Some(Fix {
+                    description: format!("Repair: {}", messages.join("; ")),
+                    replacements: replacement(self.source, &fixed).into_iter().collect(),
+                })
+            }
+            // One rewrite fixes every mismatch, so only the first carries it
+            LintRule::EvidenceMismatch if self.evidence => {
+                self.evidence = false;
+                let fixed = rewrite_evidence(self.source);
+                Some(Fix {
+                    description: "Recompute the evidence block".to_string(),
+                    replacements: replacement(self.source, &fixed).into_iter().collect(),
+                })
+            }
+            _ => None,
+        }
+        .filter(|fix| !fix.replacements.is_empty())
+    }
+}
+
+/// The single region of `before` that differs from `after`, and its replacement
+fn replacement(before: &str, after: &str) -> Option<(Span, String)> {
+    if before == after {
+        return None;
+    }
+    let prefix = before
+        .char_indices()
+        .zip(after.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(before.len().min(after.len()), |((i, _), _)| i);
+    let suffix = before[prefix..]
+        .chars()
+        .rev()
+        .zip(after[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    let span = Span::new(prefix, before.len() - suffix);
+    Some((span, after[prefix..after.len() - suffix].to_string()))
+}
+
+/// SARIF region with 1-based lines and code point columns
+fn region(source: &str, span: Span) -> Value {
+    let (start_line, start_column) = span.line_col(source);
+    let (end_line, end_column) = Span::new(span.end, span.end).line_col(source);
+    json!({
+        "startLine": start_line,
+        "startColumn": start_column,
+        "endLine": end_line,
+        "endColumn": end_column,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replacement() {
+        assert_eq!(replacement("abc", "abc"), None);
+        assert_eq!(
+            replacement("⟦Ε⟧⟨δ≜0.9⟩", "⟦Ε⟧⟨δ≜0.25⟩"),
+            Some((Span::new(18, 19), "25".to_string()))
+        );
+        assert_eq!(
+            replacement("ab", "abc"),
+            Some((Span::new(2, 2), "c".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sarif_log() {
+        let source = "𝔸5.1.x@2026-01-09\nγ≔x\n⟦Λ:Funcs⟧{f≜λx.x⋈y}\n⟦Ε⟧⟨δ≜0.99⟩";
+        let log = lint_to_sarif(&[("spec.aisp", source)], &LintConfig::default());
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert!(rules.iter().all(|r| r["id"] != "unused-definition"));
+
+        let results = run["results"].as_array().unwrap();
+        let glyph = results
+            .iter()
+            .find(|r| r["ruleId"] == "unknown-glyph")
+            .unwrap();
+        assert_eq!(glyph["level"], "error");
+        let region = &glyph["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 3);
+        assert_eq!(region["startColumn"], 17);
+        assert!(glyph.get("fixes").is_none());
+
+        let evidence = results
+            .iter()
+            .find(|r| r["ruleId"] == "evidence-mismatch")
+            .unwrap();
+        let replacement = &evidence["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert!(replacement["insertedContent"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("τ≜"));
+    }
+
+    #[test]
+    fn test_sarif_fixes_are_minimal_and_unique() {
+        let source = "𝔸5.1.x@2026-01-09\nγ≔x\n⟦Σ:Types⟧{T≜f(a}\n⟦Λ:Funcs⟧{g≜h(b}\n⟦Ε⟧⟨δ≜0.99⟩";
+        let log = lint_to_sarif(&[("spec.aisp", source)], &LintConfig::default());
+        let results = log["runs"][0]["results"].as_array().unwrap();
+
+        let parse_fixes: Vec<&Value> = results
+            .iter()
+            .filter(|r| r["ruleId"] == "parse-error")
+            .filter_map(|r| r.get("fixes"))
+            .collect();
+        assert_eq!(parse_fixes.len(), 2);
+        for fix in parse_fixes {
+            let replacements = fix[0]["artifactChanges"][0]["replacements"]
+                .as_array()
+                .unwrap();
+            assert_eq!(replacements.len(), 1);
+            assert_eq!(replacements[0]["insertedContent"]["text"], ")");
+        }
+
+        let evidence: Vec<&Value> = results
+            .iter()
+            .filter(|r| r["ruleId"] == "evidence-mismatch")
+            .collect();
+        assert_eq!(
+            evidence.iter().filter(|r| r.get("fixes").is_some()).count(),
+            1
+        );
+
+        // Headers carry today's date, so they are never inserted
+        let headerless = [("spec.aisp", "⟦Λ:Funcs⟧{f≜λx.x}")];
+        let headerless = lint_to_sarif(&headerless, &LintConfig::default());
+        assert!(!headerless.to_string().contains('𝔸'));
+    }
+}
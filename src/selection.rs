@@ -0,0 +1,56 @@
+//! Best-of Selection
+//!
+//! Scores the deterministic and LLM conversions with the same quality
+//! metrics and keeps the better one, instead of always trusting the LLM.
+
+use crate::quality::QualityScore;
+use rosetta_aisp::ConversionResult;
+use serde::{Deserialize, Serialize};
+
+/// Which conversion was returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Candidate {
+    /// The deterministic Rosetta conversion
+    Deterministic,
+    /// The LLM fallback conversion
+    Llm,
+}
+
+/// Outcome of comparing the deterministic and LLM conversions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Selection {
+    /// The candidate that was returned
+    pub chosen: Candidate,
+    /// Score of the deterministic conversion
+    pub deterministic: QualityScore,
+    /// Score of the LLM conversion
+    pub llm: QualityScore,
+}
+
+/// Pick the better of two conversions of `prose`
+///
+/// Ties go to the deterministic conversion, which is reproducible.
+pub(crate) fn select_best(
+    prose: &str,
+    deterministic: ConversionResult,
+    llm: ConversionResult,
+) -> (ConversionResult, Selection) {
+    let deterministic_score =
+        QualityScore::compute(prose, &deterministic.output, deterministic.tier);
+    let llm_score = QualityScore::compute(prose, &llm.output, llm.tier);
+
+    let (chosen, result) = if llm_score.total > deterministic_score.total {
+        (Candidate::Llm, llm)
+    } else {
+        (Candidate::Deterministic, deterministic)
+    };
+
+    let selection = Selection {
+        chosen,
+        deterministic: deterministic_score,
+        llm: llm_score,
+    };
+
+    (result, selection)
+}
@@ -0,0 +1,202 @@
+//! Speculative Fallback
+//!
+//! Normally the LLM request only starts once the deterministic pass has
+//! reported low confidence, so interactive callers wait for both in turn.
+//! In speculative mode the confidence is predicted first from a cheap
+//! dictionary lookup, and for prose that looks likely to need the LLM the
+//! request starts alongside the deterministic pass. It is cancelled if the
+//! deterministic result clears the threshold after all.
+
+use crate::{llm_convert, ConversionOptionsExt, FallbackMode};
+use futures::future::{select, Either};
+use rosetta_aisp::{
+    prose_to_symbol, AispConverter, ConversionOptions, ConversionResult, ConversionTier,
+    RosettaStone, TokenStats,
+};
+use serde::{Deserialize, Serialize};
+
+/// Longest phrase, in words, looked up as a whole
+const MAX_PHRASE: usize = 3;
+
+/// What happened to the speculative LLM request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeculationOutcome {
+    /// Started early, and its result was used because the deterministic
+    /// result needed the LLM
+    Used,
+    /// Started early, and the deterministic result needed the LLM, but the
+    /// request failed or its output was rejected, so the deterministic
+    /// result was kept
+    Failed,
+    /// Started early, then cancelled because the deterministic result was
+    /// confident enough
+    Cancelled,
+    /// Not started, and the deterministic result was confident enough
+    Skipped,
+    /// Not started, but the deterministic result needed the LLM after all,
+    /// which was then asked as usual
+    Missed,
+}
+
+/// The prediction behind a speculative conversion and how it turned out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Speculation {
+    /// Confidence predicted before the deterministic pass
+    pub predicted_confidence: f64,
+    pub outcome: SpeculationOutcome,
+}
+
+/// Predict the confidence of the deterministic conversion of `prose`
+///
+/// Counts the characters of words and phrases of up to three words that
+/// have a Rosetta symbol, longest phrases first. This skips the pattern
+/// matching of the real conversion, so it runs in a fraction of the time;
+/// it tends to be close, but can miss inflected or split phrases.
+pub fn predict_confidence(prose: &str) -> f64 {
+    let (mapped, _) = lookup(prose);
+    RosettaStone::confidence(prose.len(), mapped)
+}
+
+/// Characters covered by symbol lookups, and the words left uncovered
+fn lookup(prose: &str) -> (usize, Vec<String>) {
+    let words: Vec<String> = prose
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+    let mut covered = vec![false; words.len()];
+    let mut mapped = 0;
+    for len in (1..=MAX_PHRASE).rev() {
+        for start in 0..words.len().saturating_sub(len - 1) {
+            let span = start..start + len;
+            if covered[span.clone()].iter().any(|c| *c) {
+                continue;
+            }
+            let phrase = words[span.clone()].join(" ");
+            if !phrase.is_empty() && prose_to_symbol(&phrase).is_some() {
+                covered[span].iter_mut().for_each(|c| *c = true);
+                mapped += phrase.len();
+            }
+        }
+    }
+
+    let mut unmapped: Vec<String> = Vec::new();
+    for (word, covered) in words.into_iter().zip(covered) {
+        let content = word.chars().count() > 2 && word.chars().all(char::is_alphabetic);
+        if !covered && content && !unmapped.contains(&word) {
+            unmapped.push(word);
+        }
+    }
+    (mapped, unmapped)
+}
+
+/// A prediction made before the deterministic pass
+pub(crate) struct Prediction {
+    pub confidence: f64,
+    /// Whether to start the LLM request early
+    pub start: bool,
+    /// Stand-in for the deterministic result in the early request
+    provisional: ConversionResult,
+}
+
+/// Predict whether `prose` will need the LLM
+///
+/// Only full fallbacks with a single sample and no model ladder are started
+/// early: targeted fallback splices into the deterministic output, which
+/// doesn't exist yet, and several samples or models are too much to spend
+/// on a guess.
+pub(crate) fn predict(prose: &str, opts: &ConversionOptionsExt, ambiguous: bool) -> Prediction {
+    let (mapped, unmapped) = lookup(prose);
+    let confidence = RosettaStone::confidence(prose.len(), mapped);
+    let threshold = opts.confidence_threshold.unwrap_or(0.8);
+    let eligible = opts.fallback_mode == FallbackMode::Full
+        && opts.samples <= 1
+        && opts.model_ladder.is_empty();
+    let tier: ConversionTier = opts
+        .tier
+        .unwrap_or_else(|| AispConverter::detect_tier(prose));
+
+    Prediction {
+        confidence,
+        start: eligible && (confidence < threshold || ambiguous),
+        provisional: ConversionResult {
+            output: String::new(),
+            confidence,
+            unmapped,
+            tier,
+            tokens: TokenStats {
+                input: prose.len(),
+                output: 0,
+                ratio: 0.0,
+            },
+            used_fallback: false,
+        },
+    }
+}
+
+/// Run the deterministic pass and the early LLM request side by side
+///
+/// Returns the deterministic result and, unless it was cancelled because
+/// `needs_llm` rejected the deterministic result, the LLM result.
+pub(crate) async fn race(
+    prose: &str,
+    base_options: ConversionOptions,
+    prediction: &Prediction,
+    opts: &ConversionOptionsExt,
+    provider: &dyn crate::LlmProvider,
+    needs_llm: impl Fn(&ConversionResult) -> bool,
+) -> (ConversionResult, Option<Option<ConversionResult>>) {
+    let early = Box::pin(async {
+        if provider.is_available().await {
+            llm_convert(prose, &prediction.provisional, opts, provider).await
+        } else {
+            None
+        }
+    });
+    // The deterministic pass is CPU-bound, so it runs off this task while
+    // the request is in flight
+    let owned = prose.to_string();
+    let deterministic =
+        tokio::task::spawn_blocking(move || AispConverter::convert(&owned, Some(base_options)));
+
+    match select(deterministic, early).await {
+        Either::Left((result, early)) => {
+            let result = result.expect("deterministic conversion panicked");
+            if needs_llm(&result) {
+                (result, Some(early.await))
+            } else {
+                // Dropping the request cancels it
+                (result, None)
+            }
+        }
+        Either::Right((llm_result, deterministic)) => {
+            let result = deterministic
+                .await
+                .expect("deterministic conversion panicked");
+            if needs_llm(&result) {
+                (result, Some(llm_result))
+            } else {
+                (result, None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predict_confidence() {
+        assert_eq!(predict_confidence("and"), 1.0);
+
+        let prose = "Neural networks approximate arbitrary continuous functions";
+        assert_eq!(predict_confidence(prose), 0.0);
+        let (_, unmapped) = lookup(prose);
+        assert_eq!(unmapped[0], "neural");
+        assert_eq!(unmapped.len(), 6);
+    }
+}
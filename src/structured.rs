@@ -0,0 +1,261 @@
+//! Structured Generation
+//!
+//! Free-form LLM text is the main source of malformed output: unbalanced
+//! brackets, prose between blocks, blocks in the wrong order. In JSON mode
+//! the provider fills in a [`StructuredDocument`] instead, which is checked
+//! field by field and rendered into canonical AISP text, so the layout never
+//! depends on the model.
+
+use crate::formatter::{format_aisp, FormatStyle};
+use crate::parser::parse;
+use crate::provider::{LlmProvider, LlmResult};
+use crate::repair::BLOCK_ORDER;
+use crate::tools;
+use crate::ConversionOptionsExt;
+use anyhow::{anyhow, bail, Result};
+use rosetta_aisp::ConversionTier;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// An AISP document as a JSON object
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredDocument {
+    /// The `𝔸` header; omitted for minimal documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<StructuredHeader>,
+    /// The `γ≔` context id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// The `ρ≔⟨…⟩` references
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+    /// Blocks other than evidence
+    #[serde(default)]
+    pub blocks: Vec<StructuredBlock>,
+    /// Entries of the `⟦Ε⟧` evidence block, which is omitted when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<StructuredEvidence>,
+}
+
+/// Header fields, as in `𝔸5.1.auth.rules@2026-01-09`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredHeader {
+    pub version: String,
+    pub name: String,
+    pub date: String,
+}
+
+/// A block and its statements, one AISP statement per entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredBlock {
+    /// Category glyph: `Ω`, `Σ`, `Γ`, `Λ` or `Χ`
+    pub category: String,
+    /// Name after the colon, e.g. `Types`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub statements: Vec<String>,
+}
+
+/// An evidence entry: a named field (`δ≜0.82`) or a claim (`⊢valid`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredEvidence {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub value: String,
+}
+
+impl StructuredDocument {
+    /// JSON Schema of the object providers are asked for
+    pub fn schema() -> Value {
+        let text = json!({ "type": "string", "minLength": 1 });
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["blocks"],
+            "properties": {
+                "header": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["version", "name", "date"],
+                    "properties": { "version": text, "name": text, "date": text },
+                },
+                "context": text,
+                "references": { "type": "array", "items": text },
+                "blocks": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "required": ["category", "statements"],
+                        "properties": {
+                            "category": { "enum": ["Ω", "Σ", "Γ", "Λ", "Χ"] },
+                            "name": text,
+                            "statements": { "type": "array", "items": text },
+                        },
+                    },
+                },
+                "evidence": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "required": ["value"],
+                        "properties": { "name": text, "value": text },
+                    },
+                },
+            },
+        })
+    }
+
+    /// Parse the outermost JSON object in `response`, ignoring text around it
+    pub fn from_json(response: &str) -> Result<Self> {
+        let Some((start, end)) = response
+            .find('{')
+            .zip(response.rfind('}'))
+            .filter(|(start, end)| start < end)
+        else {
+            bail!("No JSON object in response");
+        };
+        serde_json::from_str(&response[start..=end])
+            .map_err(|e| anyhow!("Invalid structured document: {}", e))
+    }
+
+    /// Render as canonical AISP text
+    ///
+    /// Fails if a field doesn't parse as what it stands for, for example a
+    /// statement entry holding two statements or an unknown block category.
+    pub fn to_aisp(&self) -> Result<String> {
+        let mut lines = Vec::new();
+        if let Some(header) = &self.header {
+            lines.push(format!(
+                "𝔸{}.{}@{}",
+                header.version, header.name, header.date
+            ));
+        }
+        if let Some(context) = &self.context {
+            lines.push(format!("γ≔{}", context));
+        }
+        if !self.references.is_empty() {
+            lines.push(format!("ρ≔⟨{}⟩", self.references.join(",")));
+        }
+        for block in &self.blocks {
+            let known = BLOCK_ORDER.iter().any(|(c, _)| *c == block.category);
+            if !known || block.category == "Ε" {
+                bail!("Unknown block category `{}`", block.category);
+            }
+            match &block.name {
+                Some(name) => lines.push(format!("⟦{}:{}⟧{{", block.category, name)),
+                None => lines.push(format!("⟦{}⟧{{", block.category)),
+            }
+            lines.extend(block.statements.iter().map(|s| s.trim().to_string()));
+            lines.push("}".to_string());
+        }
+        if !self.evidence.is_empty() {
+            let entries: Vec<String> = self
+                .evidence
+                .iter()
+                .map(|entry| match &entry.name {
+                    Some(name) => format!("{}≜{}", name, entry.value),
+                    None => entry.value.clone(),
+                })
+                .collect();
+            lines.push(format!("⟦Ε⟧⟨{}⟩", entries.join(";")));
+        }
+        let text = lines.join("\n") + "\n";
+
+        // Every field must come back as exactly what it was meant to be
+        let parsed = parse(&text);
+        if let Some(error) = parsed.errors.first() {
+            bail!("Structured document does not parse: {}", error.message);
+        }
+        let doc = &parsed.document;
+        if self.header.is_some() != doc.header.is_some()
+            || self.context.is_some() != doc.context.is_some()
+            || doc.blocks.len() != self.blocks.len()
+            || doc.evidence.as_ref().map_or(0, |e| e.entries.len()) != self.evidence.len()
+        {
+            bail!("Structured document fields spill into each other");
+        }
+        for (block, parsed) in self.blocks.iter().zip(&doc.blocks) {
+            if parsed.body.len() != block.statements.len() {
+                bail!(
+                    "Block ⟦{}⟧ has {} statement entries but {} statements",
+                    block.category,
+                    block.statements.len(),
+                    parsed.body.len()
+                );
+            }
+        }
+
+        format_aisp(&text, FormatStyle::Pretty)
+            .map_err(|errors| anyhow!("Structured document does not format: {:?}", errors))
+    }
+}
+
+/// Ask the provider for a document, as JSON if `opts.json_mode` is set
+///
+/// JSON responses are rendered into AISP. Providers that can't produce
+/// JSON, and JSON that isn't a valid document, fall back to free text.
+pub(crate) async fn convert(
+    provider: &dyn LlmProvider,
+    prose: &str,
+    tier: ConversionTier,
+    unmapped: &[String],
+    partial_output: Option<&str>,
+    opts: &ConversionOptionsExt,
+) -> Result<LlmResult> {
+    if opts.json_mode {
+        let structured = provider
+            .convert_structured(prose, tier, unmapped, partial_output, opts.use_aisp_prompt)
+            .await;
+        if let Ok(Some(mut result)) = structured {
+            let rendered = StructuredDocument::from_json(&result.output)
+                .and_then(|document| document.to_aisp());
+            if let Ok(aisp) = rendered {
+                result.output = aisp;
+                return Ok(result);
+            }
+        }
+    }
+    tools::convert(provider, prose, tier, unmapped, partial_output, opts).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_document_renders_canonically() {
+        let response = r#"Here it is:
+        {
+          "header": {"version": "5.1", "name": "auth", "date": "2026-01-09"},
+          "context": "auth",
+          "blocks": [
+            {"category": "Λ", "name": "Funcs", "statements": ["f ≜ λx.x"]},
+            {"category": "Ω", "name": "Meta", "statements": ["domain≜auth"]}
+          ],
+          "evidence": [{"name": "δ", "value": "0.82"}, {"value": "⊢valid"}]
+        }"#;
+        let document = StructuredDocument::from_json(response).unwrap();
+        assert_eq!(
+            document.to_aisp().unwrap(),
+            "𝔸5.1.auth@2026-01-09\nγ≔auth\n\n⟦Ω:Meta⟧{\n  domain≜auth\n}\n\n\
+             ⟦Λ:Funcs⟧{\n  f≜λx.x\n}\n\n⟦Ε⟧⟨δ≜0.82;⊢valid⟩\n"
+        );
+    }
+
+    #[test]
+    fn test_structured_document_rejects_malformed_fields() {
+        let check = |json: &str| StructuredDocument::from_json(json).and_then(|d| d.to_aisp());
+        assert!(check(r#"{"blocks": [{"category": "Λ", "statements": ["a≜1; b≜2"]}]}"#).is_err());
+        assert!(check(r#"{"blocks": [{"category": "Q", "statements": ["a≜1"]}]}"#).is_err());
+        assert!(check(r#"{"blocks": [{"category": "Λ", "statements": ["a≜(1"]}]}"#).is_err());
+        assert!(check(r#"{"blocks": [], "extra": 1}"#).is_err());
+        assert!(check("not json").is_err());
+        assert!(check(r#"{"blocks": [{"category": "Λ", "statements": ["a≜1"]}]}"#).is_ok());
+    }
+}
@@ -0,0 +1,211 @@
+//! Targeted Fallback
+//!
+//! Instead of re-converting the whole prose, sends only the phrases the
+//! deterministic pass left unmapped to the LLM and splices the translations
+//! back into the deterministic output. The mapped parts are preserved exactly.
+
+use crate::provider::{token_stats, LlmProvider, SpanRequest, LLM_CONFIDENCE};
+use rosetta_aisp::ConversionResult;
+use std::ops::Range;
+
+/// Number of words of surrounding prose sent with each span
+const CONTEXT_WORDS: usize = 4;
+
+/// Build span requests for the unmapped phrases of a conversion
+///
+/// Duplicate phrases are sent once; phrases are kept in input order.
+fn extract_spans(prose: &str, unmapped: &[String]) -> Vec<SpanRequest> {
+    let mut spans: Vec<SpanRequest> = Vec::new();
+
+    for phrase in unmapped {
+        let text = phrase.trim();
+        if text.is_empty() || spans.iter().any(|s| s.text == text) {
+            continue;
+        }
+        spans.push(SpanRequest {
+            text: text.to_string(),
+            context: context_around(prose, text),
+        });
+    }
+
+    spans
+}
+
+/// Replace each span in the deterministic output with its translation
+///
+/// Every occurrence of a span (on word boundaries, ignoring case, since
+/// Rosetta reports unmapped words in lowercase) is replaced. Returns `None`
+/// if any span cannot be located, in which case the output cannot be patched
+/// reliably and the caller should fall back to a full conversion.
+fn splice(output: &str, spans: &[SpanRequest], translations: &[String]) -> Option<String> {
+    if spans.len() != translations.len() {
+        return None;
+    }
+
+    let mut spliced = output.to_string();
+    for (span, translation) in spans.iter().zip(translations) {
+        let positions = find_word(&spliced, &span.text);
+        if positions.is_empty() {
+            return None;
+        }
+        for range in positions.into_iter().rev() {
+            spliced.replace_range(range, translation.trim());
+        }
+    }
+
+    Some(spliced)
+}
+
+/// Run targeted fallback for a low-confidence deterministic result
+///
+/// Returns `None` when there is nothing to target, the provider fails, or
+/// the translations cannot be spliced back in.
+pub(crate) async fn convert(
+    prose: &str,
+    result: &ConversionResult,
+    provider: &dyn LlmProvider,
+    use_aisp_prompt: bool,
+) -> Option<ConversionResult> {
+    let spans = extract_spans(prose, &result.unmapped);
+    if spans.is_empty() {
+        return None;
+    }
+
+    let translations = provider.convert_spans(&spans, use_aisp_prompt).await.ok()?;
+    let output = splice(&result.output, &spans, &translations)?;
+
+    Some(ConversionResult {
+        tokens: token_stats(prose.len(), output.len()),
+        output,
+        confidence: LLM_CONFIDENCE,
+        unmapped: vec![],
+        tier: result.tier,
+        used_fallback: true,
+    })
+}
+
+/// Up to `CONTEXT_WORDS` words either side of the first occurrence of `text`
+fn context_around(prose: &str, text: &str) -> String {
+    let Some(Range { start, end }) = find_word(prose, text).into_iter().next() else {
+        return String::new();
+    };
+    // Keep the casing of the prose
+    let text = &prose[start..end];
+
+    let before: Vec<&str> = prose[..start].split_whitespace().collect();
+    let after: Vec<&str> = prose[end..].split_whitespace().collect();
+    let before = &before[before.len().saturating_sub(CONTEXT_WORDS)..];
+    let after = &after[..after.len().min(CONTEXT_WORDS)];
+
+    let mut context = before.join(" ");
+    if !context.is_empty() {
+        context.push(' ');
+    }
+    context.push_str(text);
+    if !after.is_empty() {
+        context.push(' ');
+        context.push_str(&after.join(" "));
+    }
+    context
+}
+
+/// Byte ranges of every occurrence of `word` on word boundaries, ignoring case
+pub(crate) fn find_word(haystack: &str, word: &str) -> Vec<Range<usize>> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let lowercase = |s: &str| {
+        s.chars()
+            .flat_map(char::to_lowercase)
+            .collect::<Vec<char>>()
+    };
+    let word = lowercase(word);
+    if word.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    for (start, _) in haystack.char_indices() {
+        if start < from {
+            continue;
+        }
+        // Shortest prefix of the rest that lowercases to `word`
+        let rest = &haystack[start..];
+        let mut lowered = Vec::with_capacity(word.len());
+        let mut end = None;
+        for (offset, c) in rest.char_indices() {
+            lowered.extend(c.to_lowercase());
+            if !word.starts_with(&lowered) {
+                break;
+            }
+            if lowered.len() == word.len() {
+                end = Some(start + offset + c.len_utf8());
+                break;
+            }
+        }
+        let Some(end) = end else { continue };
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[end..].chars().next();
+        if !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char) {
+            ranges.push(start..end);
+            from = end;
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_spans_dedupes_and_adds_context() {
+        let prose = "The quantum entanglement manifests probabilistic correlation";
+        let unmapped = vec![
+            "entanglement".to_string(),
+            "entanglement".to_string(),
+            "correlation".to_string(),
+        ];
+
+        let spans = extract_spans(prose, &unmapped);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "entanglement");
+        assert_eq!(
+            spans[0].context,
+            "The quantum entanglement manifests probabilistic correlation"
+        );
+        assert_eq!(
+            spans[1].context,
+            "quantum entanglement manifests probabilistic correlation"
+        );
+    }
+
+    #[test]
+    fn test_splice_respects_word_boundaries() {
+        let spans = vec![SpanRequest {
+            text: "user".to_string(),
+            context: String::new(),
+        }];
+        let output = splice("∀user∈users:valid(user)", &spans, &["u".to_string()]).unwrap();
+        assert_eq!(output, "∀u∈users:valid(u)");
+    }
+
+    #[test]
+    fn test_splice_ignores_case() {
+        let spans = vec![SpanRequest {
+            text: "neural".to_string(),
+            context: String::new(),
+        }];
+        let output = splice("Neural∧neural∧neurals", &spans, &["ν".to_string()]).unwrap();
+        assert_eq!(output, "ν∧ν∧neurals");
+        assert_eq!(find_word("Über über", "über"), vec![0..5, 6..11]);
+    }
+
+    #[test]
+    fn test_splice_missing_span() {
+        let spans = vec![SpanRequest {
+            text: "absent".to_string(),
+            context: String::new(),
+        }];
+        assert!(splice("x≜5", &spans, &["a".to_string()]).is_none());
+    }
+}
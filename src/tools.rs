@@ -0,0 +1,203 @@
+//! Validation Tools
+//!
+//! In agentic mode the LLM can check its own output before answering,
+//! instead of the crate discarding it afterwards. The tools take and return
+//! plain JSON and text, so a provider can expose them through whatever tool
+//! use its API offers ([`crate::ClaudeFallback`] serves them from an
+//! in-process MCP server), and stand-in providers can call them directly.
+
+use crate::glyphs::{check_glyphs, GlyphClass};
+use crate::provider::{LlmProvider, LlmResult};
+use crate::validator::{conforming_tier, validate};
+use crate::ConversionOptionsExt;
+use anyhow::{anyhow, bail, Result};
+use rosetta_aisp::{prose_to_symbol, symbol_to_prose, ConversionTier};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Name of the tool that validates a draft
+pub const VALIDATE_AISP: &str = "validate_aisp";
+
+/// Name of the tool that looks up symbols and their prose
+pub const LOOKUP_SYMBOL: &str = "lookup_symbol";
+
+/// A tool as described to the LLM
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the arguments
+    pub input_schema: Value,
+}
+
+/// The tools offered during an agentic conversion at one tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationTools {
+    tier: ConversionTier,
+}
+
+impl ValidationTools {
+    /// Tools that validate drafts against `tier`
+    pub fn new(tier: ConversionTier) -> Self {
+        Self { tier }
+    }
+
+    /// The tier drafts are validated against
+    pub fn tier(&self) -> ConversionTier {
+        self.tier
+    }
+
+    /// Every tool, for the provider to announce
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition {
+                name: VALIDATE_AISP,
+                description: "Check an AISP draft the way the converter will: parse errors, \
+                              missing header or blocks, low density, unknown glyphs and \
+                              the tier it conforms to.",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "aisp": { "type": "string" } },
+                    "required": ["aisp"],
+                }),
+            },
+            ToolDefinition {
+                name: LOOKUP_SYMBOL,
+                description: "Find the AISP symbol for a prose pattern such as \"for all\", \
+                              or the prose for a symbol such as \"∀\".",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "query": { "type": "string" } },
+                    "required": ["query"],
+                }),
+            },
+        ]
+    }
+
+    /// Run the tool `name` with JSON `args`, returning its text result
+    pub fn call(&self, name: &str, args: &Value) -> Result<String> {
+        let argument = |key: &str| {
+            args[key]
+                .as_str()
+                .ok_or_else(|| anyhow!("Missing `{}` argument", key))
+        };
+        match name {
+            VALIDATE_AISP => Ok(self.validate_aisp(argument("aisp")?)),
+            LOOKUP_SYMBOL => Ok(self.lookup_symbol(argument("query")?)),
+            _ => bail!("Unknown tool `{}`", name),
+        }
+    }
+
+    /// Report whether `aisp` would be accepted, and what to fix if not
+    ///
+    /// The first line is `accepted` or `rejected`, followed by the tier
+    /// glyph, δ and φ, then one line per problem.
+    pub fn validate_aisp(&self, aisp: &str) -> String {
+        let validation = validate(aisp);
+        let unknown: Vec<String> = check_glyphs(aisp)
+            .into_iter()
+            .filter(|issue| issue.class == GlyphClass::Unknown)
+            .map(|issue| issue.glyph)
+            .collect();
+        let accepted = validation.accepts(self.tier) && unknown.is_empty();
+
+        let mut lines = vec![
+            format!(
+                "{} for the {} tier",
+                if accepted { "accepted" } else { "rejected" },
+                self.tier
+            ),
+            format!(
+                "τ≜{} δ≜{:.2} φ≜{}",
+                validation.tier, validation.density, validation.completeness
+            ),
+        ];
+        lines.extend(
+            validation
+                .violations
+                .iter()
+                .map(|v| format!("- {}", v.message)),
+        );
+        lines.extend(
+            unknown
+                .iter()
+                .map(|glyph| format!("- unknown glyph `{}`", glyph)),
+        );
+        let conforming = conforming_tier(aisp);
+        if conforming != self.tier {
+            lines.push(format!("- conforms to the {} tier", conforming));
+        }
+        lines.join("\n")
+    }
+
+    /// The symbol for a prose pattern, or the prose for a symbol
+    pub fn lookup_symbol(&self, query: &str) -> String {
+        let query = query.trim();
+        if let Some(symbol) = prose_to_symbol(query) {
+            format!("\"{}\" is {}", query, symbol)
+        } else if let Some(prose) = symbol_to_prose(query) {
+            format!("{} means \"{}\"", query, prose)
+        } else {
+            format!("No symbol or prose found for \"{}\"", query)
+        }
+    }
+}
+
+/// Ask the provider for AISP text, agentically if `opts.agentic` is set
+///
+/// Providers without tool use, and failed agentic requests, fall back to a
+/// plain single-turn conversion.
+pub(crate) async fn convert(
+    provider: &dyn LlmProvider,
+    prose: &str,
+    tier: ConversionTier,
+    unmapped: &[String],
+    partial_output: Option<&str>,
+    opts: &ConversionOptionsExt,
+) -> Result<LlmResult> {
+    if opts.agentic {
+        let tools = ValidationTools::new(tier);
+        let agentic = provider
+            .convert_agentic(
+                prose,
+                tier,
+                unmapped,
+                partial_output,
+                opts.use_aisp_prompt,
+                &tools,
+            )
+            .await;
+        if let Ok(Some(result)) = agentic {
+            return Ok(result);
+        }
+    }
+    provider
+        .convert(prose, tier, unmapped, partial_output, opts.use_aisp_prompt)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_tools() {
+        let tools = ValidationTools::new(ConversionTier::Standard);
+        let report = tools
+            .call(VALIDATE_AISP, &json!({ "aisp": "⟦Λ:Funcs⟧{f≜λx.x" }))
+            .unwrap();
+        assert!(report.starts_with("rejected for the standard tier\nτ≜"));
+        assert!(report.lines().count() > 2);
+
+        let valid = "𝔸5.1.t@2026-01-09\nγ≔t\n⟦Λ:Funcs⟧{f≜λx.∀y∈S:y⇒x}\n⟦Ε⟧⟨δ≜0.9;τ≜◊⁺⁺⟩";
+        assert!(tools.validate_aisp(valid).starts_with("accepted"));
+
+        let symbol = tools
+            .call(LOOKUP_SYMBOL, &json!({ "query": "for all" }))
+            .unwrap();
+        assert_eq!(symbol, "\"for all\" is ∀");
+        assert_eq!(tools.lookup_symbol("∀"), "∀ means \"for all\"");
+        assert!(tools.call(LOOKUP_SYMBOL, &json!({})).is_err());
+        assert!(tools.call("read_file", &json!({})).is_err());
+    }
+}
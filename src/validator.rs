@@ -0,0 +1,396 @@
+//! AISP Document Validation
+//!
+//! Implements `validate≜⌈⌉∘δ∘Γ?∘∂` from the specification: parse the
+//! document, check well-formedness (`wf₁`: header present, `wf₂`: at least
+//! two blocks), compute the symbol density δ outside the evidence block and
+//! comments, and map it to a quality tier τ.
+
+use crate::ast::{Document, Span};
+use crate::lexer::{tokenize, TokenKind};
+use crate::parser::parse;
+use crate::quality::density;
+use rosetta_aisp::ConversionTier;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Blocks every complete document carries (`Required≜{⟦Ω⟧,⟦Σ⟧,⟦Γ⟧,⟦Λ⟧,⟦Ε⟧}`)
+pub const REQUIRED_BLOCKS: &[&str] = &["Ω", "Σ", "Γ", "Λ", "Ε"];
+
+/// Blocks a conversion at `tier` must contain
+///
+/// Minimal outputs are bare statements; Standard outputs carry functions and
+/// evidence; Full outputs carry every required block.
+pub fn required_blocks(tier: ConversionTier) -> &'static [&'static str] {
+    match tier {
+        ConversionTier::Minimal => &[],
+        ConversionTier::Standard => &["Λ", "Ε"],
+        ConversionTier::Full => REQUIRED_BLOCKS,
+    }
+}
+
+/// The highest conversion tier a document conforms to
+///
+/// Full and Standard documents have a header and the blocks
+/// [`required_blocks`] lists for the tier; anything else, such as a bare
+/// one-line statement, is Minimal.
+pub fn conforming_tier(source: &str) -> ConversionTier {
+    let doc = parse(source).document;
+    if doc.header.is_none() {
+        return ConversionTier::Minimal;
+    }
+    [ConversionTier::Full, ConversionTier::Standard]
+        .into_iter()
+        .find(|tier| required_blocks(*tier).iter().all(|c| doc.has_block(c)))
+        .unwrap_or(ConversionTier::Minimal)
+}
+
+/// Position of `tier` in Minimal < Standard < Full
+pub(crate) fn tier_rank(tier: ConversionTier) -> u8 {
+    match tier {
+        ConversionTier::Minimal => 0,
+        ConversionTier::Standard => 1,
+        ConversionTier::Full => 2,
+    }
+}
+
+/// Quality tier τ (`◊≜{◊⁺⁺≻◊⁺≻◊≻◊⁻≻⊘}`), ordered from worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityTier {
+    /// `⊘`: δ < 0.20 or not well-formed
+    Reject,
+    /// `◊⁻`: δ ≥ 0.20
+    Bronze,
+    /// `◊`: δ ≥ 0.40
+    Silver,
+    /// `◊⁺`: δ ≥ 0.60
+    Gold,
+    /// `◊⁺⁺`: δ ≥ 0.75
+    Platinum,
+}
+
+impl QualityTier {
+    /// Tier for a density per `⌈⌉≜λd.[≥¾↦◊⁺⁺,≥⅗↦◊⁺,≥⅖↦◊,≥⅕↦◊⁻,_↦⊘](d)`
+    pub fn from_density(density: f64) -> Self {
+        if density >= 0.75 {
+            QualityTier::Platinum
+        } else if density >= 0.60 {
+            QualityTier::Gold
+        } else if density >= 0.40 {
+            QualityTier::Silver
+        } else if density >= 0.20 {
+            QualityTier::Bronze
+        } else {
+            QualityTier::Reject
+        }
+    }
+
+    /// Parse a tier glyph such as `◊⁺⁺`
+    pub fn from_glyph(glyph: &str) -> Option<Self> {
+        Some(match glyph.trim() {
+            "◊⁺⁺" => QualityTier::Platinum,
+            "◊⁺" => QualityTier::Gold,
+            "◊" => QualityTier::Silver,
+            "◊⁻" => QualityTier::Bronze,
+            "⊘" => QualityTier::Reject,
+            _ => return None,
+        })
+    }
+
+    /// The tier glyph, e.g. `◊⁺⁺`
+    pub fn glyph(self) -> &'static str {
+        match self {
+            QualityTier::Platinum => "◊⁺⁺",
+            QualityTier::Gold => "◊⁺",
+            QualityTier::Silver => "◊",
+            QualityTier::Bronze => "◊⁻",
+            QualityTier::Reject => "⊘",
+        }
+    }
+}
+
+impl fmt::Display for QualityTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.glyph())
+    }
+}
+
+/// Kinds of validation failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ViolationKind {
+    /// The document does not parse
+    Parse,
+    /// No `𝔸Ver.Name@Date` header (`wf₁`)
+    MissingHeader,
+    /// Fewer than two blocks (`wf₂`)
+    TooFewBlocks,
+    /// A required block is absent
+    MissingBlock,
+    /// δ below the lowest tier (`⊘`)
+    LowDensity,
+}
+
+/// A single validation failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// What failed
+    pub kind: ViolationKind,
+    /// Human-readable description
+    pub message: String,
+    /// Where in the source, if the failure has a location
+    pub span: Option<Span>,
+}
+
+impl Violation {
+    fn new(kind: ViolationKind, message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+/// Validation result `𝕍≜Σ(ν:𝔹)(τ:◊)(δ:ℝ[0,1])(φ:Fin 101)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validation {
+    /// ν: parses and satisfies `wf₁` and `wf₂`
+    ///
+    /// δ is reported but doesn't affect ν, since the header and context lines
+    /// alone keep short documents below `◊⁻`; τ is `⊘` for them instead.
+    pub valid: bool,
+    /// τ: quality tier
+    pub tier: QualityTier,
+    /// δ: symbol density outside the evidence block and comments (0.0 - 1.0)
+    pub density: f64,
+    /// φ: percentage of required blocks present (0 - 100)
+    pub completeness: u8,
+    /// Every failed check; only parse errors, `wf₁` and `wf₂` affect ν
+    pub violations: Vec<Violation>,
+}
+
+impl Validation {
+    /// Whether any violation of `kind` was found
+    pub fn has(&self, kind: ViolationKind) -> bool {
+        self.violations.iter().any(|v| v.kind == kind)
+    }
+
+    /// Whether an output converted at `tier` is acceptable
+    ///
+    /// Minimal-tier outputs are bare statements without header or blocks, so
+    /// they only need to parse and reach the `◊⁻` density. Other tiers must be
+    /// valid.
+    pub fn accepts(&self, tier: ConversionTier) -> bool {
+        match tier {
+            ConversionTier::Minimal => {
+                !self.has(ViolationKind::Parse) && !self.has(ViolationKind::LowDensity)
+            }
+            _ => self.valid,
+        }
+    }
+}
+
+/// Validate an AISP document
+pub fn validate(source: &str) -> Validation {
+    let parsed = parse(source);
+    let mut violations: Vec<Violation> = parsed
+        .errors
+        .iter()
+        .map(|e| Violation::new(ViolationKind::Parse, e.message.clone(), Some(e.span)))
+        .collect();
+    let doc = &parsed.document;
+
+    // wf₁: d↓₁≡𝔸
+    if doc.header.is_none() {
+        violations.push(Violation::new(
+            ViolationKind::MissingHeader,
+            "document has no `𝔸Ver.Name@Date` header",
+            None,
+        ));
+    }
+
+    // wf₂: |b⃗|≥2, counting the evidence block
+    let blocks = block_count(doc);
+    if blocks < 2 {
+        violations.push(Violation::new(
+            ViolationKind::TooFewBlocks,
+            format!("document has {} block(s), at least 2 are required", blocks),
+            None,
+        ));
+    }
+
+    let missing: Vec<&str> = REQUIRED_BLOCKS
+        .iter()
+        .copied()
+        .filter(|category| !doc.has_block(category))
+        .collect();
+    for category in &missing {
+        violations.push(Violation::new(
+            ViolationKind::MissingBlock,
+            format!("missing required block ⟦{}⟧", category),
+            None,
+        ));
+    }
+
+    let density = document_density(source, doc.evidence.as_ref().map(|e| e.span));
+    if density < 0.20 {
+        violations.push(Violation::new(
+            ViolationKind::LowDensity,
+            format!("density δ={:.2} is below the ◊⁻ threshold of 0.20", density),
+            None,
+        ));
+    }
+
+    let well_formed = !violations.iter().any(|v| {
+        matches!(
+            v.kind,
+            ViolationKind::Parse | ViolationKind::MissingHeader | ViolationKind::TooFewBlocks
+        )
+    });
+
+    // δ(d)<⅕ ∨ ¬wf(d) ⊢ d:⊘
+    let tier = if well_formed {
+        QualityTier::from_density(density)
+    } else {
+        QualityTier::Reject
+    };
+    let present = REQUIRED_BLOCKS.len() - missing.len();
+
+    Validation {
+        valid: well_formed,
+        tier,
+        density,
+        completeness: (present * 100 / REQUIRED_BLOCKS.len()) as u8,
+        violations,
+    }
+}
+
+/// δ of a document, leaving out the `⟦Ε⟧` block and `;;` comments
+///
+/// The evidence block claims δ itself, so counting it would make the claim
+/// change the value it claims.
+fn document_density(source: &str, evidence: Option<Span>) -> f64 {
+    let mut cuts: Vec<Span> = tokenize(source)
+        .into_iter()
+        .filter(|t| t.kind == TokenKind::Comment)
+        .map(|t| t.span)
+        .chain(evidence)
+        .collect();
+    cuts.sort_by_key(|span| span.start);
+
+    let mut body = String::with_capacity(source.len());
+    let mut from = 0;
+    for cut in cuts {
+        if cut.start >= from {
+            body.push_str(&source[from..cut.start]);
+            body.push('\n');
+        }
+        from = from.max(cut.end);
+    }
+    body.push_str(&source[from..]);
+    density(&body)
+}
+
+/// Number of blocks including the evidence block
+fn block_count(doc: &Document) -> usize {
+    doc.blocks.len() + usize::from(doc.evidence.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "𝔸5.1.x@2026-01-09
+γ≔x
+⟦Ω:Meta⟧{∀x∈S:⊤}
+⟦Σ:Types⟧{T≜⟨⟩}
+⟦Γ:Rules⟧{∀x:P⇒Q}
+⟦Λ:Funcs⟧{f≜λx.x}
+⟦Ε⟧⟨δ≜0.5;τ≜◊⟩";
+
+    #[test]
+    fn test_conforming_tier() {
+        assert_eq!(conforming_tier("x≜5"), ConversionTier::Minimal);
+        assert_eq!(
+            conforming_tier("⟦Λ:Funcs⟧{f≜⊤}\n⟦Ε⟧⟨δ≜0.5⟩"),
+            ConversionTier::Minimal
+        );
+
+        let standard = "𝔸5.1.x@2026-01-09\n⟦Λ:Funcs⟧{f≜⊤}\n⟦Ε⟧⟨δ≜0.5⟩";
+        assert_eq!(conforming_tier(standard), ConversionTier::Standard);
+
+        let full = "𝔸5.1.x@2026-01-09\n⟦Ω:Meta⟧{⊤}\n⟦Σ:Types⟧{T≜ℕ}\n⟦Γ:Rules⟧{a⇒b}\n\
+                    ⟦Λ:Funcs⟧{f≜⊤}\n⟦Ε⟧⟨δ≜0.5⟩";
+        assert_eq!(conforming_tier(full), ConversionTier::Full);
+    }
+
+    #[test]
+    fn test_density_skips_evidence_and_comments() {
+        let commented = DOC.replace("γ≔x", "γ≔x ;; ∀∀∀∀\n;; ⟦⟧⟦⟧");
+        let bare = DOC.replace("\n⟦Ε⟧⟨δ≜0.5;τ≜◊⟩", "");
+        assert_eq!(validate(&commented).density, validate(DOC).density);
+        assert_eq!(validate(&bare).density, validate(DOC).density);
+    }
+
+    #[test]
+    fn test_tier_from_density() {
+        assert_eq!(QualityTier::from_density(0.80), QualityTier::Platinum);
+        assert_eq!(QualityTier::from_density(0.75), QualityTier::Platinum);
+        assert_eq!(QualityTier::from_density(0.60), QualityTier::Gold);
+        assert_eq!(QualityTier::from_density(0.45), QualityTier::Silver);
+        assert_eq!(QualityTier::from_density(0.20), QualityTier::Bronze);
+        assert_eq!(QualityTier::from_density(0.19), QualityTier::Reject);
+        assert!(QualityTier::Platinum > QualityTier::Bronze);
+        assert_eq!(QualityTier::Gold.to_string(), "◊⁺");
+        assert_eq!(QualityTier::from_glyph("◊⁻"), Some(QualityTier::Bronze));
+        assert_eq!(QualityTier::from_glyph("◊⁺⁺⁺"), None);
+    }
+
+    #[test]
+    fn test_validate_complete_document() {
+        let validation = validate(DOC);
+        assert!(validation.valid, "violations: {:?}", validation.violations);
+        assert!(validation.violations.is_empty());
+        assert_eq!(validation.completeness, 100);
+        assert_eq!(
+            validation.tier,
+            QualityTier::from_density(validation.density)
+        );
+    }
+
+    #[test]
+    fn test_validate_well_formedness() {
+        let validation = validate("⟦Λ:Funcs⟧{f≜λx.x}");
+        assert!(!validation.valid);
+        assert_eq!(validation.tier, QualityTier::Reject);
+        assert!(validation.has(ViolationKind::MissingHeader));
+        assert!(validation.has(ViolationKind::TooFewBlocks));
+        assert_eq!(validation.completeness, 20);
+
+        // Missing optional-for-ν blocks lower φ but keep the document valid
+        let validation = validate("𝔸5.1.x@2026-01-09\n⟦Λ:Funcs⟧{f≜λx.x}\n⟦Ε⟧⟨δ≜0.5⟩");
+        assert!(validation.valid);
+        assert!(validation.has(ViolationKind::MissingBlock));
+        assert_eq!(validation.completeness, 40);
+    }
+
+    #[test]
+    fn test_accepts_by_tier() {
+        let fragment = validate("x≜5");
+        assert!(fragment.accepts(ConversionTier::Minimal));
+        assert!(!fragment.accepts(ConversionTier::Standard));
+
+        let broken = validate("f≜λx.(x");
+        assert!(!broken.accepts(ConversionTier::Minimal));
+
+        let prose = validate("the user must log in");
+        assert!(!prose.accepts(ConversionTier::Minimal));
+
+        // Short Standard documents fall below ◊⁻ but are still accepted
+        let sparse = validate("𝔸5.1.users@2026-01-09\nγ≔users\n⟦Λ:Funcs⟧{f≜λx.x}\n⟦Ε⟧⟨δ≜0.7⟩");
+        assert!(sparse.valid);
+        assert_eq!(sparse.tier, QualityTier::Reject);
+        assert!(sparse.accepts(ConversionTier::Standard));
+    }
+}
@@ -4,10 +4,23 @@
 //! These tests verify that low-confidence conversions can be improved
 //! using the rosetta-aisp-llm integration.
 
+use anyhow::Result;
+use async_trait::async_trait;
 use rosetta_aisp_llm::{
-    convert_with_fallback, AispConverter, ClaudeFallback, ConversionOptions, ConversionOptionsExt,
-    ConversionResult, ConversionTier, LlmProvider, RosettaStone,
+    convert_clarified_with_provider, convert_interactive_with_provider,
+    convert_or_clarify_with_provider, convert_with_fallback, convert_with_provider,
+    convert_with_provider_ext, predict_confidence, validate, verify_evidence, AispConverter,
+    AlignmentOrigin, Candidate, ClarificationAnswer, ClarificationHandler, ClarificationQuestion,
+    ClarificationRequest, ClaudeFallback, ConversionOptions, ConversionOptionsExt,
+    ConversionOutcome, ConversionResult, ConversionTier, Escalation, EvidenceMode, FallbackMode,
+    FirstInterpretation, HedgeStats, HedgedProvider, LlmProvider, LlmResult, PoolStats, RepairKind,
+    Reply, RosettaStone, SessionFactory, SessionPool, SourceQuote, SpanRequest, SpeculationOutcome,
+    TierCheck, ValidationTools, WarmSession, VALIDATE_AISP,
 };
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Test cases with expected low deterministic confidence
 /// These represent prose that doesn't map well to standard Rosetta patterns
@@ -26,6 +39,239 @@ const HIGH_CONFIDENCE_CASES: &[&str] = &[
     "there exists a user such that admin is true",
 ];
 
+/// Output returned by `StubProvider` for whole-prose conversions
+const STUB_OUTPUT: &str = "⟦Λ:Funcs⟧{stub≜⊤}";
+
+/// Stand-in provider that answers without calling an LLM
+struct StubProvider;
+
+#[async_trait]
+impl LlmProvider for StubProvider {
+    async fn convert(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        Ok(LlmResult {
+            output: STUB_OUTPUT.to_string(),
+            provider: "stub".to_string(),
+            model: "stub".to_string(),
+            tokens_used: None,
+            cost_usd: None,
+        })
+    }
+
+    async fn convert_spans(
+        &self,
+        spans: &[SpanRequest],
+        _use_aisp_prompt: bool,
+    ) -> Result<Vec<String>> {
+        Ok(spans.iter().map(|_| "⊗".to_string()).collect())
+    }
+
+    async fn align(&self, prose: &str, _aisp: &str) -> Result<Vec<SourceQuote>> {
+        // Quotes the first word of the prose as the source of the one statement
+        let first = prose.split_whitespace().next().unwrap_or_default();
+        Ok(vec![SourceQuote {
+            statement: "stub ≜ ⊤".to_string(),
+            source: vec![first.to_string()],
+        }])
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+}
+
+/// Answer in prose instead of AISP
+const PROSE_OUTPUT: &str = "Sure! Here is the AISP for your text.";
+
+/// Standard document without header and with an unclosed block
+const BROKEN_OUTPUT: &str = "⟦Λ:Funcs⟧{\n  f≜λx.∀y∈S:y⇒x\n⟦Ε⟧⟨δ≜0.9;τ≜◊⁺⁺⟩";
+
+/// Valid Standard document
+const STANDARD_OUTPUT: &str =
+    "𝔸5.1.short@2026-01-09\nγ≔short\n⟦Λ:Funcs⟧{f≜λx.x⊗x}\n⟦Ε⟧⟨δ≜0.8;τ≜◊⁺⁺⟩";
+
+/// `STANDARD_OUTPUT` with the blocks of the Full tier
+const FULL_OUTPUT: &str = "𝔸5.1.short@2026-01-09\nγ≔short\n⟦Ω:Meta⟧{⊤}\n⟦Σ:Types⟧{T≜ℕ}\n⟦Γ:Rules⟧{∀x∈T:x⇒x}\n⟦Λ:Funcs⟧{f≜λx.x⊗x}\n⟦Ε⟧⟨δ≜0.8;τ≜◊⁺⁺⟩";
+
+/// `BROKEN_OUTPUT` after fixing the header and brackets
+const AGENT_OUTPUT: &str =
+    "𝔸5.1.agent@2026-01-09\nγ≔agent\n⟦Λ:Funcs⟧{f≜λx.∀y∈S:y⇒x}\n⟦Ε⟧⟨δ≜0.9;τ≜◊⁺⁺⟩";
+
+/// Valid Standard document for `LOW_CONFIDENCE_CASES[1]`
+const NETWORKS_OUTPUT: &str = "𝔸5.1.nn@2026-01-09\n⟦Σ:Types⟧{Networks≜Neural⟨⟩;Functions≜Continuous⟨⟩}\n⟦Γ:Rules⟧{∀f∈Functions:∃n∈Networks:⊢approximate(n,f)}\n⟦Λ:Funcs⟧{arbitrary≜λf.⊤}\n⟦Ε⟧⟨δ≜0.5;τ≜◊⟩";
+
+/// Valid JSON document
+const JSON_OUTPUT: &str = r#"{
+  "header": {"version": "5.1", "name": "json", "date": "2026-01-09"},
+  "context": "json",
+  "blocks": [{"category": "Λ", "name": "Funcs", "statements": ["f≜λx.∀y∈S:y⇒x"]}],
+  "evidence": [{"name": "δ", "value": "0.9"}, {"name": "τ", "value": "◊⁺⁺"}]
+}"#;
+
+/// Provider that answers from a script instead of calling an LLM
+///
+/// Plain conversions take the scripted outputs in turn, repeating the last
+/// one, unless the model chosen with `for_model` has an output of its own.
+/// JSON-mode and agentic conversions are only supported when scripted.
+#[derive(Clone, Default)]
+struct ScriptedProvider {
+    outputs: Vec<String>,
+    /// Output of JSON-mode conversions
+    structured: Option<String>,
+    /// Drafts of agentic conversions, checked with the validation tool in
+    /// turn until one is accepted
+    drafts: Vec<String>,
+    /// Outputs of particular models; `for_model` is only supported if set
+    models: Vec<(String, String)>,
+    /// Model chosen with `for_model`
+    model: Option<String>,
+    /// Wait before every answer
+    delay: Duration,
+    tokens_used: Option<usize>,
+    cost_usd: Option<f64>,
+    /// Plain conversions answered, shared with the `for_model` providers
+    calls: Arc<AtomicUsize>,
+    /// Validation reports of the agentic drafts
+    reports: Arc<Mutex<Vec<String>>>,
+}
+
+impl ScriptedProvider {
+    fn answering(output: &str) -> Self {
+        Self {
+            outputs: vec![output.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// Answer the next plain conversion with `output`
+    fn then(mut self, output: &str) -> Self {
+        self.outputs.push(output.to_string());
+        self
+    }
+
+    fn structured(mut self, output: &str) -> Self {
+        self.structured = Some(output.to_string());
+        self
+    }
+
+    fn drafts(mut self, drafts: &[&str]) -> Self {
+        self.drafts = drafts.iter().map(|d| d.to_string()).collect();
+        self
+    }
+
+    fn model(mut self, model: &str, output: &str) -> Self {
+        self.models.push((model.to_string(), output.to_string()));
+        self
+    }
+
+    fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn usage(mut self, tokens_used: usize, cost_usd: f64) -> Self {
+        self.tokens_used = Some(tokens_used);
+        self.cost_usd = Some(cost_usd);
+        self
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn reports(&self) -> Vec<String> {
+        self.reports.lock().unwrap().clone()
+    }
+
+    async fn answer(&self, output: &str) -> LlmResult {
+        tokio::time::sleep(self.delay).await;
+        LlmResult {
+            output: output.to_string(),
+            provider: "scripted".to_string(),
+            model: self.model.clone().unwrap_or_else(|| "scripted".to_string()),
+            tokens_used: self.tokens_used,
+            cost_usd: self.cost_usd,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    async fn convert(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        let model = self
+            .models
+            .iter()
+            .find(|(model, _)| Some(model) == self.model.as_ref());
+        let output = match model {
+            Some((_, output)) => output,
+            None => &self.outputs[call.min(self.outputs.len() - 1)],
+        };
+        Ok(self.answer(output).await)
+    }
+
+    async fn convert_structured(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+    ) -> Result<Option<LlmResult>> {
+        match &self.structured {
+            Some(output) => Ok(Some(self.answer(output).await)),
+            None => Ok(None),
+        }
+    }
+
+    async fn convert_agentic(
+        &self,
+        _prose: &str,
+        _tier: ConversionTier,
+        _unmapped: &[String],
+        _partial_output: Option<&str>,
+        _use_aisp_prompt: bool,
+        tools: &ValidationTools,
+    ) -> Result<Option<LlmResult>> {
+        for draft in &self.drafts {
+            let report = tools.call(VALIDATE_AISP, &json!({ "aisp": draft }))?;
+            let accepted = report.starts_with("accepted");
+            self.reports.lock().unwrap().push(report);
+            if accepted || Some(draft) == self.drafts.last() {
+                return Ok(Some(self.answer(draft).await));
+            }
+        }
+        Ok(None)
+    }
+
+    fn for_model(&self, model: &str) -> Option<Box<dyn LlmProvider>> {
+        if self.models.is_empty() {
+            return None;
+        }
+        Some(Box::new(Self {
+            model: Some(model.to_string()),
+            ..self.clone()
+        }))
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+}
+
 #[tokio::test]
 async fn test_llm_provider_availability() {
     let provider = ClaudeFallback::new();
@@ -140,6 +386,9 @@ async fn test_tier_preserved_with_fallback() {
             confidence_threshold: Some(0.99), // Force fallback
             llm_model: Some("haiku".to_string()),
             use_aisp_prompt: false,
+            // Otherwise shorter answers are reported with their own tier
+            tier_check: TierCheck::Off,
+            ..Default::default()
         };
 
         let result = convert_with_fallback(prose, Some(options)).await;
@@ -153,6 +402,785 @@ async fn test_tier_preserved_with_fallback() {
     }
 }
 
+#[tokio::test]
+async fn test_convert_with_provider_full_mode() {
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+
+    let result = convert_with_provider(LOW_CONFIDENCE_CASES[0], Some(options), &StubProvider).await;
+
+    assert!(result.used_fallback);
+    assert_eq!(result.output, STUB_OUTPUT);
+}
+
+#[tokio::test]
+async fn test_alignment() {
+    let options = ConversionOptionsExt {
+        align: true,
+        ..Default::default()
+    };
+    let prose = "Define x as 5";
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &StubProvider).await;
+    let alignment = ext.alignment.expect("alignment was requested");
+    assert_eq!(alignment.statements.len(), 1);
+    assert_eq!(alignment.statements[0].origin, AlignmentOrigin::Rosetta);
+    assert_eq!(alignment.statements[0].prose[0].text(prose), prose);
+
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..options
+    };
+    let prose = LOW_CONFIDENCE_CASES[0];
+    let ext = convert_with_provider_ext(prose, Some(options), &StubProvider).await;
+    let alignment = ext.alignment.expect("alignment was requested");
+    let statement = &alignment.statements[0];
+    assert_eq!(statement.origin, AlignmentOrigin::Llm);
+    assert_eq!(statement.block.as_deref(), Some("Λ:Funcs"));
+    assert_eq!(statement.prose[0].text(prose), "The");
+    assert_eq!(alignment.blocks[0].prose, statement.prose);
+}
+
+#[tokio::test]
+async fn test_requirement_tracing() {
+    let prose = "REQ-1: Define x as 5.\nREQ-2: Define y as 10.";
+    let options = ConversionOptionsExt {
+        trace_requirements: true,
+        ..Default::default()
+    };
+
+    let ext = convert_with_provider_ext(prose, Some(options), &StubProvider).await;
+    let output = &ext.result.output;
+    assert!(!output.contains("REQ-1:"), "Labels should not be converted");
+    assert!(output.contains(";; REQ-1") && output.contains(";; REQ-2"));
+
+    let coverage = ext.requirements.expect("the prose has requirements");
+    assert_eq!(coverage.requirements.len(), 2);
+    assert!(coverage.is_complete());
+
+    let ext = convert_with_provider_ext(prose, None, &StubProvider).await;
+    assert!(ext.requirements.is_none());
+    assert!(ext.result.output.contains("REQ-1:"));
+}
+
+#[tokio::test]
+async fn test_ambiguity_triggers_fallback() {
+    let prose = "Every user has a role";
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        confidence_threshold: Some(0.0), // Never below threshold
+        ..Default::default()
+    };
+
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &StubProvider).await;
+    assert!(!ext.result.used_fallback);
+    let report = ext.ambiguity.expect("ambiguity is always estimated");
+    assert_eq!(report.prose.score, 0.5);
+
+    let options = ConversionOptionsExt {
+        ambiguity_threshold: Some(0.4),
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options), &StubProvider).await;
+    assert!(
+        ext.result.used_fallback,
+        "Ambiguous prose should use the LLM"
+    );
+    assert_eq!(ext.ambiguity.unwrap().output.score, 0.0);
+}
+
+/// Provider that asks about a term the deterministic analysis cannot see
+struct AskingProvider;
+
+#[async_trait]
+impl LlmProvider for AskingProvider {
+    async fn convert(
+        &self,
+        prose: &str,
+        tier: ConversionTier,
+        unmapped: &[String],
+        partial_output: Option<&str>,
+        use_aisp_prompt: bool,
+    ) -> Result<LlmResult> {
+        StubProvider
+            .convert(prose, tier, unmapped, partial_output, use_aisp_prompt)
+            .await
+    }
+
+    async fn clarify(&self, _prose: &str) -> Result<Vec<ClarificationQuestion>> {
+        Ok(vec![ClarificationQuestion {
+            kind: None,
+            text: "recent".to_string(),
+            span: None,
+            question: "How recent is recent?".to_string(),
+            interpretations: vec!["within a day".to_string(), "within a week".to_string()],
+        }])
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_clarification_round_trip() {
+    let clear = "Define x as 5";
+    let outcome = convert_or_clarify_with_provider(clear, None, &AskingProvider).await;
+    assert!(matches!(outcome, ConversionOutcome::Converted(_)));
+
+    let prose = "Every user has a role. Log recent logins.";
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        ..Default::default()
+    };
+    let outcome =
+        convert_or_clarify_with_provider(prose, Some(options.clone()), &AskingProvider).await;
+    let ConversionOutcome::NeedsClarification(request) = outcome else {
+        panic!("Ambiguous prose should ask for clarification");
+    };
+    assert_eq!(request.questions.len(), 2);
+    assert_eq!(request.questions[1].question, "How recent is recent?");
+    assert_eq!(request.questions[1].span.map(|s| s.start), Some(27));
+
+    let answers: Vec<ClarificationAnswer> = request
+        .questions
+        .iter()
+        .enumerate()
+        .map(|(question, q)| ClarificationAnswer {
+            question,
+            interpretation: q.choose(0).unwrap(),
+        })
+        .collect();
+    assert_eq!(
+        request.clarified_prose(&answers),
+        "Every user has a role (meaning a possibly different role for each user). \
+         Log recent (meaning within a day) logins."
+    );
+
+    let ext =
+        convert_clarified_with_provider(&request, &answers, Some(options), &AskingProvider).await;
+    assert!(!ext.result.output.is_empty());
+}
+
+/// Handler that answers the second question only, counting what it is asked
+struct SecondOnly {
+    asked: AtomicUsize,
+}
+
+#[async_trait]
+impl ClarificationHandler for SecondOnly {
+    async fn answer(&self, request: &ClarificationRequest, index: usize) -> Option<String> {
+        self.asked.fetch_add(1, Ordering::SeqCst);
+        (index == 1).then(|| request.questions[index].interpretations[1].clone())
+    }
+}
+
+#[tokio::test]
+async fn test_clarification_handlers() {
+    let prose = "Every user has a role. Grant read or write.";
+
+    let handler = SecondOnly {
+        asked: AtomicUsize::new(0),
+    };
+    let ext = convert_interactive_with_provider(prose, None, &StubProvider, &handler).await;
+    assert_eq!(handler.asked.load(Ordering::SeqCst), 2);
+    let prose_score = ext.ambiguity.unwrap().prose.score;
+    assert!(prose_score > 0.0, "Only one ambiguity was resolved");
+
+    let handler = FirstInterpretation::new();
+    convert_interactive_with_provider(prose, None, &StubProvider, &handler).await;
+    assert_eq!(
+        handler.log(),
+        vec![
+            "\"Every user has a role\": a possibly different role for each user",
+            "\"read or write\": read, write or both",
+        ]
+    );
+
+    let handler = FirstInterpretation::new();
+    convert_interactive_with_provider("Define x as 5", None, &StubProvider, &handler).await;
+    assert!(handler.log().is_empty());
+}
+
+/// Sessions that answer with their id and how many prompts they answered
+struct CountingSessions {
+    starts: AtomicUsize,
+    closed: Arc<AtomicUsize>,
+}
+
+struct CountingSession {
+    id: usize,
+    answered: usize,
+    closed: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl SessionFactory for CountingSessions {
+    type Session = CountingSession;
+
+    async fn start(&self) -> Result<CountingSession> {
+        Ok(CountingSession {
+            id: self.starts.fetch_add(1, Ordering::SeqCst),
+            answered: 0,
+            closed: self.closed.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl WarmSession for CountingSession {
+    async fn ask(&mut self, prompt: &str) -> Result<Reply> {
+        if prompt == "fail" {
+            anyhow::bail!("session died");
+        }
+        let delay = if prompt == "slow" { 60_000 } else { 5 };
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        self.answered += 1;
+        Ok(Reply {
+            text: format!("{}:{}", self.id, self.answered),
+            ..Default::default()
+        })
+    }
+
+    async fn close(&mut self) {
+        self.closed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_session_pool_reuses_sessions() {
+    let closed = Arc::new(AtomicUsize::new(0));
+    let pool = SessionPool::new(
+        CountingSessions {
+            starts: AtomicUsize::new(0),
+            closed: closed.clone(),
+        },
+        2,
+    );
+    assert_eq!(pool.ask("a").await.unwrap().text, "0:1");
+    assert_eq!(pool.ask("b").await.unwrap().text, "0:2");
+
+    // Concurrent requests never run more than `size` sessions
+    let answers = futures::future::join_all((0..6).map(|_| pool.ask("c"))).await;
+    assert!(answers.iter().all(|a| a.is_ok()));
+    assert_eq!(pool.stats().started, 2);
+    assert_eq!(pool.stats().reused, 6);
+
+    // A failed session is dropped and replaced
+    assert!(pool.ask("fail").await.is_err());
+    pool.warm_up().await.unwrap();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            started: 3,
+            reused: 7,
+            failed: 1,
+        }
+    );
+
+    // A cancelled request's session is shut down rather than returned
+    let cancelled = tokio::time::timeout(Duration::from_millis(20), pool.ask("slow")).await;
+    assert!(cancelled.is_err());
+    tokio::task::yield_now().await;
+    assert_eq!(pool.stats().failed, 2);
+    assert_eq!(closed.load(Ordering::SeqCst), 2);
+    assert!(pool.ask("d").await.is_ok());
+    assert_eq!(pool.stats().started, 3);
+}
+
+#[tokio::test]
+async fn test_targeted_fallback_splices_spans() {
+    // Rosetta reports unmapped words in lowercase; the output keeps the
+    // sentence-initial capital
+    let prose = "Homomorphic encryption preserves algebraic structure";
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        fallback_mode: FallbackMode::Targeted,
+        ..Default::default()
+    };
+
+    let deterministic = AispConverter::convert(prose, None);
+    assert!(deterministic.output.contains("Homomorphic"));
+    let result = convert_with_provider(prose, Some(options), &StubProvider).await;
+
+    assert!(result.used_fallback);
+    assert_ne!(
+        result.output, STUB_OUTPUT,
+        "Targeted mode should not fall back"
+    );
+    assert!(
+        result.output.contains("⊗"),
+        "Spans should be spliced in: {}",
+        result.output
+    );
+    assert!(!result.output.contains("Homomorphic"));
+    assert!(result.unmapped.is_empty());
+}
+
+#[tokio::test]
+async fn test_select_best_records_both_scores() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        select_best: true,
+        ..Default::default()
+    };
+    let base = ConversionOptions {
+        tier: options.tier,
+        confidence_threshold: None,
+    };
+    let deterministic = AispConverter::convert(prose, Some(base));
+
+    // A valid document covering the prose beats the deterministic output
+    let provider = ScriptedProvider::answering(NETWORKS_OUTPUT);
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+    let selection = ext.selection.expect("Selection should be recorded");
+    assert_eq!(selection.chosen, Candidate::Llm);
+    assert!(selection.llm.total > selection.deterministic.total);
+    assert_eq!(ext.result.output, NETWORKS_OUTPUT);
+
+    // Unvalidated prose scores below it
+    let unvalidated = ConversionOptionsExt {
+        validate_llm_output: false,
+        ..options
+    };
+    let provider = ScriptedProvider::answering(PROSE_OUTPUT);
+    let ext = convert_with_provider_ext(prose, Some(unvalidated), &provider).await;
+    let selection = ext.selection.expect("Selection should be recorded");
+    assert_eq!(selection.chosen, Candidate::Deterministic);
+    assert!(selection.deterministic.total >= selection.llm.total);
+    assert_eq!(ext.result.output, deterministic.output);
+}
+
+#[tokio::test]
+async fn test_multi_sample_ranking() {
+    for parallel_samples in [false, true] {
+        let options = ConversionOptionsExt {
+            enable_llm_fallback: true,
+            confidence_threshold: Some(1.01), // Always below threshold
+            samples: 3,
+            parallel_samples,
+            ..Default::default()
+        };
+
+        let ext =
+            convert_with_provider_ext(LOW_CONFIDENCE_CASES[0], Some(options), &StubProvider).await;
+        let report = ext.samples.expect("Sample report should be recorded");
+
+        assert_eq!(report.scores.len(), 3);
+        assert_eq!(report.chosen, 0, "Identical samples should keep the first");
+        assert_eq!(report.self_consistency, 1.0);
+        assert_eq!(ext.result.output, STUB_OUTPUT);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_llm_output_rejected() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let deterministic = AispConverter::convert(prose, None);
+
+    let options = ConversionOptionsExt {
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+    let provider = ScriptedProvider::answering(PROSE_OUTPUT);
+    let result = convert_with_provider(prose, Some(options.clone()), &provider).await;
+
+    assert!(!result.used_fallback, "Prose output should fail validation");
+    assert_eq!(result.output, deterministic.output);
+
+    let options = ConversionOptionsExt {
+        validate_llm_output: false,
+        ..options
+    };
+    let result = convert_with_provider(prose, Some(options), &provider).await;
+
+    assert!(result.used_fallback);
+    assert!(result.output.starts_with("Sure!"));
+}
+
+#[tokio::test]
+async fn test_low_density_llm_output_accepted() {
+    let prose = "Every user with a valid login must eventually get a session that persists";
+    let output = "𝔸5.1.users@2026-01-09\nγ≔users\n\
+                  ⟦Λ:Funcs⟧{ ∀u∈Users:valid(u)⇒∃s:Session(u,s) }\n⟦Ε⟧⟨δ≜0.70;τ≜◊⁺⟩";
+    assert!(validate(output).density < 0.20);
+
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+    let provider = ScriptedProvider::answering(output);
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+
+    assert!(
+        ext.result.used_fallback,
+        "Well-formed output should be used"
+    );
+    assert_eq!(ext.result.output, output);
+}
+
+#[tokio::test]
+async fn test_repair_llm_output() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+    let provider = ScriptedProvider::answering(BROKEN_OUTPUT);
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+
+    assert!(
+        !ext.result.used_fallback,
+        "Broken output should fail validation"
+    );
+    assert!(ext.repairs.is_empty());
+
+    let options = ConversionOptionsExt {
+        repair: true,
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+
+    assert!(
+        ext.result.used_fallback,
+        "Repaired output should be accepted"
+    );
+    let kinds: Vec<RepairKind> = ext.repairs.iter().map(|r| r.kind).collect();
+    assert!(kinds.contains(&RepairKind::Brackets));
+    assert!(kinds.contains(&RepairKind::Header));
+    assert!(validate(&ext.result.output).valid);
+}
+
+#[tokio::test]
+async fn test_json_mode() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        json_mode: true,
+        ..Default::default()
+    };
+    let provider = ScriptedProvider::answering(BROKEN_OUTPUT).structured(JSON_OUTPUT);
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+
+    assert!(
+        ext.result.used_fallback,
+        "Rendered document should be accepted"
+    );
+    assert!(ext
+        .result
+        .output
+        .starts_with("𝔸5.1.json@2026-01-09\nγ≔json\n"));
+    assert!(ext.result.output.ends_with("⟦Ε⟧⟨δ≜0.9;τ≜◊⁺⁺⟩\n"));
+    assert!(validate(&ext.result.output).valid);
+
+    // Providers without JSON output take the free-text path
+    let minimal = ConversionOptionsExt {
+        tier: Some(ConversionTier::Minimal),
+        ..options.clone()
+    };
+    let ext = convert_with_provider_ext(prose, Some(minimal), &StubProvider).await;
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+
+    let options = ConversionOptionsExt {
+        json_mode: false,
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+    assert!(!ext.result.used_fallback, "Free text is broken");
+}
+
+#[tokio::test]
+async fn test_agentic_mode() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let provider =
+        ScriptedProvider::answering(BROKEN_OUTPUT).drafts(&[BROKEN_OUTPUT, AGENT_OUTPUT]);
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        agentic: true,
+        ..Default::default()
+    };
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+
+    assert!(
+        ext.result.used_fallback,
+        "Checked output should be accepted"
+    );
+    assert_eq!(ext.result.output, AGENT_OUTPUT);
+    let reports = provider.reports();
+    assert!(reports[0].starts_with("rejected for the standard tier"));
+    assert!(reports[1].starts_with("accepted for the standard tier"));
+
+    // Without agentic mode the broken single-turn output is discarded
+    let options = ConversionOptionsExt {
+        agentic: false,
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+    assert!(!ext.result.used_fallback);
+}
+
+#[tokio::test]
+async fn test_speculative_fallback() {
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Minimal),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(0.8),
+        speculative: true,
+        ..Default::default()
+    };
+    let ext = convert_with_provider_ext(
+        LOW_CONFIDENCE_CASES[1],
+        Some(options.clone()),
+        &StubProvider,
+    )
+    .await;
+    let speculation = ext
+        .speculation
+        .expect("Speculative conversions are reported");
+    assert_eq!(speculation.outcome, SpeculationOutcome::Used);
+    assert!(speculation.predicted_confidence < 0.8);
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+
+    // An early request whose output is rejected leaves the deterministic result
+    let provider = ScriptedProvider::answering(PROSE_OUTPUT);
+    let ext =
+        convert_with_provider_ext(LOW_CONFIDENCE_CASES[1], Some(options.clone()), &provider).await;
+    assert_eq!(ext.speculation.unwrap().outcome, SpeculationOutcome::Failed);
+    assert!(!ext.result.used_fallback);
+
+    let confident = ConversionOptionsExt {
+        confidence_threshold: Some(0.0),
+        ..options.clone()
+    };
+    let ext =
+        convert_with_provider_ext(LOW_CONFIDENCE_CASES[1], Some(confident), &StubProvider).await;
+    assert_eq!(
+        ext.speculation.unwrap().outcome,
+        SpeculationOutcome::Skipped
+    );
+    assert!(!ext.result.used_fallback);
+
+    // The prediction misses "there exists", so the request starts early and
+    // is cancelled once the deterministic result clears the threshold
+    let prose = "There exists a unique element x in set S such that for all y in S, \
+                 x is less than or equal to y";
+    let base = ConversionOptions {
+        tier: options.tier,
+        confidence_threshold: None,
+    };
+    let deterministic = AispConverter::convert(prose, Some(base));
+    assert!(predict_confidence(prose) < deterministic.confidence);
+    let cancelled = ConversionOptionsExt {
+        confidence_threshold: Some(deterministic.confidence),
+        ..options
+    };
+    let slow = ScriptedProvider::answering(STUB_OUTPUT).delayed(Duration::from_secs(60));
+    let ext = tokio::time::timeout(
+        Duration::from_secs(10),
+        convert_with_provider_ext(prose, Some(cancelled), &slow),
+    )
+    .await
+    .expect("The early request should be cancelled");
+    assert_eq!(
+        ext.speculation.unwrap().outcome,
+        SpeculationOutcome::Cancelled
+    );
+    assert_eq!(ext.result.output, deterministic.output);
+}
+
+#[tokio::test]
+async fn test_hedged_requests() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Minimal),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+    let delay = Duration::from_millis(20);
+    let slow = || ScriptedProvider::answering(STUB_OUTPUT).delayed(Duration::from_secs(60));
+
+    // The hedge answers while the slow primary is still running
+    let hedged = HedgedProvider::new(slow(), StubProvider, delay);
+    let ext = tokio::time::timeout(
+        Duration::from_secs(10),
+        convert_with_provider_ext(prose, Some(options.clone()), &hedged),
+    )
+    .await
+    .expect("The slow primary should be cancelled");
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+
+    // Invalid output from the primary is hedged without waiting
+    let broken = HedgedProvider::new(
+        ScriptedProvider::answering(BROKEN_OUTPUT),
+        StubProvider,
+        Duration::from_secs(60),
+    );
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &broken).await;
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+
+    let fast = HedgedProvider::new(StubProvider, slow(), delay);
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &fast).await;
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+
+    let expected = |hedged, primary_wins, hedge_wins| HedgeStats {
+        requests: 1,
+        hedged,
+        primary_wins,
+        hedge_wins,
+        failed: 0,
+    };
+    assert_eq!(hedged.stats(), expected(1, 0, 1));
+    assert_eq!(broken.stats(), expected(1, 0, 1));
+    assert_eq!(fast.stats(), expected(0, 1, 0));
+}
+
+#[tokio::test]
+async fn test_model_ladder() {
+    let prose = LOW_CONFIDENCE_CASES[1];
+    let provider = ScriptedProvider::answering(STANDARD_OUTPUT)
+        .model("weak", BROKEN_OUTPUT)
+        .usage(100, 0.25);
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        model_ladder: vec!["weak".to_string(), "strong".to_string()],
+        ..Default::default()
+    };
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+
+    assert_eq!(ext.result.output, STANDARD_OUTPUT);
+    let models: Vec<&str> = ext.ladder.iter().map(|r| r.model.as_str()).collect();
+    assert_eq!(models, ["weak", "strong"]);
+    assert_eq!(ext.ladder[0].escalation, Some(Escalation::Rejected));
+    assert_eq!(ext.ladder[0].tokens_used, Some(100));
+    assert_eq!(ext.ladder[0].cost_usd, Some(0.25));
+    assert_eq!(ext.ladder[1].escalation, None);
+    assert!(ext.ladder[1].quality.is_some());
+
+    // Full-tier prose starts one rung up
+    let full = ConversionOptionsExt {
+        tier: Some(ConversionTier::Full),
+        ..options.clone()
+    };
+    let ext = convert_with_provider_ext(prose, Some(full), &provider).await;
+    assert_eq!(ext.ladder[0].escalation, Some(Escalation::FullTier));
+    assert_eq!(ext.ladder[0].requests, 0);
+    assert_eq!(ext.ladder[1].requests, 1);
+
+    // Nothing clears the bar, so the best accepted output is kept
+    let strict = ConversionOptionsExt {
+        model_ladder: vec!["strong".to_string(), "stronger".to_string()],
+        escalation_quality: Some(1.01),
+        ..options.clone()
+    };
+    let ext = convert_with_provider_ext(prose, Some(strict), &provider).await;
+    assert!(ext.result.used_fallback);
+    assert_eq!(ext.ladder[0].escalation, Some(Escalation::LowQuality));
+    assert_eq!(ext.ladder[1].escalation, None);
+
+    // Providers with a fixed model ignore the ladder
+    let minimal = ConversionOptionsExt {
+        tier: Some(ConversionTier::Minimal),
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(minimal), &StubProvider).await;
+    assert_eq!(ext.result.output, STUB_OUTPUT);
+    assert!(ext.ladder.is_empty());
+}
+
+#[tokio::test]
+async fn test_tier_check() {
+    let prose = LOW_CONFIDENCE_CASES[2];
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Full),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        tier_check: TierCheck::Off,
+        ..Default::default()
+    };
+    // Only the reprompt of the third conversion returns the Full tier
+    let provider = ScriptedProvider::answering(STANDARD_OUTPUT)
+        .then(STANDARD_OUTPUT)
+        .then(STANDARD_OUTPUT)
+        .then(FULL_OUTPUT);
+
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+    assert!(ext.result.used_fallback);
+    assert_eq!(
+        ext.result.tier,
+        ConversionTier::Full,
+        "Unchecked tier is echoed"
+    );
+    assert_eq!(ext.requested_tier, None);
+
+    let options = ConversionOptionsExt {
+        tier_check: TierCheck::Report,
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options.clone()), &provider).await;
+    assert_eq!(ext.result.tier, ConversionTier::Standard);
+    assert_eq!(ext.requested_tier, Some(ConversionTier::Full));
+    assert_eq!(provider.calls(), 2);
+
+    let options = ConversionOptionsExt {
+        tier_check: TierCheck::Reprompt,
+        ..options
+    };
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+    assert_eq!(ext.result.tier, ConversionTier::Full);
+    assert_eq!(ext.requested_tier, None);
+    assert!(ext.result.output.contains("⟦Σ:Types⟧"));
+    assert_eq!(provider.calls(), 4);
+
+    // A one-liner answering a Full request is reported as Minimal, not discarded
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Full),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(1.01), // Always below threshold
+        ..Default::default()
+    };
+    let provider = ScriptedProvider::answering("∀x∈S:enc(x)⇒⊤");
+    let ext = convert_with_provider_ext(prose, Some(options), &provider).await;
+    assert!(ext.result.used_fallback);
+    assert_eq!(ext.result.output, "∀x∈S:enc(x)⇒⊤");
+    assert_eq!(ext.result.tier, ConversionTier::Minimal);
+    assert_eq!(ext.requested_tier, Some(ConversionTier::Full));
+}
+
+#[tokio::test]
+async fn test_evidence_rewrite() {
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Standard),
+        evidence_mode: EvidenceMode::Rewrite,
+        ..Default::default()
+    };
+
+    let ext =
+        convert_with_provider_ext(HIGH_CONFIDENCE_CASES[1], Some(options), &StubProvider).await;
+
+    assert!(ext.evidence.is_some(), "Evidence report should be recorded");
+    assert!(ext.result.output.contains("⟦Ε⟧"));
+    assert!(verify_evidence(&ext.result.output).is_accurate());
+}
+
 #[tokio::test]
 async fn test_deterministic_fallback_consistency() {
     // Even without LLM, the fallback path should be consistent
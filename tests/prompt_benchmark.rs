@@ -9,9 +9,10 @@
 //! Measures speed and accuracy for each combination.
 
 use rosetta_aisp_llm::{
-    convert_with_fallback, ClaudeFallback, ConversionOptionsExt, ConversionTier, LlmProvider,
+    convert_with_fallback, convert_with_fallback_ext, convert_with_provider, ClaudeFallback,
+    ConversionOptionsExt, ConversionTier, LlmProvider,
 };
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 /// Test cases that require LLM fallback (low deterministic confidence)
 const TEST_CASES: &[(&str, &[&str])] = &[
@@ -72,6 +73,7 @@ const CHALLENGING_CASES: &[(&str, &[&str])] = &[
 
 /// Result of a single benchmark run
 #[derive(Debug, Clone)]
+#[allow(dead_code)] // Not every field is printed, but all are useful in `{:?}` dumps
 struct BenchmarkResult {
     model: String,
     prompt_style: String,
@@ -97,6 +99,7 @@ async fn run_benchmark(
         confidence_threshold: Some(0.99), // Force fallback
         llm_model: Some(model.to_string()),
         use_aisp_prompt,
+        ..Default::default()
     };
 
     let start = Instant::now();
@@ -484,3 +487,109 @@ async fn benchmark_challenging_all_quadrants() {
         println!("Best combination for challenging cases: {}", name);
     }
 }
+
+#[tokio::test]
+async fn benchmark_challenging_multi_sample() {
+    let provider = ClaudeFallback::new();
+    if !provider.is_available().await {
+        eprintln!("Skipping multi-sample benchmark: Claude CLI not available");
+        return;
+    }
+
+    println!("\n=== CHALLENGING BENCHMARK: haiku + aisp, 3 parallel samples ===\n");
+
+    let mut total_accuracy = 0.0;
+    for (prose, expected) in CHALLENGING_CASES {
+        let options = ConversionOptionsExt {
+            tier: Some(ConversionTier::Minimal),
+            enable_llm_fallback: true,
+            confidence_threshold: Some(0.99), // Force fallback
+            llm_model: Some("haiku".to_string()),
+            samples: 3,
+            parallel_samples: true,
+            ..Default::default()
+        };
+
+        let start = Instant::now();
+        let ext = convert_with_fallback_ext(prose, Some(options)).await;
+        let duration = start.elapsed();
+
+        let found = expected
+            .iter()
+            .filter(|s| ext.result.output.contains(*s))
+            .count();
+        let accuracy = found as f64 / expected.len() as f64;
+        total_accuracy += accuracy;
+
+        let consistency = ext.samples.map(|r| r.self_consistency).unwrap_or(1.0);
+        println!(
+            "  [{}ms] {:.1}% acc | {:.1}% consistency | {}",
+            duration.as_millis(),
+            accuracy * 100.0,
+            consistency * 100.0,
+            prose.chars().take(40).collect::<String>()
+        );
+    }
+
+    println!(
+        "  Avg accuracy: {:.1}%",
+        total_accuracy / CHALLENGING_CASES.len() as f64 * 100.0
+    );
+}
+
+/// Time the basic cases through `provider`, returning the duration of each
+async fn time_cases(provider: &ClaudeFallback) -> Vec<u128> {
+    let options = ConversionOptionsExt {
+        tier: Some(ConversionTier::Minimal),
+        enable_llm_fallback: true,
+        confidence_threshold: Some(0.99), // Force fallback
+        ..Default::default()
+    };
+
+    let mut durations = Vec::new();
+    for (prose, _) in TEST_CASES {
+        let start = Instant::now();
+        convert_with_provider(prose, Some(options.clone()), provider).await;
+        durations.push(start.elapsed().as_millis());
+    }
+    durations
+}
+
+#[tokio::test]
+async fn benchmark_warm_pool() {
+    let cold = ClaudeFallback::haiku();
+    if !cold.is_available().await {
+        eprintln!("Skipping: Claude CLI not available");
+        return;
+    }
+
+    println!("\n=== WARM SESSION POOL (haiku + aisp) ===\n");
+
+    let cold_times = time_cases(&cold).await;
+
+    // Start-up is paid before the first conversion rather than during it
+    let warm = ClaudeFallback::haiku().with_pool(1);
+    let start = Instant::now();
+    if let Err(e) = warm.warm_up(true).await {
+        eprintln!("Skipping: could not start a session: {}", e);
+        return;
+    }
+    let warm_up_ms = start.elapsed().as_millis();
+    let warm_times = time_cases(&warm).await;
+    let stats = warm.pool_stats().unwrap_or_default();
+    warm.shutdown().await;
+
+    println!("| Case                                     | Cold     | Warm     |");
+    println!("|------------------------------------------|----------|----------|");
+    for (((prose, _), cold_ms), warm_ms) in TEST_CASES.iter().zip(&cold_times).zip(&warm_times) {
+        let case: String = prose.chars().take(40).collect();
+        println!("| {:<40} | {:>6}ms | {:>6}ms |", case, cold_ms, warm_ms);
+    }
+
+    let cold_avg = cold_times.iter().sum::<u128>() / cold_times.len() as u128;
+    let warm_avg = warm_times.iter().sum::<u128>() / warm_times.len() as u128;
+    println!(
+        "\nAvg cold: {}ms | Avg warm: {}ms | Warm-up: {}ms | Sessions started: {}, reused: {}",
+        cold_avg, warm_avg, warm_up_ms, stats.started, stats.reused
+    );
+}